@@ -0,0 +1,54 @@
+//! Instruction counts of the core functions, which, unlike the wall-clock
+//! times of `perf`, are deterministic, so that regressions can be caught on
+//! noisy machines, e.g., in CI. Requires Valgrind.
+//!
+//! The keys are fixed, as the number of iterations of the loops of Flip Hash
+//! depends on them.
+
+use flip_hash::{
+    flip_hash_64, flip_hash_64_with_seed, flip_hash_xxh3_128, flip_hash_xxh3_64, FixedFlipRange,
+};
+use flip_hash_benchmarks::jump_hash;
+use iai::black_box;
+
+const KEY: u64 = 0x9e37_79b9_7f4a_7c15;
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+const BYTES: [u8; 128] = [0xa5; 128];
+
+fn flip_hash_64_small_range() -> u64 {
+    flip_hash_64(black_box(KEY), black_box(..=10))
+}
+
+fn flip_hash_64_large_range() -> u64 {
+    flip_hash_64(black_box(KEY), black_box(..=10000000))
+}
+
+fn flip_hash_64_with_seed_large_range() -> u64 {
+    flip_hash_64_with_seed(black_box(KEY), black_box(SEED), black_box(..=10000000))
+}
+
+fn flip_hash_64_fixed_large_range() -> u64 {
+    FixedFlipRange::<10000000>::hash(black_box(KEY))
+}
+
+fn flip_hash_xxh3_64_large_range() -> u64 {
+    flip_hash_xxh3_64(black_box(&BYTES), black_box(..=10000000))
+}
+
+fn flip_hash_xxh3_128_large_range() -> u128 {
+    flip_hash_xxh3_128(black_box(&BYTES), black_box(..=10000000))
+}
+
+fn jump_hash_large_range() -> u32 {
+    jump_hash(black_box(KEY), black_box(..=10000000))
+}
+
+iai::main!(
+    flip_hash_64_small_range,
+    flip_hash_64_large_range,
+    flip_hash_64_with_seed_large_range,
+    flip_hash_64_fixed_large_range,
+    flip_hash_xxh3_64_large_range,
+    flip_hash_xxh3_128_large_range,
+    jump_hash_large_range
+);
@@ -1,12 +1,27 @@
-use std::{hint::black_box, time::Duration};
+use std::{hint::black_box, ops::RangeToInclusive, time::Duration};
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
-use flip_hash::{flip_hash_64, flip_hash_xxh3_64};
-use flip_hash_benchmarks::jump_hash;
-use rand::{thread_rng, RngCore};
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion,
+    SamplingMode, Throughput,
+};
+use flip_hash::{
+    compat::{guava_consistent_hash, KetamaRing},
+    flip_hash_64, flip_hash_64_many, flip_hash_64_multi_seed, flip_hash_64_with_seed,
+    flip_hash_aes_64, flip_hash_keyed_64, flip_hash_murmur2_64, flip_hash_xxh3_128,
+    flip_hash_xxh3_128_with_seed, flip_hash_xxh3_64, flip_hash_xxh3_64_with_seed, FixedFlipRange,
+};
+use flip_hash_benchmarks::{
+    jump_hash, jump_hash_64_with_seed, rendezvous_hash, weighted_rendezvous_hash, AnchorHash,
+    DxHash, Maglev,
+};
+use rand::{thread_rng, Rng, RngCore};
 use xxhash_rust::xxh3;
 
 const RANGE_ENDS: [u64; 4] = [10, 1000, 100000, 10000000];
+/// The lengths of the keys of `HashBytesByLength`, from 4 bytes to 64 KiB.
+const KEY_LENGTHS: [usize; 8] = [4, 16, 64, 256, 1024, 4096, 16384, 65536];
+/// The numbers of keys of the batches of `HashU64ManyByBatchSize`.
+const BATCH_SIZES: [usize; 3] = [1 << 10, 1 << 16, 1 << 20];
 
 fn hash_u64(c: &mut Criterion) {
     let mut group = c.benchmark_group("HashU64");
@@ -26,6 +41,34 @@ fn hash_u64(c: &mut Criterion) {
                 b.iter(|| jump_hash(black_box(key), black_box(..=range.end as u32)))
             },
         );
+        let capacity = (2 * (range_end as u32 + 1)).next_power_of_two();
+        let anchor_hash = AnchorHash::new(capacity, ..=range_end as u32);
+        group.bench_with_input(
+            BenchmarkId::new("Anchor", format!("..={}", range_end)),
+            &anchor_hash,
+            |b, anchor_hash| {
+                let key = rng.next_u64();
+                b.iter(|| anchor_hash.hash(black_box(key)))
+            },
+        );
+        let capacity = (range_end as u32 + 1).next_power_of_two();
+        let dx_hash = DxHash::new(capacity, ..=range_end as u32);
+        group.bench_with_input(
+            BenchmarkId::new("Dx", format!("..={}", range_end)),
+            &dx_hash,
+            |b, dx_hash| {
+                let key = rng.next_u64();
+                b.iter(|| dx_hash.hash(black_box(key)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Guava", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| guava_consistent_hash(black_box(key), black_box(range.end as u32 + 1)))
+            },
+        );
         group.bench_with_input(
             BenchmarkId::new("Flip", format!("..={}", range_end)),
             &..=range_end,
@@ -35,9 +78,39 @@ fn hash_u64(c: &mut Criterion) {
             },
         );
     }
+    // The tables of the largest range would not fit in memory. They are built
+    // once per range, rather than once per sample.
+    for range_end in RANGE_ENDS[..3].iter().copied() {
+        let maglev = Maglev::new(100 * (range_end as u32 + 1), 0, ..=range_end as u32);
+        group.bench_with_input(
+            BenchmarkId::new("Maglev", format!("..={}", range_end)),
+            &maglev,
+            |b, maglev| {
+                let key = rng.next_u64();
+                b.iter(|| maglev.hash(black_box(key)))
+            },
+        );
+    }
+    hash_u64_fixed_range::<{ RANGE_ENDS[0] }>(&mut group, &mut rng);
+    hash_u64_fixed_range::<{ RANGE_ENDS[1] }>(&mut group, &mut rng);
+    hash_u64_fixed_range::<{ RANGE_ENDS[2] }>(&mut group, &mut rng);
+    hash_u64_fixed_range::<{ RANGE_ENDS[3] }>(&mut group, &mut rng);
     group.finish();
 }
 
+fn hash_u64_fixed_range<const END: u64>(
+    group: &mut BenchmarkGroup<WallTime>,
+    rng: &mut impl RngCore,
+) {
+    group.bench_function(
+        BenchmarkId::new("Flip_fixed_range", format!("..={}", END)),
+        |b| {
+            let key = rng.next_u64();
+            b.iter(|| FixedFlipRange::<END>::hash(black_box(key)))
+        },
+    );
+}
+
 fn hash_bytes_with_xxh3(c: &mut Criterion) {
     let mut group = c.benchmark_group("HashBytes");
     group.sampling_mode(SamplingMode::Flat);
@@ -47,6 +120,7 @@ fn hash_bytes_with_xxh3(c: &mut Criterion) {
 
     let mut rng = thread_rng();
     let mut bytes = [0_u8; 128];
+    let secret = rng.gen::<[u8; 16]>();
     for range_end in RANGE_ENDS {
         group.bench_with_input(
             BenchmarkId::new("XXH3_then_Jump", format!("..={}", range_end)),
@@ -72,9 +146,527 @@ fn hash_bytes_with_xxh3(c: &mut Criterion) {
                 b.iter(|| flip_hash_xxh3_64(&black_box(bytes), black_box(range)))
             },
         );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip128", format!("..={}", range_end)),
+            &..=range_end as u128,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_128(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("AES_based_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_aes_64(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Murmur2_based_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_murmur2_64(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("SipHash_based_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| {
+                    flip_hash_keyed_64(&black_box(bytes), black_box(&secret), black_box(range))
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_u64_multi_seed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashU64MultiSeed");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    let mut rng = thread_rng();
+    let seeds = [rng.next_u64(), rng.next_u64(), rng.next_u64()];
+    let mut hashes = [0_u64; 3];
+    for range_end in RANGE_ENDS {
+        group.bench_with_input(
+            BenchmarkId::new("Flip_per_seed", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| {
+                    for (hash, &seed) in hashes.iter_mut().zip(&seeds) {
+                        *hash = flip_hash_64_with_seed(
+                            black_box(key),
+                            black_box(seed),
+                            black_box(range),
+                        );
+                    }
+                    black_box(hashes)
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Flip_multi_seed", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| {
+                    flip_hash_64_multi_seed(
+                        black_box(key),
+                        black_box(&seeds),
+                        black_box(range),
+                        &mut hashes,
+                    );
+                    black_box(hashes)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_with_seed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashWithSeed");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    // The unseeded functions hash with a seed of 0, which the compiler can
+    // fold, while the seeds of the seeded ones are opaque to it, as those of
+    // tenants would be.
+    let mut rng = thread_rng();
+    let seed = rng.next_u64();
+    let mut bytes = [0_u8; 128];
+    for range_end in RANGE_ENDS {
+        group.bench_with_input(
+            BenchmarkId::new("Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| flip_hash_64(black_box(key), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Flip_with_seed", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| flip_hash_64_with_seed(black_box(key), black_box(seed), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Jump_with_seed", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| jump_hash_64_with_seed(black_box(key), black_box(seed), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_64(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip_with_seed", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| {
+                    flip_hash_xxh3_64_with_seed(
+                        &black_box(bytes),
+                        black_box(seed),
+                        black_box(range),
+                    )
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip128", format!("..={}", range_end)),
+            &..=range_end as u128,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_128(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip128_with_seed", format!("..={}", range_end)),
+            &..=range_end as u128,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| {
+                    flip_hash_xxh3_128_with_seed(
+                        &black_box(bytes),
+                        black_box(seed),
+                        black_box(range),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_u64_many(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashU64Many");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    let mut rng = thread_rng();
+    let mut keys = [0_u64; 1024];
+    let mut hashes = [0_u64; 1024];
+    for range_end in RANGE_ENDS {
+        group.throughput(Throughput::Elements(keys.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("Flip_per_key", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill(&mut keys);
+                b.iter(|| {
+                    for (hash, &key) in hashes.iter_mut().zip(&keys) {
+                        *hash = flip_hash_64(black_box(key), black_box(range));
+                    }
+                    black_box(&hashes);
+                })
+            },
+        );
+        // SIMD with the `simd` feature.
+        group.bench_with_input(
+            BenchmarkId::new("Flip_many", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill(&mut keys);
+                b.iter(|| {
+                    flip_hash_64_many(black_box(&keys), black_box(range), &mut hashes);
+                    black_box(&hashes);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_u64_many_by_batch_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashU64ManyByBatchSize");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(100);
+
+    // The largest batches do not fit in the L2 cache, so that the throughput
+    // also depends on the memory bandwidth.
+    let range = ..=RANGE_ENDS[1];
+    let mut rng = thread_rng();
+    for batch_size in BATCH_SIZES {
+        let mut keys = vec![0_u64; batch_size];
+        let mut hashes = vec![0_u64; batch_size];
+        rng.fill(keys.as_mut_slice());
+        group.throughput(Throughput::Elements(batch_size as u64));
+        group.bench_function(BenchmarkId::new("Flip_per_key", batch_size), |b| {
+            b.iter(|| {
+                for (hash, &key) in hashes.iter_mut().zip(&keys) {
+                    *hash = flip_hash_64(black_box(key), black_box(range));
+                }
+                black_box(&hashes);
+            })
+        });
+        // SIMD with the `simd` feature.
+        group.bench_function(BenchmarkId::new("Flip_many", batch_size), |b| {
+            b.iter(|| {
+                flip_hash_64_many(black_box(&keys), black_box(range), &mut hashes);
+                black_box(&hashes);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn hash_long_bytes_with_xxh3(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashLongBytes");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    // XXH3 only uses SIMD instructions for inputs longer than 240 bytes.
+    let mut rng = thread_rng();
+    let mut bytes = [0_u8; 4096];
+    for range_end in RANGE_ENDS {
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_then_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_64(xxh3::xxh3_64(&black_box(bytes)), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip", format!("..={}", range_end)),
+            &..=range_end,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_64(&black_box(bytes), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip128", format!("..={}", range_end)),
+            &..=range_end as u128,
+            |b, &range| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_128(&black_box(bytes), black_box(range)))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_bytes_by_length_with_xxh3(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashBytesByLength");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    // The cost of the range reduction does not depend on the length of the
+    // key, so that a single range end shows where hashing the key dominates.
+    let range = ..=RANGE_ENDS[1];
+    let mut rng = thread_rng();
+    let mut bytes = vec![0_u8; KEY_LENGTHS[KEY_LENGTHS.len() - 1]];
+    for key_length in KEY_LENGTHS {
+        group.throughput(Throughput::Bytes(key_length as u64));
+        group.bench_with_input(
+            BenchmarkId::new("XXH3", key_length),
+            &key_length,
+            |b, &key_length| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| xxh3::xxh3_64(black_box(&bytes[..key_length])))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip", key_length),
+            &key_length,
+            |b, &key_length| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| flip_hash_xxh3_64(black_box(&bytes[..key_length]), black_box(range)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("XXH3_based_Flip128", key_length),
+            &key_length,
+            |b, &key_length| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| {
+                    flip_hash_xxh3_128(
+                        black_box(&bytes[..key_length]),
+                        black_box(..=range.end as u128),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hash_adversarial_keys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashAdversarialKeys");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    // Just above a power of two, half of the hashes to the next power of two
+    // are out of the range, so that Flip Hash draws again in a loop, while it
+    // never does just below the next one.
+    let range_ends = [1 << 20, (1 << 21) - 1];
+    // The slowest path: the hash to the next power of two is out of the range,
+    // and the loop ends with a draw below the previous power of two, after
+    // which the key is hashed below it instead.
+    let slow_path_key = (0..)
+        .find(|&key| {
+            flip_hash_64(key, ..=range_ends[1]) > range_ends[0]
+                && flip_hash_64(key, ..=range_ends[0]) < range_ends[0]
+        })
+        .unwrap();
+    let keys = [
+        ("zero", 0),
+        ("single_bit", 1 << 63),
+        ("all_ones", u64::MAX),
+        ("alternating_bits", 0xaaaa_aaaa_aaaa_aaaa),
+        ("slow_path", slow_path_key),
+    ];
+    for range_end in range_ends {
+        for (name, key) in keys {
+            group.bench_with_input(
+                BenchmarkId::new(format!("Flip_{name}_key"), format!("..={}", range_end)),
+                &..=range_end,
+                |b, &range| b.iter(|| flip_hash_64(black_box(key), black_box(range))),
+            );
+        }
+    }
+
+    let mut single_bit_bytes = [0_u8; 128];
+    single_bit_bytes[0] = 1;
+    let byte_keys = [
+        ("zero", [0_u8; 128]),
+        ("single_bit", single_bit_bytes),
+        (
+            "repeated",
+            *b"abcdefgh".repeat(16).first_chunk::<128>().unwrap(),
+        ),
+    ];
+    for range_end in range_ends {
+        for (name, bytes) in byte_keys {
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!("XXH3_based_Flip_{name}_key"),
+                    format!("..={}", range_end),
+                ),
+                &..=range_end,
+                |b, &range| b.iter(|| flip_hash_xxh3_64(&black_box(bytes), black_box(range))),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn build_maglev_table(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BuildMaglevTable");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(10);
+
+    // The tables of the largest range would not fit in memory.
+    for range_end in &RANGE_ENDS[..3] {
+        // The size of the table, as a multiple of the number of values, trades
+        // memory and build time for balance.
+        for table_size_factor in [10, 100] {
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!("Maglev_x{}", table_size_factor),
+                    format!("..={}", range_end),
+                ),
+                &..=*range_end,
+                |b, &range| {
+                    let min_table_size = table_size_factor * (range.end as u32 + 1);
+                    b.iter(|| Maglev::new(black_box(min_table_size), 0, ..=range.end as u32))
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn hash_bytes_with_ketama(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashBytesKetama");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(1000);
+
+    let mut rng = thread_rng();
+    let mut bytes = [0_u8; 128];
+    // The ring of the largest range would not fit in memory.
+    for range_end in &RANGE_ENDS[..3] {
+        // The routine is run for each sample, so that the ring, which takes
+        // seconds to build for the larger ranges, is built once beforehand.
+        let ring = ketama_ring(..=*range_end);
+        group.bench_with_input(
+            BenchmarkId::new("Ketama", format!("..={}", range_end)),
+            &ring,
+            |b, ring| {
+                rng.fill_bytes(&mut bytes);
+                b.iter(|| ring.hash(&black_box(bytes)))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn build_ketama_ring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BuildKetamaRing");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(10);
+
+    // Building the ring of 10^5 values takes seconds.
+    for range_end in &RANGE_ENDS[..2] {
+        group.bench_with_input(
+            BenchmarkId::new("Ketama", format!("..={}", range_end)),
+            &..=*range_end,
+            |b, &range| b.iter(|| ketama_ring(black_box(range))),
+        );
+    }
+    group.finish();
+}
+
+/// Returns a Ketama ring of the values of `range`, whose servers are named as
+/// in the behavioral benchmarks.
+fn ketama_ring(range: RangeToInclusive<u64>) -> KetamaRing {
+    KetamaRing::new((0..=range.end).map(|server| format!("0-{server}"))).unwrap()
+}
+
+fn hash_u64_rendezvous(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HashU64Rendezvous");
+    group.sampling_mode(SamplingMode::Flat);
+    group.warm_up_time(Duration::from_millis(300));
+    group.measurement_time(Duration::from_millis(1000));
+    group.sample_size(100);
+
+    let mut rng = thread_rng();
+
+    // Rendezvous hashing scores every value, which would take too long for the
+    // largest range.
+    for range_end in &RANGE_ENDS[..3] {
+        group.bench_with_input(
+            BenchmarkId::new("Rendezvous", format!("..={}", range_end)),
+            &..=*range_end,
+            |b, &range| {
+                let key = rng.next_u64();
+                b.iter(|| rendezvous_hash(black_box(key), black_box(..=range.end as u32)))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("WeightedRendezvous", format!("..={}", range_end)),
+            &..=*range_end,
+            |b, &range| {
+                let weights = vec![1.0; range.end as usize + 1];
+                let key = rng.next_u64();
+                b.iter(|| weighted_rendezvous_hash(black_box(key), black_box(&weights)))
+            },
+        );
     }
     group.finish();
 }
 
-criterion_group!(benches, hash_u64, hash_bytes_with_xxh3);
+criterion_group!(
+    benches,
+    hash_u64,
+    hash_bytes_with_xxh3,
+    hash_long_bytes_with_xxh3,
+    hash_bytes_by_length_with_xxh3,
+    hash_u64_multi_seed,
+    hash_with_seed,
+    hash_u64_many,
+    hash_u64_many_by_batch_size,
+    hash_adversarial_keys,
+    hash_u64_rendezvous,
+    hash_bytes_with_ketama,
+    build_maglev_table,
+    build_ketama_ring
+);
 criterion_main!(benches);
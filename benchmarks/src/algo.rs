@@ -1,14 +1,19 @@
-use std::{fmt, ops::RangeToInclusive};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    ops::RangeToInclusive,
+};
 
 use flip_hash::{
-    flip_hash_64_with_seed, flip_hash_xxh3_128_with_seed, flip_hash_xxh3_64_with_seed,
+    compat::KetamaRing, flip_hash_64_with_seed, flip_hash_xxh3_128_with_seed,
+    flip_hash_xxh3_64_with_seed,
 };
-
-use crate::jump_hash;
-
-pub(crate) trait Algorithm: fmt::Display {
-    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64;
-}
+use flip_hash_benchmarks::{
+    jump_hash_64_with_seed, rendezvous_hash, weighted_rendezvous_hash, AnchorHash as Anchors,
+    DxHash as NsArray, Maglev as MaglevTable,
+};
+use flip_hash_testkit::algo::Algorithm;
 
 #[derive(Clone, Debug)]
 pub(crate) struct FlipHash64;
@@ -53,9 +58,13 @@ impl fmt::Display for FlipHashXXH3128 {
 impl Algorithm for FlipHashXXH3128 {
     #[inline]
     fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
-        flip_hash_xxh3_128_with_seed(key, seed, ..=range.end.into())
-            .try_into()
-            .unwrap()
+        // The hash is in the range, so it fits in 64 bits.
+        self.hash_128(key, seed, ..=range.end.into()) as u64
+    }
+
+    #[inline]
+    fn hash_128(&self, key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
+        flip_hash_xxh3_128_with_seed(key, seed, range)
     }
 }
 
@@ -70,10 +79,302 @@ impl Algorithm for JumpHash {
     #[inline]
     fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
         debug_assert!(key.len() >= 8);
-        jump_hash(
+        jump_hash_64_with_seed(
+            u64::from_ne_bytes(key[..8].try_into().unwrap()),
+            seed,
+            range,
+        )
+    }
+}
+
+/// Ketama rings, which are built on first use for each seed and range. The
+/// servers of a ring are named after the seed and their indices.
+#[derive(Debug, Default)]
+pub(crate) struct Ketama {
+    rings: RefCell<HashMap<(u64, u64), KetamaRing>>,
+}
+impl Ketama {
+    fn ring(seed: u64, range: RangeToInclusive<u64>) -> KetamaRing {
+        KetamaRing::new((0..=range.end).map(|server| format!("{seed}-{server}"))).unwrap()
+    }
+}
+impl fmt::Display for Ketama {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ketama")
+    }
+}
+impl Algorithm for Ketama {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        self.rings
+            .borrow_mut()
+            .entry((seed, range.end))
+            .or_insert_with(|| Self::ring(seed, range))
+            .hash(key)
+    }
+
+    fn build(&self, seed: u64, range: RangeToInclusive<u64>) -> bool {
+        match self.rings.borrow_mut().entry((seed, range.end)) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(Self::ring(seed, range));
+                true
+            }
+        }
+    }
+}
+
+/// AnchorHash of 64-bit integer keys, which are the first 8 bytes of the keys,
+/// XORed with the seed. The buckets are built on first use for each range, and
+/// their number is the smallest power of two that is at least twice the
+/// number of values of the largest range, so that adding a value keeps the
+/// other buckets, unless the number of buckets doubles, as when provisioning
+/// more of them.
+#[derive(Debug, Default)]
+pub(crate) struct AnchorHash {
+    /// The end of the largest range that the buckets are provisioned for,
+    /// which is that of each range by default.
+    max_range_end: Option<u64>,
+    anchors: RefCell<HashMap<u64, Anchors>>,
+}
+impl AnchorHash {
+    pub(crate) fn new(max_range_end: Option<u64>) -> Self {
+        Self {
+            max_range_end,
+            anchors: RefCell::default(),
+        }
+    }
+
+    fn anchors(&self, range: RangeToInclusive<u64>) -> Anchors {
+        let range_end = u32::try_from(range.end).unwrap();
+        let max_range_end = u32::try_from(self.max_range_end.unwrap_or(range.end)).unwrap();
+        Anchors::new((2 * (max_range_end + 1)).next_power_of_two(), ..=range_end)
+    }
+}
+impl fmt::Display for AnchorHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AnchorHash")
+    }
+}
+impl Algorithm for AnchorHash {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        debug_assert!(key.len() >= 8);
+        self.anchors
+            .borrow_mut()
+            .entry(range.end)
+            .or_insert_with(|| self.anchors(range))
+            .hash(u64::from_ne_bytes(key[..8].try_into().unwrap()) ^ seed)
+            .into()
+    }
+
+    fn build(&self, _seed: u64, range: RangeToInclusive<u64>) -> bool {
+        match self.anchors.borrow_mut().entry(range.end) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(self.anchors(range));
+                true
+            }
+        }
+    }
+}
+
+/// DxHash of 64-bit integer keys, which are the first 8 bytes of the keys,
+/// XORed with the seed. The buckets are built on first use for each range, and
+/// their number is the smallest power of two that is at least the number of
+/// values of the largest range, so that adding a value keeps the other
+/// buckets, unless the number of buckets doubles.
+#[derive(Debug, Default)]
+pub(crate) struct DxHash {
+    /// The end of the largest range that the buckets are provisioned for,
+    /// which is that of each range by default.
+    max_range_end: Option<u64>,
+    ns_arrays: RefCell<HashMap<u64, NsArray>>,
+}
+impl DxHash {
+    pub(crate) fn new(max_range_end: Option<u64>) -> Self {
+        Self {
+            max_range_end,
+            ns_arrays: RefCell::default(),
+        }
+    }
+
+    fn ns_array(&self, range: RangeToInclusive<u64>) -> NsArray {
+        let range_end = u32::try_from(range.end).unwrap();
+        let max_range_end = u32::try_from(self.max_range_end.unwrap_or(range.end)).unwrap();
+        NsArray::new((max_range_end + 1).next_power_of_two(), ..=range_end)
+    }
+}
+impl fmt::Display for DxHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DxHash")
+    }
+}
+impl Algorithm for DxHash {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        debug_assert!(key.len() >= 8);
+        self.ns_arrays
+            .borrow_mut()
+            .entry(range.end)
+            .or_insert_with(|| self.ns_array(range))
+            .hash(u64::from_ne_bytes(key[..8].try_into().unwrap()) ^ seed)
+            .into()
+    }
+
+    fn build(&self, _seed: u64, range: RangeToInclusive<u64>) -> bool {
+        match self.ns_arrays.borrow_mut().entry(range.end) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(self.ns_array(range));
+                true
+            }
+        }
+    }
+}
+
+/// Maglev hashing of 64-bit integer keys, which are the first 8 bytes of the
+/// keys, with lookup tables that are built on first use for each seed and
+/// range, from permutations derived from the seed.
+#[derive(Debug)]
+pub(crate) struct Maglev {
+    /// The minimum size of the lookup tables, which is
+    /// [`Maglev::DEFAULT_TABLE_SIZE_FACTOR`] times the number of values of
+    /// the largest range by default.
+    min_table_size: Option<u32>,
+    /// The end of the largest range that the lookup tables are sized for,
+    /// which is that of each range by default.
+    max_range_end: Option<u64>,
+    tables: RefCell<HashMap<(u64, u64), MaglevTable>>,
+}
+impl Maglev {
+    /// The factor of the number of values of the default size of the lookup
+    /// tables, as recommended by the authors of Maglev to keep the loads of
+    /// the values within 1% of each other.
+    pub(crate) const DEFAULT_TABLE_SIZE_FACTOR: u32 = 100;
+
+    pub(crate) fn new(min_table_size: Option<u32>, max_range_end: Option<u64>) -> Self {
+        Self {
+            min_table_size,
+            max_range_end,
+            tables: RefCell::default(),
+        }
+    }
+
+    fn table(&self, seed: u64, range: RangeToInclusive<u64>) -> MaglevTable {
+        let range_end = u32::try_from(range.end).unwrap();
+        let min_table_size = self.min_table_size.unwrap_or_else(|| {
+            let max_range_end = u32::try_from(self.max_range_end.unwrap_or(range.end)).unwrap();
+            (max_range_end + 1)
+                .checked_mul(Self::DEFAULT_TABLE_SIZE_FACTOR)
+                .unwrap()
+        });
+        MaglevTable::new(min_table_size, seed, ..=range_end)
+    }
+}
+impl fmt::Display for Maglev {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Maglev")
+    }
+}
+impl Algorithm for Maglev {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        debug_assert!(key.len() >= 8);
+        self.tables
+            .borrow_mut()
+            .entry((seed, range.end))
+            .or_insert_with(|| self.table(seed, range))
+            .hash(u64::from_ne_bytes(key[..8].try_into().unwrap()))
+            .into()
+    }
+
+    fn build(&self, seed: u64, range: RangeToInclusive<u64>) -> bool {
+        match self.tables.borrow_mut().entry((seed, range.end)) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(self.table(seed, range));
+                true
+            }
+        }
+    }
+}
+
+/// Rendezvous hashing of 64-bit integer keys, which are the first 8 bytes of
+/// the keys, XORed with the seed.
+#[derive(Clone, Debug)]
+pub(crate) struct Rendezvous;
+impl fmt::Display for Rendezvous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rendezvous")
+    }
+}
+impl Algorithm for Rendezvous {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        debug_assert!(key.len() >= 8);
+        rendezvous_hash(
             u64::from_ne_bytes(key[..8].try_into().unwrap()) ^ seed,
             ..=u32::try_from(range.end).unwrap(),
         )
         .into()
     }
 }
+
+/// Weighted rendezvous hashing of 64-bit integer keys, which are the first 8
+/// bytes of the keys, XORed with the seed. The weights of the values are
+/// equal, so that the values are expected to be uniformly distributed, as for
+/// the other algorithms, while the cost of weighting is paid.
+#[derive(Debug, Default)]
+pub(crate) struct WeightedRendezvous {
+    weights: RefCell<Vec<f64>>,
+}
+impl fmt::Display for WeightedRendezvous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Weighted Rendezvous")
+    }
+}
+impl Algorithm for WeightedRendezvous {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        debug_assert!(key.len() >= 8);
+        let num_values = usize::try_from(range.end).unwrap() + 1;
+        let mut weights = self.weights.borrow_mut();
+        if weights.len() < num_values {
+            weights.resize(num_values, 1.0);
+        }
+        weighted_rendezvous_hash(
+            u64::from_ne_bytes(key[..8].try_into().unwrap()) ^ seed,
+            &weights[..num_values],
+        )
+        .into()
+    }
+}
+
+/// An algorithm whose seeds are XORed with another seed, e.g., to run an
+/// experiment, which hashes keys with fixed seeds, under a random one.
+#[derive(Debug)]
+pub(crate) struct Reseeded<'a, A> {
+    pub(crate) algorithm: &'a A,
+    pub(crate) seed: u64,
+}
+impl<A: fmt::Display> fmt::Display for Reseeded<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.algorithm.fmt(f)
+    }
+}
+impl<A: Algorithm> Algorithm for Reseeded<'_, A> {
+    #[inline]
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+        self.algorithm.hash(key, seed ^ self.seed, range)
+    }
+
+    #[inline]
+    fn hash_128(&self, key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
+        self.algorithm.hash_128(key, seed ^ self.seed, range)
+    }
+
+    fn build(&self, seed: u64, range: RangeToInclusive<u64>) -> bool {
+        self.algorithm.build(seed ^ self.seed, range)
+    }
+}
@@ -0,0 +1,96 @@
+//! The p-values of batches of keys, whose uniformity is tested, as test
+//! suites such as TestU01 and PractRand do, since the p-value of all the keys
+//! drifts toward 0 or 1 as soon as the tiniest bias is detectable.
+
+use std::collections::HashMap;
+
+use flip_hash_testkit::exp::kolmogorov_smirnov_p_value;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// The p-values of the batches of each algorithm.
+#[derive(Default)]
+pub(crate) struct BatchPValues {
+    p_values: HashMap<String, Vec<f64>>,
+}
+
+/// The Kolmogorov-Smirnov test of the uniformity of the p-values of the
+/// batches of an algorithm.
+#[derive(Serialize)]
+pub(crate) struct BatchSummary {
+    #[serde(rename = "num batches")]
+    num_batches: usize,
+    #[serde(rename = "batch ks statistic")]
+    ks_statistic: f64,
+    #[serde(rename = "batch ks p-value", skip_serializing_if = "Option::is_none")]
+    ks_p_value: Option<f64>,
+}
+
+impl BatchPValues {
+    /// Records the p-value of the summary of a batch of `algo`, which is
+    /// `p-value`, or the first field whose name ends with `p-value` if there
+    /// is none, e.g., `g p-value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the summary has no p-value. If it is `null`, e.g., as the
+    /// batch has too few keys, it is not recorded.
+    pub(crate) fn record(&mut self, algo: &str, summary: &impl Serialize) {
+        let Ok(Value::Object(summary)) = serde_json::to_value(summary) else {
+            panic!("The summaries are not objects");
+        };
+        let p_value = p_value(&summary).expect("The summaries have no p-value");
+        if let Some(p_value) = p_value.as_f64() {
+            self.p_values
+                .entry(algo.to_owned())
+                .or_default()
+                .push(p_value);
+        }
+    }
+
+    /// Returns the test of the p-values of the batches of `algo`.
+    pub(crate) fn summary(&self, algo: &str) -> BatchSummary {
+        let p_values = self.p_values.get(algo).cloned().unwrap_or_default();
+        let num_batches = p_values.len();
+        let (ks_statistic, ks_p_value) = uniformity_ks_test(p_values);
+        BatchSummary {
+            num_batches,
+            ks_statistic,
+            ks_p_value,
+        }
+    }
+}
+
+/// Returns the field `p-value` of a summary, or its first field whose name
+/// ends with `p-value` if there is none, e.g., `g p-value`.
+pub(crate) fn p_value(summary: &Map<String, Value>) -> Option<&Value> {
+    summary.get("p-value").or_else(|| {
+        summary
+            .iter()
+            .find(|(field, _)| field.ends_with("p-value"))
+            .map(|(_, value)| value)
+    })
+}
+
+/// Returns the Kolmogorov-Smirnov statistic of the uniformity of p-values,
+/// and its p-value, unless there are none.
+pub(crate) fn uniformity_ks_test(mut p_values: Vec<f64>) -> (f64, Option<f64>) {
+    p_values.sort_unstable_by(f64::total_cmp);
+    let num_p_values = p_values.len();
+    // The largest differences between the empirical distribution function and
+    // the uniform one are at the p-values.
+    let ks_statistic = p_values
+        .iter()
+        .enumerate()
+        .map(|(index, &p_value)| {
+            f64::max(
+                (index + 1) as f64 / num_p_values as f64 - p_value,
+                p_value - index as f64 / num_p_values as f64,
+            )
+        })
+        .fold(0.0, f64::max);
+    (
+        ks_statistic,
+        kolmogorov_smirnov_p_value(ks_statistic, num_p_values as u64),
+    )
+}
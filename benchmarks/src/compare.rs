@@ -0,0 +1,144 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use flip_hash_testkit::{acc::Accumulator, checkpoint::read_checkpoint, exp::Experiment};
+use serde::Serialize;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+/// The counts of an algorithm in a checkpoint.
+#[derive(Clone, Debug)]
+pub(crate) struct Sample {
+    pub(crate) checkpoint: PathBuf,
+    pub(crate) algo: String,
+}
+
+/// The comparison of two samples, with the summaries of the experiment.
+#[derive(Serialize)]
+pub(crate) struct Comparison<S> {
+    first: SampleSummary<S>,
+    second: SampleSummary<S>,
+    #[serde(flatten)]
+    homogeneity: Homogeneity,
+}
+
+#[derive(Serialize)]
+struct SampleSummary<S> {
+    checkpoint: PathBuf,
+    algo: String,
+    #[serde(flatten)]
+    summary: S,
+}
+
+/// The chi-squared test of homogeneity of two samples of counts, whose null
+/// hypothesis is that they are drawn from the same distribution, and the
+/// sizes of their difference.
+#[derive(Serialize)]
+struct Homogeneity {
+    #[serde(rename = "degrees of freedom")]
+    degrees_of_freedom: usize,
+    statistic: f64,
+    #[serde(rename = "p-value")]
+    p_value: f64,
+    /// The effect size of the test, between 0 for identical distributions
+    /// and 1 for disjoint ones.
+    #[serde(rename = "cramers v")]
+    cramers_v: f64,
+    /// The largest difference between the probabilities of a set of values
+    /// under the two empirical distributions.
+    #[serde(rename = "total variation distance")]
+    total_variation_distance: f64,
+}
+
+/// Compares the counts of two samples of the checkpoints of `experiment`,
+/// whose identity in checkpoints is `checkpoint_experiment`.
+///
+/// # Panics
+///
+/// Panics if a checkpoint cannot be read, e.g., as it is of another
+/// experiment, or has no counts of the algorithm, or if the accumulators of
+/// the experiment do not count values.
+pub(crate) fn compare_samples<E: Experiment>(
+    experiment: &E,
+    checkpoint_experiment: &str,
+    samples: &[Sample; 2],
+) -> Comparison<E::Summary> {
+    let [first, second] = samples.each_ref().map(|sample| {
+        let mut checkpoint = read_checkpoint(&sample.checkpoint, checkpoint_experiment, || {
+            experiment.new_accumulator()
+        })
+        .unwrap_or_else(|err| panic!("Cannot read {}: {err}", sample.checkpoint.display()));
+        let accumulator = checkpoint
+            .accumulators
+            .remove(&sample.algo)
+            .unwrap_or_else(|| {
+                panic!(
+                    "No counts of {} in {}",
+                    sample.algo,
+                    sample.checkpoint.display()
+                )
+            });
+        let counts = accumulator
+            .nonzero_counts_by_value()
+            .expect("The experiment does not count values");
+        (
+            SampleSummary {
+                checkpoint: sample.checkpoint.clone(),
+                algo: sample.algo.clone(),
+                summary: experiment.summary(&accumulator),
+            },
+            counts,
+        )
+    });
+    Comparison {
+        homogeneity: homogeneity(&first.1, &second.1),
+        first: first.0,
+        second: second.0,
+    }
+}
+
+fn homogeneity(first: &[(usize, u64)], second: &[(usize, u64)]) -> Homogeneity {
+    // The values that occur in neither sample are left out, as they carry no
+    // information.
+    let mut counts = HashMap::<usize, [f64; 2]>::new();
+    for (sample, sample_counts) in [first, second].into_iter().enumerate() {
+        for &(value, count) in sample_counts {
+            counts.entry(value).or_default()[sample] += count as f64;
+        }
+    }
+    let totals = counts
+        .values()
+        .fold([0.0; 2], |[t0, t1], [c0, c1]| [t0 + c0, t1 + c1]);
+    let num_samples = totals[0] + totals[1];
+    let statistic = counts
+        .values()
+        .map(|value_counts| {
+            let value_total = value_counts[0] + value_counts[1];
+            (0..2)
+                .map(|sample| {
+                    let e = totals[sample] * value_total / num_samples;
+                    (value_counts[sample] - e).powi(2) / e
+                })
+                .sum::<f64>()
+        })
+        .sum::<f64>();
+    let degrees_of_freedom = counts.len().saturating_sub(1);
+    let p_value = if degrees_of_freedom > 0 {
+        1.0 - ChiSquared::new(degrees_of_freedom as f64)
+            .unwrap()
+            .cdf(statistic)
+    } else {
+        1.0
+    };
+    let total_variation_distance = counts
+        .values()
+        .map(|value_counts| (value_counts[0] / totals[0] - value_counts[1] / totals[1]).abs())
+        .sum::<f64>()
+        / 2.0;
+    Homogeneity {
+        degrees_of_freedom,
+        statistic,
+        p_value,
+        // With two samples, the smaller dimension of the table is 2.
+        cramers_v: (statistic / num_samples).sqrt(),
+        total_variation_distance,
+    }
+}
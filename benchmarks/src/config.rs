@@ -0,0 +1,279 @@
+//! Campaigns of experiments that TOML files declare, which `--config` runs
+//! one after the other, so that they can be version-controlled, e.g.:
+//!
+//! ```toml
+//! rng-seed = 42
+//!
+//! [defaults]
+//! max-keys = 1000000000
+//! output-dir = "results/campaign"
+//!
+//! [[experiments]]
+//! experiment = "regularity"
+//! range-end = 999
+//! input-size-bytes = 8
+//! algorithms = ["flip-hash64", "jump-hash"]
+//!
+//! [[experiments]]
+//! experiment = "independence-across-ranges"
+//! range-end = [9, 19, 29]
+//! input-size-bytes = 8
+//! key-distribution = "sequential"
+//! max-duration = "2h"
+//! output = "results/campaign/sequential_independence"
+//! ```
+//!
+//! The keys of the tables of the experiments are the long names of their
+//! arguments and of the options of their runs, e.g., `max-keys` or `output`,
+//! and those of `defaults` apply to all the experiments that do not set them.
+//! Arrays are repeated arguments, and booleans are flags. The generators and
+//! the metrics are those of the process, so that `rng-seed` and
+//! `metrics-address` are only set at the top level.
+
+use std::{error::Error, fs, path::Path};
+
+use serde::Deserialize;
+use toml::{Table, Value};
+
+/// The key of the name of the experiment in its table.
+const EXPERIMENT_KEY: &str = "experiment";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Campaign {
+    pub(crate) rng_seed: Option<u64>,
+    pub(crate) metrics_address: Option<String>,
+    #[serde(default)]
+    defaults: Table,
+    experiments: Vec<Table>,
+}
+
+impl Campaign {
+    pub(crate) fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Returns the command-line arguments of each experiment, starting with
+    /// its name, in order.
+    pub(crate) fn experiment_args(&self) -> Result<Vec<Vec<String>>, String> {
+        self.experiments
+            .iter()
+            .enumerate()
+            .map(|(index, experiment)| {
+                let mut table = self.defaults.clone();
+                table.extend(experiment.clone());
+                let Some(Value::String(name)) = table.remove(EXPERIMENT_KEY) else {
+                    return Err(format!(
+                        "Experiment {} has no `{EXPERIMENT_KEY}` name",
+                        index + 1
+                    ));
+                };
+                let mut args = vec![name];
+                for (key, value) in table {
+                    push_args(&mut args, &key, value)
+                        .map_err(|err| format!("Experiment {}: {err}", index + 1))?;
+                }
+                Ok(args)
+            })
+            .collect()
+    }
+}
+
+/// Pushes the arguments of the option `key` with `value` to `args`.
+fn push_args(args: &mut Vec<String>, key: &str, value: Value) -> Result<(), String> {
+    let value = match value {
+        Value::Boolean(true) => {
+            args.push(format!("--{key}"));
+            return Ok(());
+        }
+        Value::Boolean(false) => return Ok(()),
+        Value::Array(values) => {
+            return values
+                .into_iter()
+                .try_for_each(|value| push_args(args, key, value))
+        }
+        Value::String(value) => value,
+        Value::Integer(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+        Value::Datetime(_) | Value::Table(_) => {
+            return Err(format!("`{key}` is neither a string, a number nor a flag"))
+        }
+    };
+    args.extend([format!("--{key}"), value]);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use clap::Parser;
+
+    use super::*;
+    use crate::Args;
+
+    const CAMPAIGN: &str = r#"
+        rng-seed = 42
+
+        [defaults]
+        max-keys = 1000000
+        input-size-bytes = 8
+
+        [[experiments]]
+        experiment = "regularity"
+        range-end = 999
+        algorithms = ["flip-hash64", "jump-hash"]
+        assert-p-value-min = 1e-6
+
+        [[experiments]]
+        experiment = "independence-across-ranges"
+        range-end = [9, 19, 29]
+        key-distribution = "sequential"
+        max-keys = 1000
+        max-duration = "2h"
+    "#;
+
+    /// Parses `args` as the arguments of the process.
+    fn parse(args: &[String]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(
+            iter::once("flip_hash_benchmarks").chain(args.iter().map(String::as_str)),
+        )
+    }
+
+    #[test]
+    fn experiments_are_command_lines() {
+        let campaign = toml::from_str::<Campaign>(CAMPAIGN).unwrap();
+        assert_eq!(campaign.rng_seed, Some(42));
+        assert_eq!(campaign.metrics_address, None);
+        let experiment_args = campaign.experiment_args().unwrap();
+        // The options are sorted by name, and those of the experiments
+        // override the defaults.
+        assert_eq!(
+            experiment_args,
+            [
+                vec![
+                    "regularity",
+                    "--algorithms",
+                    "flip-hash64",
+                    "--algorithms",
+                    "jump-hash",
+                    "--assert-p-value-min",
+                    "0.000001",
+                    "--input-size-bytes",
+                    "8",
+                    "--max-keys",
+                    "1000000",
+                    "--range-end",
+                    "999",
+                ],
+                vec![
+                    "independence-across-ranges",
+                    "--input-size-bytes",
+                    "8",
+                    "--key-distribution",
+                    "sequential",
+                    "--max-duration",
+                    "2h",
+                    "--max-keys",
+                    "1000",
+                    "--range-end",
+                    "9",
+                    "--range-end",
+                    "19",
+                    "--range-end",
+                    "29",
+                ],
+            ]
+        );
+        // They parse as the command lines that run the same experiments.
+        for (args, command_line) in iter::zip(
+            &experiment_args,
+            [
+                "regularity -r 999 -i 8 -a flip-hash64 -a jump-hash --max-keys 1000000 --assert-p-value-min 1e-6",
+                "independence-across-ranges -r 9 -r 19 -r 29 -i 8 --key-distribution sequential --max-keys 1000 --max-duration 2h",
+            ],
+        ) {
+            let command_line = command_line.split(' ').map(str::to_owned).collect::<Vec<_>>();
+            assert_eq!(
+                format!("{:?}", parse(args).unwrap()),
+                format!("{:?}", parse(&command_line).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn booleans_are_flags() {
+        let mut args = Vec::new();
+        push_args(&mut args, "json", Value::Boolean(true)).unwrap();
+        push_args(&mut args, "verbose", Value::Boolean(false)).unwrap();
+        assert_eq!(args, ["--json"]);
+
+        let mut args = vec!["list-algorithms".to_owned()];
+        push_args(&mut args, "json", Value::Boolean(true)).unwrap();
+        assert!(matches!(
+            parse(&args).unwrap().command,
+            Some(crate::Command::ListAlgorithms { json: true })
+        ));
+    }
+
+    #[test]
+    fn arrays_are_repeated_arguments() {
+        let mut args = Vec::new();
+        let values = Value::Array(vec![Value::Integer(1), Value::Float(2.5), "x".into()]);
+        push_args(&mut args, "value", values).unwrap();
+        assert_eq!(args, ["--value", "1", "--value", "2.5", "--value", "x"]);
+    }
+
+    #[test]
+    fn unsupported_values_are_rejected() {
+        let campaign = toml::from_str::<Campaign>(
+            r#"
+            [[experiments]]
+            experiment = "regularity"
+            [experiments.keys]
+            key-distribution = "sequential"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            campaign.experiment_args().unwrap_err(),
+            "Experiment 1: `keys` is neither a string, a number nor a flag"
+        );
+
+        let campaign = toml::from_str::<Campaign>(
+            r#"
+            [[experiments]]
+            experiment = "regularity"
+            [[experiments]]
+            experiment = "regularity"
+            max-duration = 1979-05-27T07:32:00Z
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            campaign.experiment_args().unwrap_err(),
+            "Experiment 2: `max-duration` is neither a string, a number nor a flag"
+        );
+
+        // Nested in arrays too.
+        let mut args = Vec::new();
+        let values = Value::Array(vec![Value::Table(Table::new())]);
+        assert!(push_args(&mut args, "range-end", values).is_err());
+    }
+
+    #[test]
+    fn experiments_need_names() {
+        let campaign = toml::from_str::<Campaign>(
+            r#"
+            [[experiments]]
+            range-end = 999
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            campaign.experiment_args().unwrap_err(),
+            "Experiment 1 has no `experiment` name"
+        );
+        assert!(toml::from_str::<Campaign>("seed = 42\nexperiments = []").is_err());
+    }
+}
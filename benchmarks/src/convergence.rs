@@ -0,0 +1,156 @@
+//! Adaptive stopping, which stops hashing keys for an algorithm once a
+//! metric of its summaries is stable, rather than after a fixed number of keys
+//! or duration.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// The options of adaptive stopping, which is disabled unless
+/// `--convergence-metric` is set.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ConvergenceOptions {
+    /// Stops hashing keys for an algorithm once this field of its summaries,
+    /// e.g., `normalized c hat` or `max bias`, is within
+    /// `--convergence-tolerance` over the last `--convergence-steps` steps.
+    /// The summaries of converged algorithms are marked as such.
+    #[clap(long, global = true)]
+    convergence_metric: Option<String>,
+    /// The largest difference between the values of the metric over the
+    /// last steps for it to be stable.
+    #[clap(long, global = true, default_value_t = 1e-3)]
+    convergence_tolerance: f64,
+    #[clap(long, global = true, default_value_t = 5, value_parser = clap::value_parser!(u64).range(2..))]
+    convergence_steps: u64,
+}
+
+/// The last values of the metric of each algorithm.
+pub(crate) struct Convergence<'a> {
+    metric: &'a str,
+    tolerance: f64,
+    num_steps: usize,
+    values: HashMap<String, VecDeque<f64>>,
+}
+
+impl ConvergenceOptions {
+    /// Returns the metric of adaptive stopping, if it is enabled.
+    pub(crate) fn metric(&self) -> Option<&str> {
+        self.convergence_metric.as_deref()
+    }
+}
+
+impl<'a> Convergence<'a> {
+    /// Returns the convergence of `options`, unless adaptive stopping is
+    /// disabled.
+    pub(crate) fn new(options: &'a ConvergenceOptions) -> Option<Self> {
+        Some(Self {
+            metric: options.convergence_metric.as_deref()?,
+            tolerance: options.convergence_tolerance,
+            num_steps: options.convergence_steps as usize,
+            values: HashMap::new(),
+        })
+    }
+
+    /// Records the summary of `algo` after a step, and returns whether its
+    /// metric is stable.
+    ///
+    /// If the metric is `null` or left out, e.g., before enough keys are
+    /// hashed to compute it, it is not stable, and the steps before do not
+    /// count towards its stability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the metric is not a number.
+    pub(crate) fn record(&mut self, algo: &str, summary: &impl Serialize) -> bool {
+        let summary = serde_json::to_value(summary).unwrap();
+        let values = self.values.entry(algo.to_owned()).or_default();
+        match summary.get(self.metric) {
+            Some(Value::Number(number)) => values.push_back(number.as_f64().unwrap()),
+            Some(Value::Null) | None => values.clear(),
+            Some(_) => panic!("{:?} is not a number", self.metric),
+        }
+        if values.len() > self.num_steps {
+            values.pop_front();
+        }
+        values.len() == self.num_steps && {
+            let (min, max) = values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                    (min.min(value), max.max(value))
+                });
+            max - min <= self.tolerance
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn options(tolerance: f64, num_steps: u64) -> ConvergenceOptions {
+        ConvergenceOptions {
+            convergence_metric: Some("max bias".to_owned()),
+            convergence_tolerance: tolerance,
+            convergence_steps: num_steps,
+        }
+    }
+
+    #[test]
+    fn stops_once_the_metric_is_within_the_tolerance_over_the_window() {
+        let options = options(0.01, 3);
+        let mut convergence = Convergence::new(&options).unwrap();
+        let stable = [0.5, 0.2, 0.105, 0.1, 0.109, 0.1]
+            .map(|max_bias| convergence.record("flip", &json!({ "max bias": max_bias })));
+        assert_eq!(stable, [false, false, false, false, true, true]);
+        // A step out of the tolerance restarts the window.
+        assert!(!convergence.record("flip", &json!({ "max bias": 0.2 })));
+        assert!(!convergence.record("flip", &json!({ "max bias": 0.2 })));
+        assert!(convergence.record("flip", &json!({ "max bias": 0.2 })));
+    }
+
+    #[test]
+    fn algorithms_converge_separately() {
+        let options = options(0.01, 2);
+        let mut convergence = Convergence::new(&options).unwrap();
+        assert!(!convergence.record("flip", &json!({ "max bias": 0.1 })));
+        assert!(!convergence.record("jump", &json!({ "max bias": 0.1 })));
+        assert!(convergence.record("flip", &json!({ "max bias": 0.1 })));
+        assert!(!convergence.record("jump", &json!({ "max bias": 0.5 })));
+    }
+
+    #[test]
+    fn never_stops_without_the_metric() {
+        let options = options(0.01, 2);
+        let mut convergence = Convergence::new(&options).unwrap();
+        for _ in 0..5 {
+            assert!(!convergence.record("flip", &json!({ "max bias": null })));
+            assert!(!convergence.record("jump", &json!({ "p-value": 0.5 })));
+        }
+        // The steps without the metric do not count towards the window.
+        assert!(!convergence.record("flip", &json!({ "max bias": 0.1 })));
+        assert!(!convergence.record("flip", &json!({ "max bias": null })));
+        assert!(!convergence.record("flip", &json!({ "max bias": 0.1 })));
+        assert!(convergence.record("flip", &json!({ "max bias": 0.1 })));
+    }
+
+    #[test]
+    fn is_disabled_without_a_metric() {
+        let options = ConvergenceOptions {
+            convergence_metric: None,
+            ..options(0.01, 2)
+        };
+        assert!(Convergence::new(&options).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "\"max bias\" is not a number")]
+    fn panics_if_the_metric_is_not_a_number() {
+        let options = options(0.01, 2);
+        Convergence::new(&options)
+            .unwrap()
+            .record("flip", &json!({ "max bias": "low" }));
+    }
+}
@@ -0,0 +1,364 @@
+//! The hardware performance counters of hashing keys, e.g., cycles and
+//! branch misses per key, as micro-architectural evidence of the costs that
+//! `speed` measures.
+//!
+//! The counters are read with `perf_event_open`, so that they are only
+//! available on Linux, and only if the kernel lets unprivileged processes
+//! count their own user-space events, i.e., if `perf_event_paranoid` is at
+//! most 2, on hardware that exposes them, which most virtual machines do not.
+
+use std::{
+    cell::RefCell,
+    hint::black_box,
+    io::{self, Read, Write},
+    mem,
+    ops::RangeToInclusive,
+};
+
+use flip_hash_testkit::{
+    acc::Accumulator,
+    algo::Algorithm,
+    checkpoint::{read_u64s, write_u64s},
+    exp::Experiment,
+    keys::Keys,
+};
+use itertools::Itertools;
+use serde::Serialize;
+
+/// The events that are counted, in the order of their counts.
+const NUM_EVENTS: usize = 4;
+
+thread_local! {
+    /// The counters of the thread, which are opened on first use.
+    static COUNTERS: RefCell<Option<sys::Counters>> = const { RefCell::new(None) };
+}
+
+/// Counts the cycles, instructions, branch misses and cache misses of
+/// hashing keys, for each of the given range ends and input sizes.
+#[derive(Clone, Debug)]
+pub(crate) struct PerfCounters {
+    ranges: Vec<RangeToInclusive<u64>>,
+    input_sizes_bytes: Vec<usize>,
+    keys: Keys,
+}
+
+impl PerfCounters {
+    const BATCH_LEN: usize = 1024;
+
+    /// # Panics
+    ///
+    /// Panics if the counters cannot be opened, e.g., on other systems than
+    /// Linux.
+    pub(crate) fn new(
+        ranges: Vec<RangeToInclusive<u64>>,
+        input_sizes_bytes: Vec<usize>,
+        keys: Keys,
+    ) -> Self {
+        assert!(!ranges.is_empty() && !input_sizes_bytes.is_empty());
+        // Fails early rather than in the workers.
+        if let Err(err) = sys::Counters::open() {
+            panic!("Cannot open the hardware performance counters: {err}");
+        }
+        Self {
+            ranges,
+            input_sizes_bytes,
+            keys,
+        }
+    }
+
+    fn configurations(&self) -> impl Iterator<Item = (RangeToInclusive<u64>, usize)> + '_ {
+        self.ranges
+            .iter()
+            .cartesian_product(&self.input_sizes_bytes)
+            .map(|(&range, &input_size_bytes)| (range, input_size_bytes))
+    }
+}
+
+/// The numbers of keys hashed and the counts of the events of hashing them,
+/// for each of a number of configurations.
+pub(crate) struct EventCounts {
+    num_keys: Vec<u64>,
+    /// The counts of the events, configuration after configuration.
+    counts: Vec<u64>,
+}
+
+impl EventCounts {
+    fn new(len: usize) -> Self {
+        Self {
+            num_keys: vec![0; len],
+            counts: vec![0; len * NUM_EVENTS],
+        }
+    }
+}
+
+impl Accumulator for EventCounts {
+    /// The index of the configuration, the number of keys and the counts of
+    /// the events of hashing them.
+    type Value = (usize, u64, [u64; NUM_EVENTS]);
+
+    #[inline]
+    fn record(&mut self, (index, num_keys, counts): Self::Value) {
+        self.num_keys[index] += num_keys;
+        self.counts[index * NUM_EVENTS..][..NUM_EVENTS]
+            .iter_mut()
+            .zip(counts)
+            .for_each(|(s, c)| *s += c);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.num_keys
+            .iter_mut()
+            .zip(&other.num_keys)
+            .for_each(|(s, o)| *s += o);
+        self.counts
+            .iter_mut()
+            .zip(&other.counts)
+            .for_each(|(s, o)| *s += o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.num_keys.iter().sum::<u64>()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of_val(self.num_keys.as_slice()) + mem::size_of_val(self.counts.as_slice())
+    }
+
+    /// Writes the numbers of keys, then the counts of the events.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64s(output, &self.num_keys)?;
+        write_u64s(output, &self.counts)
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        read_u64s(input, &mut self.num_keys)?;
+        read_u64s(input, &mut self.counts)
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct PerfCountersSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    counters: Vec<KeyCounters>,
+}
+
+/// The mean counts of the events of hashing a key, for a configuration of
+/// [`PerfCounters`].
+#[derive(Serialize)]
+struct KeyCounters {
+    #[serde(rename = "range end")]
+    range_end: u64,
+    #[serde(rename = "input size bytes")]
+    input_size_bytes: usize,
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "cycles per key")]
+    cycles: f64,
+    #[serde(rename = "instructions per key")]
+    instructions: f64,
+    #[serde(rename = "branch misses per key")]
+    branch_misses: f64,
+    #[serde(rename = "cache misses per key")]
+    cache_misses: f64,
+}
+
+impl Experiment for PerfCounters {
+    type Accumulator = EventCounts;
+    type Summary = PerfCountersSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        EventCounts::new(self.configurations().count())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        let max_input_size_bytes = self.input_sizes_bytes.iter().max().copied().unwrap_or(0);
+        vec![0; Self::BATCH_LEN * max_input_size_bytes]
+    }
+
+    /// Hashes a batch of keys for each configuration, counting the events of
+    /// the hashes only.
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        batch: &mut Self::Scratch,
+    ) {
+        COUNTERS.with_borrow_mut(|counters| {
+            let counters = counters
+                .get_or_insert_with(|| sys::Counters::open().expect("Cannot open the counters"));
+            for (index, (range, input_size_bytes)) in self.configurations().enumerate() {
+                let keys = &mut batch[..Self::BATCH_LEN * input_size_bytes];
+                for key in keys.chunks_exact_mut(input_size_bytes) {
+                    self.keys.fill(key);
+                }
+                counters.start().unwrap();
+                for key in keys.chunks_exact(input_size_bytes) {
+                    black_box(algorithm.hash(black_box(key), 0, range));
+                }
+                let counts = counters.stop().unwrap();
+                accumulator.record((index, Self::BATCH_LEN as u64, counts));
+            }
+        })
+    }
+
+    /// Builds the state of the algorithm for each configuration, if it has
+    /// one, so that its events are not counted, then hashes `num_iterations`
+    /// keys for each configuration.
+    fn accumulate(&self, algorithm: &impl Algorithm, num_iterations: u64) -> Self::Accumulator {
+        for (range, _) in self.configurations() {
+            algorithm.build(0, range);
+        }
+        let mut accumulator = self.new_accumulator();
+        let mut scratch = self.new_scratch();
+        for _ in 0..num_iterations.div_ceil(Self::BATCH_LEN as u64) {
+            self.run(&mut accumulator, algorithm, &mut scratch);
+        }
+        accumulator
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let counters = self
+            .configurations()
+            .zip(&accumulator.num_keys)
+            .zip(accumulator.counts.chunks_exact(NUM_EVENTS))
+            .map(|(((range, input_size_bytes), &num_keys), counts)| {
+                let per_key = |count: u64| count as f64 / num_keys as f64;
+                KeyCounters {
+                    range_end: range.end,
+                    input_size_bytes,
+                    num_keys,
+                    cycles: per_key(counts[0]),
+                    instructions: per_key(counts[1]),
+                    branch_misses: per_key(counts[2]),
+                    cache_misses: per_key(counts[3]),
+                }
+            })
+            .collect();
+        PerfCountersSummary {
+            num_keys: accumulator.num_iterations(),
+            counters,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::{
+        fs::File,
+        io::{self, Read},
+        mem,
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    use perf_event_open_sys::{
+        bindings::{
+            perf_event_attr, perf_event_ioc_flags_PERF_IOC_FLAG_GROUP as PERF_IOC_FLAG_GROUP,
+            perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES, perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+            perf_hw_id_PERF_COUNT_HW_CPU_CYCLES, perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+            perf_type_id_PERF_TYPE_HARDWARE, PERF_FLAG_FD_CLOEXEC,
+        },
+        ioctls, perf_event_open,
+    };
+
+    use super::NUM_EVENTS;
+
+    /// The counters of the events of the calling thread, in user space,
+    /// which are counted together as a group.
+    pub(super) struct Counters {
+        /// The counters, whose first one leads the group.
+        files: [File; NUM_EVENTS],
+    }
+
+    impl Counters {
+        pub(super) fn open() -> io::Result<Self> {
+            let mut files = Vec::with_capacity(NUM_EVENTS);
+            for event in [
+                perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+                perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+                perf_hw_id_PERF_COUNT_HW_BRANCH_MISSES,
+                perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+            ] {
+                // SAFETY: the attributes are plain data, for which zero is
+                // valid.
+                let mut attr: perf_event_attr = unsafe { mem::zeroed() };
+                attr.type_ = perf_type_id_PERF_TYPE_HARDWARE;
+                attr.size = mem::size_of::<perf_event_attr>() as u32;
+                attr.config = event.into();
+                attr.set_exclude_kernel(1);
+                attr.set_exclude_hv(1);
+                // The group is enabled through its leader.
+                attr.set_disabled(u64::from(files.is_empty()));
+                let group_fd = files.first().map_or(-1, |leader: &File| leader.as_raw_fd());
+                // SAFETY: the attributes outlive the call.
+                let fd = unsafe {
+                    perf_event_open(&mut attr, 0, -1, group_fd, PERF_FLAG_FD_CLOEXEC.into())
+                };
+                if fd < 0 {
+                    return Err(io::Error::from_raw_os_error(-fd));
+                }
+                // SAFETY: the file descriptor is open and owned by nothing
+                // else.
+                files.push(unsafe { File::from_raw_fd(fd) });
+            }
+            Ok(Self {
+                files: files.try_into().unwrap(),
+            })
+        }
+
+        /// Resets the counts and starts counting.
+        pub(super) fn start(&mut self) -> io::Result<()> {
+            let leader = self.files[0].as_raw_fd();
+            // SAFETY: the file descriptor is that of an open group leader.
+            if unsafe { ioctls::RESET(leader, PERF_IOC_FLAG_GROUP) } < 0
+                || unsafe { ioctls::ENABLE(leader, PERF_IOC_FLAG_GROUP) } < 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Stops counting and returns the counts since the start.
+        pub(super) fn stop(&mut self) -> io::Result<[u64; NUM_EVENTS]> {
+            // SAFETY: the file descriptor is that of an open group leader.
+            if unsafe { ioctls::DISABLE(self.files[0].as_raw_fd(), PERF_IOC_FLAG_GROUP) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut counts = [0; NUM_EVENTS];
+            for (count, file) in counts.iter_mut().zip(&mut self.files) {
+                let mut bytes = [0; 8];
+                file.read_exact(&mut bytes)?;
+                *count = u64::from_ne_bytes(bytes);
+            }
+            Ok(counts)
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::io;
+
+    use super::NUM_EVENTS;
+
+    pub(super) struct Counters;
+
+    impl Counters {
+        pub(super) fn open() -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "hardware performance counters are only read on Linux",
+            ))
+        }
+
+        pub(super) fn start(&mut self) -> io::Result<()> {
+            unreachable!()
+        }
+
+        pub(super) fn stop(&mut self) -> io::Result<[u64; NUM_EVENTS]> {
+            unreachable!()
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! The protocol of distributed experiments, whose steps are accumulated by
+//! workers on other hosts, and merged by a coordinator, which writes the
+//! summaries.
+//!
+//! A worker opens a TCP connection to the coordinator per thread, and sends
+//! the fingerprint of its experiment, i.e., of its arguments, which must be
+//! that of the coordinator. The coordinator replies with the stream of the
+//! generator of the thread, then sends it steps to accumulate, one at a time:
+//! the index of the algorithm, the seed and the number of keys, to which the
+//! worker replies with the name of the algorithm, the time it took and the
+//! state of its accumulator, as in checkpoints, until the coordinator sends
+//! that no steps remain. Numbers are little-endian 64-bit integers, and
+//! strings are prefixed with their lengths.
+
+use std::{
+    io::{self, BufReader, BufWriter, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use flip_hash_testkit::{
+    acc::Accumulator,
+    checkpoint::{read_u64, write_u64},
+};
+
+const MAGIC: &[u8; 8] = b"FHDIST01";
+/// The algorithm index that tells a worker that no steps remain.
+const DONE: u64 = u64::MAX;
+const ACCEPTED: u64 = 0;
+const REJECTED: u64 = 1;
+
+/// A step that the coordinator hands out to a worker.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Assignment {
+    pub(crate) algorithm_index: usize,
+    pub(crate) seed: u64,
+    pub(crate) num_keys: u64,
+}
+
+/// The connection of the coordinator to a worker.
+pub(crate) struct WorkerConnection {
+    input: BufReader<TcpStream>,
+    output: BufWriter<TcpStream>,
+}
+
+impl WorkerConnection {
+    /// Accepts the worker of `stream` if the fingerprint of its experiment is
+    /// `fingerprint`, and has it use the stream `stream_index` of the
+    /// generator.
+    pub(crate) fn accept(
+        stream: TcpStream,
+        fingerprint: &str,
+        stream_index: u64,
+    ) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let mut connection = Self {
+            input: BufReader::new(stream.try_clone()?),
+            output: BufWriter::new(stream),
+        };
+        let mut magic = [0; MAGIC.len()];
+        connection.input.read_exact(&mut magic)?;
+        if magic != *MAGIC {
+            return Err(invalid_message("not a worker"));
+        }
+        let worker_fingerprint = read_string(&mut connection.input)?;
+        if worker_fingerprint != fingerprint {
+            write_u64(&mut connection.output, REJECTED)?;
+            write_string(
+                &mut connection.output,
+                &format!("the experiment of the coordinator is {fingerprint}"),
+            )?;
+            connection.output.flush()?;
+            return Err(invalid_message(&format!(
+                "the experiment of the worker is {worker_fingerprint}"
+            )));
+        }
+        write_u64(&mut connection.output, ACCEPTED)?;
+        write_u64(&mut connection.output, stream_index)?;
+        connection.output.flush()?;
+        Ok(connection)
+    }
+
+    /// Has the worker accumulate the step of `assignment` into `accumulator`,
+    /// which must be empty, and returns the name of the algorithm, with the
+    /// time that the worker took.
+    pub(crate) fn accumulate<A: Accumulator>(
+        &mut self,
+        assignment: Assignment,
+        accumulator: &mut A,
+    ) -> io::Result<(String, Duration)> {
+        write_u64(&mut self.output, assignment.algorithm_index as u64)?;
+        write_u64(&mut self.output, assignment.seed)?;
+        write_u64(&mut self.output, assignment.num_keys)?;
+        self.output.flush()?;
+        let algo = read_string(&mut self.input)?;
+        let elapsed = Duration::from_nanos(read_u64(&mut self.input)?);
+        accumulator.read_state(&mut self.input)?;
+        Ok((algo, elapsed))
+    }
+
+    /// Tells the worker that no steps remain.
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        write_u64(&mut self.output, DONE)?;
+        self.output.flush()
+    }
+}
+
+/// The connection of a worker to the coordinator.
+pub(crate) struct CoordinatorConnection {
+    input: BufReader<TcpStream>,
+    output: BufWriter<TcpStream>,
+}
+
+impl CoordinatorConnection {
+    /// Connects to the coordinator at `address`, whose experiment must have
+    /// the fingerprint `fingerprint`, and returns the connection, with the
+    /// stream of the generator that the thread must use.
+    pub(crate) fn connect(
+        address: impl ToSocketAddrs,
+        fingerprint: &str,
+    ) -> io::Result<(Self, u64)> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        let mut connection = Self {
+            input: BufReader::new(stream.try_clone()?),
+            output: BufWriter::new(stream),
+        };
+        connection.output.write_all(MAGIC)?;
+        write_string(&mut connection.output, fingerprint)?;
+        connection.output.flush()?;
+        match read_u64(&mut connection.input)? {
+            ACCEPTED => {
+                let stream_index = read_u64(&mut connection.input)?;
+                Ok((connection, stream_index))
+            }
+            REJECTED => Err(io::Error::other(format!(
+                "Rejected by the coordinator, as {}",
+                read_string(&mut connection.input)?
+            ))),
+            _ => Err(invalid_message("not a coordinator")),
+        }
+    }
+
+    /// Returns the next step to accumulate, or `None` if no steps remain.
+    pub(crate) fn next_assignment(&mut self) -> io::Result<Option<Assignment>> {
+        let algorithm_index = read_u64(&mut self.input)?;
+        if algorithm_index == DONE {
+            return Ok(None);
+        }
+        Ok(Some(Assignment {
+            algorithm_index: algorithm_index as usize,
+            seed: read_u64(&mut self.input)?,
+            num_keys: read_u64(&mut self.input)?,
+        }))
+    }
+
+    /// Sends the accumulator of the last step, with the name of its algorithm
+    /// and the time it took.
+    pub(crate) fn send_step(
+        &mut self,
+        algo: &str,
+        accumulator: &impl Accumulator,
+        elapsed: Duration,
+    ) -> io::Result<()> {
+        write_string(&mut self.output, algo)?;
+        write_u64(&mut self.output, elapsed.as_nanos() as u64)?;
+        accumulator.write_state(&mut self.output)?;
+        self.output.flush()
+    }
+}
+
+/// The largest string that is read, so that a corrupted length does not
+/// exhaust the memory.
+const MAX_STRING_LEN: u64 = 1 << 20;
+
+fn write_string(output: &mut impl Write, string: &str) -> io::Result<()> {
+    write_u64(output, string.len() as u64)?;
+    output.write_all(string.as_bytes())
+}
+
+fn read_string(input: &mut impl Read) -> io::Result<String> {
+    let len = read_u64(input)?;
+    if len > MAX_STRING_LEN {
+        return Err(invalid_message("string too long"));
+    }
+    let mut bytes = vec![0; len as usize];
+    input
+        .read_exact(&mut bytes)
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::UnexpectedEof => invalid_message("truncated string"),
+            _ => err,
+        })?;
+    String::from_utf8(bytes).map_err(|_| invalid_message("invalid string"))
+}
+
+fn invalid_message(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid message: {reason}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use flip_hash_testkit::acc::NumOccurrences;
+
+    use super::*;
+
+    const FINGERPRINT: &str = "regularity -r 3 -i 8";
+
+    fn assert_invalid_message(result: io::Result<impl std::fmt::Debug>, reason: &str) {
+        let err = result.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), format!("Invalid message: {reason}"));
+    }
+
+    /// Runs `worker` against a coordinator that listens on the loopback
+    /// interface, and returns the stream of the worker to the coordinator.
+    fn connect<T: Send + 'static>(
+        worker: impl FnOnce(String) -> T + Send + 'static,
+    ) -> (TcpStream, thread::JoinHandle<T>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let worker = thread::spawn(move || worker(address));
+        (listener.accept().unwrap().0, worker)
+    }
+
+    #[test]
+    fn steps_round_trip() {
+        let (stream, worker) = connect(|address| {
+            let (mut connection, stream_index) =
+                CoordinatorConnection::connect(address, FINGERPRINT).unwrap();
+            let mut assignments = Vec::new();
+            while let Some(assignment) = connection.next_assignment().unwrap() {
+                let mut accumulator = NumOccurrences::<u64>::new(4);
+                (0..assignment.num_keys).for_each(|_| accumulator.record(assignment.seed));
+                connection
+                    .send_step("flip", &accumulator, Duration::from_nanos(42))
+                    .unwrap();
+                assignments.push((assignment.algorithm_index, assignment.seed));
+            }
+            (stream_index, assignments)
+        });
+        let mut connection = WorkerConnection::accept(stream, FINGERPRINT, 7).unwrap();
+        for (algorithm_index, seed, num_keys) in [(0, 2, 3), (1, 0, 5)] {
+            let mut accumulator = NumOccurrences::<u64>::new(4);
+            let assignment = Assignment {
+                algorithm_index,
+                seed,
+                num_keys,
+            };
+            assert_eq!(
+                connection.accumulate(assignment, &mut accumulator).unwrap(),
+                ("flip".to_string(), Duration::from_nanos(42))
+            );
+            assert_eq!(accumulator.count(seed as usize), num_keys);
+            assert_eq!(accumulator.num_iterations(), num_keys);
+        }
+        connection.finish().unwrap();
+        assert_eq!(worker.join().unwrap(), (7, vec![(0, 2), (1, 0)]));
+    }
+
+    #[test]
+    fn workers_of_other_experiments_are_rejected() {
+        let (stream, worker) = connect(|address| {
+            CoordinatorConnection::connect(address, "regularity -r 4 -i 8")
+                .map(|_| ())
+                .unwrap_err()
+        });
+        assert_invalid_message(
+            WorkerConnection::accept(stream, FINGERPRINT, 0).map(|_| ()),
+            "the experiment of the worker is regularity -r 4 -i 8",
+        );
+        assert_eq!(
+            worker.join().unwrap().to_string(),
+            format!("Rejected by the coordinator, as the experiment of the coordinator is {FINGERPRINT}")
+        );
+    }
+
+    #[test]
+    fn other_clients_are_not_workers() {
+        let (stream, worker) = connect(|address| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(b"GET / HTTP/1.1\r\n").unwrap();
+        });
+        assert_invalid_message(
+            WorkerConnection::accept(stream, FINGERPRINT, 0).map(|_| ()),
+            "not a worker",
+        );
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn workers_that_disconnect_during_a_step_fail_it() {
+        let (stream, worker) = connect(|address| {
+            let (mut connection, _) = CoordinatorConnection::connect(address, FINGERPRINT).unwrap();
+            connection.next_assignment().unwrap().unwrap();
+            // Sends half of the step.
+            write_string(&mut connection.output, "flip").unwrap();
+            connection.output.flush().unwrap();
+        });
+        let mut connection = WorkerConnection::accept(stream, FINGERPRINT, 0).unwrap();
+        let assignment = Assignment {
+            algorithm_index: 0,
+            seed: 0,
+            num_keys: 1,
+        };
+        let err = connection
+            .accumulate(assignment, &mut NumOccurrences::<u64>::new(4))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let mut message = Vec::new();
+        write_string(&mut message, "").unwrap();
+        write_string(&mut message, "Flip Hash (XXH3, 64 bits)").unwrap();
+        let mut input = message.as_slice();
+        assert_eq!(read_string(&mut input).unwrap(), "");
+        assert_eq!(
+            read_string(&mut input).unwrap(),
+            "Flip Hash (XXH3, 64 bits)"
+        );
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn malformed_strings_are_invalid_messages() {
+        let mut too_long = Vec::new();
+        write_u64(&mut too_long, MAX_STRING_LEN + 1).unwrap();
+        too_long.extend(vec![b'a'; MAX_STRING_LEN as usize + 1]);
+        assert_invalid_message(read_string(&mut too_long.as_slice()), "string too long");
+
+        let mut truncated = Vec::new();
+        write_string(&mut truncated, "flip").unwrap();
+        truncated.pop();
+        assert_invalid_message(read_string(&mut truncated.as_slice()), "truncated string");
+
+        let mut not_utf8 = Vec::new();
+        write_u64(&mut not_utf8, 2).unwrap();
+        not_utf8.extend([0xc3, 0x28]);
+        assert_invalid_message(read_string(&mut not_utf8.as_slice()), "invalid string");
+
+        // The length itself is truncated.
+        let err = read_string(&mut [4, 0, 0].as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,170 @@
+//! Estimates of what a run of an experiment needs, which `estimate` reports
+//! instead of running it, so that infeasible runs are caught before they
+//! exhaust the memory or stop without the power to detect anything: the
+//! memory of the accumulators, the number of cells of the chi-squared test,
+//! e.g., of the cartesian product of the ranges of an independence test, and
+//! the number of keys that the test needs to detect a given effect.
+//!
+//! The number of keys is that of the counts of the test, and is `lambda /
+//! w^2` for an effect size `w`, as Cohen's w, where `lambda` is the
+//! noncentrality of the chi-squared distribution of the statistic under the
+//! effect for which the test has the given power. The noncentral
+//! distribution is approximated with Patnaik's scaled chi-squared one.
+
+use std::fs;
+
+use flip_hash_testkit::exp::{Experiment, TestCells, MIN_EXPECTED_COUNT};
+use serde::Serialize;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+/// The options of the test that the number of keys is estimated for.
+#[derive(clap::Args, Clone, Copy, Debug)]
+pub(crate) struct EstimateOptions {
+    /// The effect size to detect, as Cohen's w, i.e., the square root of the
+    /// chi-squared statistic per key under the effect.
+    #[clap(long, default_value_t = 0.01)]
+    effect_size: f64,
+    /// The significance level of the test.
+    #[clap(long, default_value_t = 0.01)]
+    alpha: f64,
+    /// The probability that the test detects the effect.
+    #[clap(long, default_value_t = 0.8)]
+    power: f64,
+}
+
+/// The estimate of a run, along with the reasons why it is infeasible.
+#[derive(Serialize)]
+pub(crate) struct Estimate {
+    #[serde(rename = "num algorithms")]
+    num_algorithms: usize,
+    #[serde(rename = "num threads")]
+    num_threads: usize,
+    #[serde(rename = "max keys")]
+    max_keys: Option<u64>,
+    /// The bytes of an accumulator of all the keys of an algorithm.
+    #[serde(rename = "accumulator bytes")]
+    accumulator_bytes: f64,
+    /// The bytes of the accumulators of all the algorithms, and of the steps
+    /// of the workers, of which as many again wait to be merged.
+    #[serde(rename = "total bytes")]
+    total_bytes: f64,
+    #[serde(rename = "available bytes", skip_serializing_if = "Option::is_none")]
+    available_bytes: Option<u64>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    test_cells: Option<TestCells>,
+    #[serde(rename = "needed keys", skip_serializing_if = "Option::is_none")]
+    needed_keys: Option<f64>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Estimates the run of `experiment` for `num_algorithms` algorithms with
+/// `num_threads` workers whose steps have `step_size` keys, up to `max_keys`
+/// keys for each algorithm.
+pub(crate) fn estimate(
+    experiment: &impl Experiment,
+    num_algorithms: usize,
+    num_threads: usize,
+    step_size: u64,
+    max_keys: Option<u64>,
+    options: &EstimateOptions,
+) -> Estimate {
+    let accumulator_bytes = experiment.accumulator_memory_bytes(max_keys.unwrap_or(u64::MAX));
+    let step_bytes = experiment.accumulator_memory_bytes(step_size);
+    let total_bytes =
+        num_algorithms as f64 * accumulator_bytes + 2.0 * num_threads as f64 * step_bytes;
+    let available_bytes = available_memory_bytes();
+    let test_cells = experiment.test_cells();
+    let needed_keys = test_cells.map(|cells| {
+        (noncentrality(cells.degrees_of_freedom, options.alpha, options.power)
+            / options.effect_size.powi(2))
+        .ceil()
+    });
+
+    let mut warnings = Vec::new();
+    if let Some(available_bytes) = available_bytes {
+        if total_bytes > available_bytes as f64 {
+            warnings.push(format!(
+                "The accumulators need {total_bytes:.3e} bytes, but {available_bytes:.3e} are \
+                 available"
+            ));
+        }
+    }
+    if let (Some(needed_keys), Some(max_keys)) = (needed_keys, max_keys) {
+        if needed_keys > max_keys as f64 {
+            warnings.push(format!(
+                "The test needs {needed_keys:.3e} keys to detect an effect size of {} with a \
+                 power of {}, but --max-keys is {max_keys}",
+                options.effect_size, options.power
+            ));
+        }
+    }
+    if let (Some(cells), Some(max_keys)) = (test_cells, max_keys) {
+        if (max_keys as f64) < MIN_EXPECTED_COUNT * cells.num_cells {
+            warnings.push(format!(
+                "The expected counts of the {:.3e} cells are below {MIN_EXPECTED_COUNT} with \
+                 --max-keys {max_keys}, so that the values are merged into fewer cells, which \
+                 lose power",
+                cells.num_cells
+            ));
+        }
+    }
+
+    Estimate {
+        num_algorithms,
+        num_threads,
+        max_keys,
+        accumulator_bytes,
+        total_bytes,
+        available_bytes,
+        test_cells,
+        needed_keys,
+        warnings,
+    }
+}
+
+/// Returns the noncentrality for which the chi-squared test with
+/// `degrees_of_freedom` at the significance level `alpha` has `power`, by
+/// bisection.
+fn noncentrality(degrees_of_freedom: f64, alpha: f64, power: f64) -> f64 {
+    assert!(degrees_of_freedom > 0.0);
+    assert!(alpha > 0.0 && alpha < 1.0 && power > alpha && power < 1.0);
+    let critical_value = ChiSquared::new(degrees_of_freedom)
+        .unwrap()
+        .inverse_cdf(1.0 - alpha);
+    let power_of = |noncentrality: f64| {
+        // Patnaik's approximation of the noncentral chi-squared distribution
+        // by `c` times a chi-squared one with `h` degrees of freedom.
+        let c = (degrees_of_freedom + 2.0 * noncentrality) / (degrees_of_freedom + noncentrality);
+        let h = (degrees_of_freedom + noncentrality).powi(2)
+            / (degrees_of_freedom + 2.0 * noncentrality);
+        1.0 - ChiSquared::new(h).unwrap().cdf(critical_value / c)
+    };
+    let mut high = 1.0;
+    while power_of(high) < power {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    while high - low > 1e-6 * high {
+        let middle = (low + high) / 2.0;
+        if power_of(middle) < power {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    high
+}
+
+/// Returns the memory that is available to new processes, on Linux.
+fn available_memory_bytes() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let kibibytes = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kibibytes * 1024)
+}
@@ -0,0 +1,76 @@
+//! The per-value counts of the hashes of an experiment, which are written at
+//! the end of a run for offline analysis, e.g., of the spatial structure of a
+//! bias, which summaries do not show.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+/// The extension of the files of counts, which are not result files.
+pub(crate) const EXTENSION: &str = "counts";
+
+/// The formats of the files of counts.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum HistogramFormat {
+    /// A `value,count` line per value, after a `#` comment line with the
+    /// metadata of the run.
+    Csv,
+    /// The counts, as 64-bit little-endian integers, e.g., to read with
+    /// `numpy.fromfile(path, dtype="<u8")`.
+    Binary,
+}
+
+/// The counts of the values of an experiment to write, and their format.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HistogramOptions {
+    pub(crate) format: HistogramFormat,
+    pub(crate) num_values: usize,
+}
+
+impl HistogramOptions {
+    /// Returns the path of the counts of the algorithm `algo_id` of the
+    /// output file at `output_path`.
+    pub(crate) fn path(&self, output_path: &Path, algo_id: &str) -> PathBuf {
+        let mut path = output_path.as_os_str().to_owned();
+        path.push(format!(".{algo_id}.{EXTENSION}"));
+        if let HistogramFormat::Csv = self.format {
+            path.push(".csv");
+        }
+        PathBuf::from(path)
+    }
+
+    /// Writes the counts of all the values, given the nonzero ones by index
+    /// of value, to `path`.
+    pub(crate) fn write(
+        &self,
+        path: &Path,
+        nonzero_counts: &[(usize, u64)],
+        metadata: &impl Serialize,
+    ) -> io::Result<()> {
+        let mut counts = vec![0; self.num_values];
+        for &(index, count) in nonzero_counts {
+            counts[index] = count;
+        }
+        let mut output = BufWriter::new(File::create(path)?);
+        match self.format {
+            HistogramFormat::Csv => {
+                output.write_all(b"# ")?;
+                serde_json::to_writer(&mut output, metadata)?;
+                writeln!(output, "\nvalue,count")?;
+                for (value, count) in counts.iter().enumerate() {
+                    writeln!(output, "{value},{count}")?;
+                }
+            }
+            HistogramFormat::Binary => {
+                for count in counts {
+                    output.write_all(&count.to_le_bytes())?;
+                }
+            }
+        }
+        output.flush()
+    }
+}
@@ -1,7 +1,5 @@
 use std::ops::RangeToInclusive;
 
-mod algo;
-
 #[inline]
 pub fn jump_hash(key: u64, range: RangeToInclusive<u32>) -> u32 {
     let mut k = key;
@@ -13,3 +11,450 @@ pub fn jump_hash(key: u64, range: RangeToInclusive<u32>) -> u32 {
     }
     b as u32
 }
+
+/// Jump hash of `key` under `seed` to a 64-bit range, which is that of
+/// [`jump_hash`] under seed 0 for 32-bit ranges, so that it can be compared
+/// with Flip Hash on the same seeds and ranges.
+///
+/// The seed is mixed before being XORed into the key, so that seeds that
+/// differ in a few bits, e.g., sequential ones, give unrelated hashes.
+#[inline]
+pub fn jump_hash_64_with_seed(key: u64, seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    // The jumps at or beyond this one overflow the range of any key.
+    const MAX_JUMP: f64 = 18446744073709551616.0;
+    let mut k = key ^ fmix64(seed);
+    let mut b = 0;
+    loop {
+        k = k.wrapping_mul(2862933555777941757).wrapping_add(1);
+        let j = (b as f64 + 1.0) * (f64::from(1_u32 << 31) / ((k >> 33) + 1) as f64);
+        if j >= MAX_JUMP || j as u64 > range.end {
+            return b;
+        }
+        b = j as u64;
+    }
+}
+
+/// AnchorHash (Mendelson et al., 2021), with a capacity of buckets, among
+/// which the working ones are the first ones, as if the others had been
+/// removed from last to first.
+#[derive(Clone, Debug)]
+pub struct AnchorHash {
+    /// The number of working buckets when each bucket was removed, which is 0
+    /// for working buckets.
+    removed_at: Vec<u32>,
+    /// The bucket that replaced each removed bucket.
+    replacements: Vec<u32>,
+}
+
+impl AnchorHash {
+    /// # Panics
+    ///
+    /// Panics if `range.end` is not less than `capacity`.
+    pub fn new(capacity: u32, range: RangeToInclusive<u32>) -> Self {
+        assert!(range.end < capacity);
+        let num_working = range.end + 1;
+        Self {
+            removed_at: (0..capacity)
+                .map(|bucket| if bucket < num_working { 0 } else { bucket })
+                .collect(),
+            replacements: (0..capacity).collect(),
+        }
+    }
+
+    #[inline]
+    pub fn hash(&self, key: u64) -> u32 {
+        let mut b = reduce(fmix64(key), self.removed_at.len() as u32);
+        while self.removed_at[b as usize] > 0 {
+            let removed_at = self.removed_at[b as usize];
+            let mut h = reduce(fmix64(key ^ fmix64(u64::from(b) + 1)), removed_at);
+            while self.removed_at[h as usize] >= removed_at {
+                h = self.replacements[h as usize];
+            }
+            b = h;
+        }
+        b
+    }
+}
+
+/// Rendezvous hashing, i.e., highest random weight hashing (Thaler and
+/// Ravishankar, 1996), which maps a key to the value of the highest score of
+/// the key and the value, in time linear in the number of values.
+#[inline]
+pub fn rendezvous_hash(key: u64, range: RangeToInclusive<u32>) -> u32 {
+    (0..=range.end)
+        .max_by_key(|&value| rendezvous_score(key, value))
+        .unwrap()
+}
+
+/// Weighted rendezvous hashing, with the logarithmic method (Schindelhauer and
+/// Schomaker, 2005), which maps a key to a value with a probability
+/// proportional to its weight.
+///
+/// # Panics
+///
+/// Panics if there are no weights.
+#[inline]
+pub fn weighted_rendezvous_hash(key: u64, weights: &[f64]) -> u32 {
+    weights
+        .iter()
+        .enumerate()
+        .map(|(value, weight)| {
+            // A uniform draw from (0, 1).
+            let u =
+                ((rendezvous_score(key, value as u32) >> 11) as f64 + 0.5) / (1_u64 << 53) as f64;
+            (value as u32, -weight / u.ln())
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
+        .0
+}
+
+#[inline]
+fn rendezvous_score(key: u64, value: u32) -> u64 {
+    fmix64(key ^ fmix64(u64::from(value)))
+}
+
+/// The finalizer of MurmurHash3, which is a bijection of 64-bit integers.
+#[inline]
+fn fmix64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51afd7ed558ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ceb9fe1a85ec53);
+    x ^ (x >> 33)
+}
+
+/// Maps a hash to `0..n` with a multiplication rather than a division.
+#[inline]
+fn reduce(hash: u64, n: u32) -> u32 {
+    ((u128::from(hash) * u128::from(n)) >> 64) as u32
+}
+
+/// DxHash (Dong and Wang, 2021), with a power of two of buckets, among which
+/// the working ones are the first ones. A key is mapped to the first working
+/// bucket of a pseudo-random sequence seeded with it.
+#[derive(Clone, Debug)]
+pub struct DxHash {
+    /// The bitmap of the working buckets.
+    working: Vec<u64>,
+    mask: u64,
+}
+
+impl DxHash {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is not a power of two, or if `range.end` is not
+    /// less than `capacity`.
+    pub fn new(capacity: u32, range: RangeToInclusive<u32>) -> Self {
+        assert!(capacity.is_power_of_two());
+        assert!(range.end < capacity);
+        let mut working = vec![0; (capacity as usize).div_ceil(64)];
+        for bucket in 0..=range.end as usize {
+            working[bucket / 64] |= 1 << (bucket % 64);
+        }
+        Self {
+            working,
+            mask: u64::from(capacity) - 1,
+        }
+    }
+
+    #[inline]
+    pub fn hash(&self, key: u64) -> u32 {
+        // The sequence of SplitMix64, whose state is the key.
+        let mut state = key;
+        loop {
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let bucket = (fmix64(state) & self.mask) as usize;
+            if self.working[bucket / 64] & (1 << (bucket % 64)) != 0 {
+                return bucket as u32;
+            }
+        }
+    }
+}
+
+/// Maglev hashing (Eisenbud et al., 2016), whose lookup table maps hashes of
+/// keys to values, and is filled by letting each value in turn take the next
+/// free entry of its own permutation of the entries.
+#[derive(Clone, Debug)]
+pub struct Maglev {
+    table: Vec<u32>,
+}
+
+impl Maglev {
+    /// Builds the lookup table of the values of `range`, whose size is the
+    /// smallest prime that is at least `min_table_size`, so that the
+    /// permutations of the values cover all the entries. The permutations are
+    /// derived from `seed`, which plays the role of the names of the values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the table is not more than `range.end`, or does
+    /// not fit in 32 bits.
+    pub fn new(min_table_size: u32, seed: u64, range: RangeToInclusive<u32>) -> Self {
+        let table_size = (min_table_size.max(2)..=u32::MAX)
+            .find(|&n| is_prime(n))
+            .expect("No prime table size fits in 32 bits");
+        assert!(range.end < table_size);
+        let table_size = u64::from(table_size);
+        // The current entry of the permutation of each value, and its step.
+        let (mut entries, skips): (Vec<_>, Vec<_>) = (0..=u64::from(range.end))
+            .map(|value| {
+                let hash = fmix64(fmix64(seed) ^ value);
+                (hash % table_size, fmix64(hash) % (table_size - 1) + 1)
+            })
+            .unzip();
+        let mut table = vec![u32::MAX; table_size as usize];
+        let mut num_filled = 0;
+        loop {
+            for (value, (entry, skip)) in entries.iter_mut().zip(&skips).enumerate() {
+                while table[*entry as usize] != u32::MAX {
+                    *entry = (*entry + skip) % table_size;
+                }
+                table[*entry as usize] = value as u32;
+                num_filled += 1;
+                if num_filled == table.len() {
+                    return Self { table };
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn hash(&self, key: u64) -> u32 {
+        self.table[reduce(fmix64(key), self.table.len() as u32) as usize]
+    }
+}
+
+fn is_prime(n: u32) -> bool {
+    n >= 2
+        && (2..)
+            .take_while(|d| d * d <= u64::from(n))
+            .all(|d| u64::from(n) % d != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const NUM_KEYS: u64 = 100_000;
+
+    fn keys() -> impl Iterator<Item = u64> {
+        (0..NUM_KEYS).map(|i| fmix64(i ^ 0x5851f42d4c957f2d))
+    }
+
+    /// Asserts that the hashes of the keys are in `range`, and that the count
+    /// of every value is within 10% of the expected one, which is at least 8
+    /// standard deviations for the ranges of at most 16 values of the tests.
+    fn assert_uniform(hash: impl Fn(u64) -> u32, range: RangeToInclusive<u32>) {
+        let mut counts = vec![0_u64; range.end as usize + 1];
+        for key in keys() {
+            let value = hash(key);
+            assert!(range.contains(&value), "{value} is not in {range:?}");
+            counts[value as usize] += 1;
+        }
+        let expected = NUM_KEYS as f64 / counts.len() as f64;
+        for (value, &count) in counts.iter().enumerate() {
+            assert!(
+                (count as f64 - expected).abs() <= 0.1 * expected,
+                "{value} has {count} keys, expected {expected}"
+            );
+        }
+    }
+
+    /// Asserts that the keys that do not keep their values when the range
+    /// grows by one move to the new value, and that they are roughly as many
+    /// as the keys of any other value.
+    fn assert_consistent(
+        hash: impl Fn(u64) -> u32,
+        grown_hash: impl Fn(u64) -> u32,
+        range: RangeToInclusive<u32>,
+    ) {
+        let mut num_moved = 0;
+        for key in keys() {
+            let (value, grown_value) = (hash(key), grown_hash(key));
+            if grown_value != value {
+                assert_eq!(grown_value, range.end + 1, "key {key} moved from {value}");
+                num_moved += 1;
+            }
+        }
+        let expected = NUM_KEYS as f64 / f64::from(range.end + 2);
+        assert!((f64::from(num_moved) - expected).abs() <= 0.1 * expected);
+    }
+
+    #[test]
+    fn anchor_hash_is_uniform() {
+        for end in [0, 1, 6, 15] {
+            let anchor_hash = AnchorHash::new(16, ..=end);
+            assert_uniform(|key| anchor_hash.hash(key), ..=end);
+        }
+        let anchor_hash = AnchorHash::new(1000, ..=9);
+        assert_uniform(|key| anchor_hash.hash(key), ..=9);
+    }
+
+    #[test]
+    fn anchor_hash_is_consistent() {
+        for end in 0..15 {
+            let (anchor_hash, grown_anchor_hash) =
+                (AnchorHash::new(16, ..=end), AnchorHash::new(16, ..=end + 1));
+            assert_consistent(
+                |key| anchor_hash.hash(key),
+                |key| grown_anchor_hash.hash(key),
+                ..=end,
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn anchor_hash_range_is_within_capacity() {
+        AnchorHash::new(16, ..=16);
+    }
+
+    #[test]
+    fn dx_hash_is_uniform() {
+        for end in [0, 1, 6, 15] {
+            let dx_hash = DxHash::new(16, ..=end);
+            assert_uniform(|key| dx_hash.hash(key), ..=end);
+        }
+        let dx_hash = DxHash::new(1024, ..=9);
+        assert_uniform(|key| dx_hash.hash(key), ..=9);
+    }
+
+    #[test]
+    fn dx_hash_is_consistent() {
+        for end in 0..15 {
+            let (dx_hash, grown_dx_hash) = (DxHash::new(16, ..=end), DxHash::new(16, ..=end + 1));
+            assert_consistent(
+                |key| dx_hash.hash(key),
+                |key| grown_dx_hash.hash(key),
+                ..=end,
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn dx_hash_capacity_is_a_power_of_two() {
+        DxHash::new(12, ..=5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dx_hash_range_is_within_capacity() {
+        DxHash::new(16, ..=16);
+    }
+
+    #[test]
+    fn maglev_table_size_is_the_next_prime() {
+        for (min_table_size, table_size) in [(0, 2), (2, 2), (4, 5), (100, 101), (101, 101)] {
+            assert_eq!(Maglev::new(min_table_size, 0, ..=0).table.len(), table_size);
+        }
+        assert_eq!(Maglev::new(65536, 0, ..=9).table.len(), 65537);
+    }
+
+    #[test]
+    fn maglev_table_is_balanced() {
+        for (min_table_size, end) in [(2, 1), (101, 0), (101, 6), (101, 100), (65537, 999)] {
+            for seed in 0..4 {
+                let maglev = Maglev::new(min_table_size, seed, ..=end);
+                let mut counts = vec![0; end as usize + 1];
+                for &value in &maglev.table {
+                    assert!(value <= end, "{value} is not in ..={end}");
+                    counts[value as usize] += 1;
+                }
+                assert_eq!(counts.iter().sum::<usize>(), maglev.table.len());
+                let (min, max) = (counts.iter().min().unwrap(), counts.iter().max().unwrap());
+                assert!(max - min <= 1, "{min} to {max} entries per value");
+            }
+        }
+    }
+
+    #[test]
+    fn maglev_is_uniform() {
+        for end in [0, 1, 6, 15] {
+            let maglev = Maglev::new(65537, 0, ..=end);
+            assert_uniform(|key| maglev.hash(key), ..=end);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn maglev_range_is_within_table_size() {
+        Maglev::new(5, 0, ..=5);
+    }
+
+    #[test]
+    fn rendezvous_hash_is_uniform() {
+        for end in [0, 1, 6, 15] {
+            assert_uniform(|key| rendezvous_hash(key, ..=end), ..=end);
+            let weights = vec![2.5; end as usize + 1];
+            assert_uniform(|key| weighted_rendezvous_hash(key, &weights), ..=end);
+        }
+    }
+
+    #[test]
+    fn rendezvous_hash_is_consistent() {
+        for end in 0..15 {
+            assert_consistent(
+                |key| rendezvous_hash(key, ..=end),
+                |key| rendezvous_hash(key, ..=end + 1),
+                ..=end,
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_rendezvous_hash_is_proportional() {
+        let weights = [1.0, 2.0, 3.0, 4.0, 0.5, 9.5];
+        let mut counts = [0_u64; 6];
+        for key in keys() {
+            counts[weighted_rendezvous_hash(key, &weights) as usize] += 1;
+        }
+        let total_weight: f64 = weights.iter().sum();
+        for (&weight, &count) in weights.iter().zip(&counts) {
+            let expected = NUM_KEYS as f64 * weight / total_weight;
+            assert!(
+                (count as f64 - expected).abs() <= 0.1 * expected,
+                "{count} keys of weight {weight}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_rendezvous_hash_only_moves_the_keys_of_removed_values() {
+        let weights = [1.0, 2.0, 3.0, 4.0, 0.5, 9.5];
+        for removed in 0..weights.len() {
+            // A value of zero weight is never hashed to, as if it was removed.
+            let mut remaining_weights = weights;
+            remaining_weights[removed] = 0.0;
+            for key in keys() {
+                let value = weighted_rendezvous_hash(key, &weights);
+                let remaining_value = weighted_rendezvous_hash(key, &remaining_weights);
+                assert_ne!(remaining_value as usize, removed);
+                if value as usize != removed {
+                    assert_eq!(remaining_value, value, "key {key} moved from {value}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn jump_hash_64_with_seed_0_is_jump_hash() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let ends = [0, 1, 2, 1000, u32::MAX - 1, u32::MAX]
+            .into_iter()
+            .chain((0..100).map(|_| rng.gen()));
+        for end in ends.collect::<Vec<u32>>() {
+            for _ in 0..1000 {
+                let key = rng.gen();
+                assert_eq!(
+                    jump_hash_64_with_seed(key, 0, ..=u64::from(end)),
+                    u64::from(jump_hash(key, ..=end)),
+                    "key {key}, range ..={end}"
+                );
+            }
+        }
+    }
+}
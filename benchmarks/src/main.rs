@@ -1,34 +1,557 @@
 //extern crate clap;
 use std::{
-    collections::HashMap,
-    fmt,
+    collections::{HashMap, HashSet},
+    env, fmt,
     fs::{create_dir_all, File},
-    io::Write,
-    sync::mpsc,
+    io::{self, Write},
+    iter,
+    net::TcpListener,
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    ops::RangeToInclusive,
+    panic,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, SyncSender},
+    },
     thread,
+    time::{Duration, Instant},
 };
 
-mod acc;
 mod algo;
-mod exp;
+mod batches;
+mod compare;
+mod config;
+mod convergence;
+mod counters;
+mod distributed;
+mod estimate;
+mod histogram;
+mod manifest;
+mod metadata;
+mod metrics;
+mod plot;
+mod profile;
+mod progress;
+mod report;
+mod seeds;
+mod snapshot;
+mod sweep;
+mod thresholds;
 
-use acc::Accumulator;
-use algo::{FlipHash64, FlipHashXXH3128, FlipHashXXH364, JumpHash};
-use clap::Parser;
-use exp::{Collisions, Experiment, IndependenceAcrossRanges, IndependenceAcrossSeeds, Regularity};
-use flip_hash_benchmarks::jump_hash;
+use algo::{
+    AnchorHash, DxHash, FlipHash64, FlipHashXXH3128, FlipHashXXH364, JumpHash, Ketama, Maglev,
+    Rendezvous, Reseeded, WeightedRendezvous,
+};
+use batches::{BatchPValues, BatchSummary};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use compare::{compare_samples, Sample};
+use config::Campaign;
+use convergence::{Convergence, ConvergenceOptions};
+use counters::PerfCounters;
+use distributed::{Assignment, CoordinatorConnection, WorkerConnection};
+use estimate::EstimateOptions;
+use flip_hash_testkit::{
+    acc::Accumulator,
+    checkpoint::{read_checkpoint, write_checkpoint},
+    exp::{
+        Avalanche, Collisions, CollisionsSketch, Consistency, Experiment, GoodnessOfFitTest,
+        IndependenceAcrossRanges, IndependenceAcrossSeeds, KeyRelation, Latency, MaxLoad,
+        Regularity, Remap, SeedAvalanche, SerialCorrelation, Shrink, Speed,
+    },
+    keys::{KeyOptions, Keys},
+    rng::{self, thread_rng},
+};
+use histogram::{HistogramFormat, HistogramOptions};
 use itertools::Itertools;
+use manifest::Manifest;
+use metadata::{Metadata, MetadataRecord};
+use plot::ImageFormat;
+use progress::Progress;
+use rand::RngCore;
+use seeds::{SeedSummary, SeedSweep};
+use serde::Serialize;
+use snapshot::Snapshots;
+use sweep::{range_sweep, write_range_biases, RangeSweepSummary};
+use thresholds::Thresholds;
 
 const RESULT_DIR: &str = "results";
+/// The output path of the standard output.
+const STDOUT_PATH: &str = "-";
+/// Whether experiments are interrupted, e.g., by Ctrl-C, in which case their
+/// workers stop after their current steps.
+static STOP: AtomicBool = AtomicBool::new(false);
+/// Whether the final results of an algorithm do not meet the thresholds, in
+/// which case the benchmarks exit with a non-zero code.
+static FAILED: AtomicBool = AtomicBool::new(false);
 const DEFAULT_ALGORITHMS: [Algorithm; 4] = [
     Algorithm::FlipHash64,
     Algorithm::FlipHashXXH364,
     Algorithm::FlipHashXXH3128,
     Algorithm::JumpHash,
 ];
+/// The algorithms of `remap`, which compares the moves of Flip Hash with
+/// those of the baselines.
+const REMAP_ALGORITHMS: [Algorithm; 8] = [
+    Algorithm::FlipHash64,
+    Algorithm::JumpHash,
+    Algorithm::Ketama,
+    Algorithm::AnchorHash,
+    Algorithm::DxHash,
+    Algorithm::Maglev,
+    Algorithm::Rendezvous,
+    Algorithm::WeightedRendezvous,
+];
 
 #[derive(Parser, Debug)]
+struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+    /// Runs the experiments that a TOML file declares, with the options of
+    /// their runs, one after the other, instead of a command. See
+    /// `src/config.rs` for the format.
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// Seeds the random number generators, so that the results can be
+    /// reproduced, given the same number of threads. Otherwise, the seed is
+    /// drawn from the entropy of the system, and recorded in the metadata and
+    /// manifests of the runs.
+    #[clap(long, global = true)]
+    rng_seed: Option<u64>,
+    /// Serves the metrics of the experiments at this address, e.g.,
+    /// 0.0.0.0:9090, at any path, in the text format of Prometheus: the number
+    /// of keys, the p-values and the throughput of each algorithm.
+    #[clap(long, global = true)]
+    metrics_address: Option<String>,
+    #[clap(flatten)]
+    run_options: RunOptions,
+    #[clap(flatten)]
+    output_options: OutputOptions,
+}
+
+/// The options of the runs of experiments.
+#[derive(clap::Args, Clone, Debug)]
+struct RunOptions {
+    /// The number of worker threads, which is one fewer than the available
+    /// parallelism by default, and may be larger, e.g., to study throughput
+    /// under oversubscription.
+    #[clap(long, global = true)]
+    threads: Option<NonZeroUsize>,
+    /// Stops after hashing this number of keys for each algorithm.
+    #[clap(long, global = true)]
+    max_keys: Option<u64>,
+    /// Stops after this duration, e.g., 90s, 30m, 12h or 2d, once the
+    /// workers finish their current steps.
+    #[clap(long, global = true, value_parser = parse_duration)]
+    max_duration: Option<Duration>,
+    /// Writes the accumulated counts to a checkpoint, next to the output
+    /// file with the `.checkpoint` extension, at this interval, e.g., 10m,
+    /// and when stopping.
+    #[clap(long, global = true, value_parser = parse_duration)]
+    checkpoint_interval: Option<Duration>,
+    /// Writes a snapshot of the summaries of all the keys, and of those
+    /// hashed since the previous snapshot, next to the output file with the
+    /// `.snapshot` extension, along with a checkpoint, at this interval,
+    /// e.g., 1h, so that runs without an end, e.g., quality monitors, can be
+    /// inspected, and resumed from the checkpoint, at any time.
+    #[clap(long, global = true, value_parser = parse_duration)]
+    snapshot_interval: Option<Duration>,
+    /// Hashes keys in batches of this size, and reports the Kolmogorov-Smirnov
+    /// test of the uniformity of the p-values of the batches of this run
+    /// along with the summaries, which stays sound with a huge number of
+    /// keys, unlike the p-value of all the keys.
+    #[clap(long, global = true, value_name = "BATCH_SIZE")]
+    batch_p_values: Option<NonZeroU64>,
+    /// The minimum number of entries of the lookup tables of Maglev, which
+    /// is rounded up to a prime. It is 100 times the number of values of
+    /// each range by default.
+    #[clap(long, global = true)]
+    maglev_table_size: Option<NonZeroU32>,
+    /// Resumes an experiment from the counts of a checkpoint of the same
+    /// experiment, appending to its output file. `--max-keys` applies to
+    /// the keys hashed after resuming.
+    #[clap(long, global = true)]
+    resume: Option<PathBuf>,
+    /// What is done with the experiment, which `merge-results`, `compare`,
+    /// `estimate`, `coordinate` and `work` set.
+    #[clap(skip)]
+    mode: Mode,
+    /// The number of random seeds that the steps of each algorithm are hashed
+    /// under in turn, which the experiments that sweep seeds set.
+    #[clap(skip)]
+    num_seeds: Option<NonZeroUsize>,
+    /// The counts of the values to write at the end of the run, which the
+    /// experiments that count values set.
+    #[clap(skip)]
+    histogram: Option<HistogramOptions>,
+    /// The end of the largest range that the experiment hashes to, which the
+    /// experiments that grow ranges set, so that the buckets of AnchorHash and
+    /// DxHash, and the lookup tables of Maglev, are provisioned for it in all
+    /// the ranges, as they are in deployments that grow.
+    #[clap(skip)]
+    max_range_end: Option<u64>,
+    /// The identity of the experiment in its checkpoints, which those that
+    /// are resumed or merged must have.
+    #[clap(skip)]
+    checkpoint_experiment: String,
+    #[clap(flatten)]
+    convergence: ConvergenceOptions,
+    #[clap(flatten)]
+    thresholds: Thresholds,
+}
+
+/// What is done with an experiment.
+#[derive(Clone, Debug, Default)]
+enum Mode {
+    /// Runs it.
+    #[default]
+    Run,
+    /// Merges checkpoints of it.
+    Merge(Vec<PathBuf>),
+    /// Compares the counts of two samples of checkpoints of it.
+    Compare([Sample; 2]),
+    /// Estimates what running it needs.
+    Estimate(EstimateOptions),
+    /// Runs it with the workers of other hosts, which connect to the address,
+    /// and must have the fingerprint of the experiment, instead of local ones.
+    Coordinate {
+        address: String,
+        fingerprint: String,
+    },
+    /// Accumulates steps of it for the coordinator at the address, which must
+    /// have the fingerprint of the experiment.
+    Work {
+        coordinator: String,
+        fingerprint: String,
+    },
+}
+
+impl RunOptions {
+    fn maglev_table_size(&self) -> Option<u32> {
+        self.maglev_table_size.map(NonZeroU32::get)
+    }
+
+    fn num_threads(&self) -> usize {
+        self.threads.map_or_else(
+            || {
+                usize::from(thread::available_parallelism().unwrap())
+                    .saturating_sub(1)
+                    .max(1)
+            },
+            NonZeroUsize::get,
+        )
+    }
+}
+
+/// The options of the output files of experiments.
+#[derive(clap::Args, Clone, Debug)]
+struct OutputOptions {
+    /// The directory of the output files, which are written to a
+    /// subdirectory per experiment, and named after its parameters.
+    #[clap(long, global = true, default_value = RESULT_DIR)]
+    output_dir: PathBuf,
+    /// The output file, instead of one in the output directory, or `-` for
+    /// the standard output, e.g., to pipe results to another tool.
+    #[clap(long, global = true)]
+    output: Option<PathBuf>,
+}
+
+impl OutputOptions {
+    /// Returns the path of the output file of an experiment, which is
+    /// `file_name` in the `experiment_dir` subdirectory of the output
+    /// directory, unless `--output` is set.
+    fn output_path(&self, experiment_dir: &str, file_name: String) -> PathBuf {
+        if let Some(output) = &self.output {
+            return output.clone();
+        }
+        let output_dir = self.output_dir.join(experiment_dir);
+        create_dir_all(&output_dir).unwrap();
+        output_dir.join(file_name)
+    }
+}
+
+/// Opens the output file at `path`, or the standard output if `path` is
+/// `-`, appending to the file if `append` is set.
+fn open_output(path: &Path, append: bool) -> io::Result<Box<dyn Write>> {
+    if path == Path::new(STDOUT_PATH) {
+        return Ok(Box::new(io::stdout()));
+    }
+    let file = if append {
+        File::options().append(true).create(true).open(path)?
+    } else {
+        File::create(path)?
+    };
+    Ok(Box::new(file))
+}
+
+/// Parses a number of seconds, minutes, hours or days, e.g., 90s or 1.5h.
+fn parse_duration(arg: &str) -> Result<Duration, String> {
+    let (number, unit_secs) = match arg.char_indices().last() {
+        Some((index, 's')) => (&arg[..index], 1.0),
+        Some((index, 'm')) => (&arg[..index], 60.0),
+        Some((index, 'h')) => (&arg[..index], 3600.0),
+        Some((index, 'd')) => (&arg[..index], 86400.0),
+        _ => return Err("expected a unit among s, m, h and d".to_owned()),
+    };
+    let number = number.parse::<f64>().map_err(|err| err.to_string())?;
+    Duration::try_from_secs_f64(number * unit_secs).map_err(|err| err.to_string())
+}
+
+#[derive(Subcommand, Debug)]
 enum Command {
+    #[clap(flatten)]
+    Experiment(ExperimentCommand),
+    /// Merges the checkpoints of runs of an experiment, e.g., on separate
+    /// machines, and writes the summaries of the merged counts, and a merged
+    /// checkpoint, to the `merged` subdirectory of the output directory,
+    /// instead of running the experiment, which follows with its
+    /// arguments, e.g., `merge-results -c a.checkpoint -c b.checkpoint
+    /// regularity -r 99 -i 8`. All the algorithms of the checkpoints are
+    /// merged.
+    MergeResults {
+        /// A checkpoint to merge, of a run of the experiment with the same
+        /// arguments.
+        #[clap(short, long = "checkpoint", required = true)]
+        checkpoints: Vec<PathBuf>,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Compares the counts of two samples of an experiment, which are the
+    /// counts of algorithms in checkpoints, with a chi-squared test of
+    /// homogeneity, and reports the sizes of their difference, along with
+    /// the summaries of the samples, on the standard output by default. The
+    /// experiment follows with its arguments, e.g., `compare
+    /// --first-checkpoint a.checkpoint --first-algorithm flip-hash64
+    /// --second-algorithm jump-hash regularity -r 99 -i 8`.
+    Compare {
+        #[clap(long)]
+        first_checkpoint: PathBuf,
+        #[clap(long)]
+        first_algorithm: Algorithm,
+        /// The checkpoint of the second sample, which is the first one by
+        /// default, to compare two algorithms of a run.
+        #[clap(long)]
+        second_checkpoint: Option<PathBuf>,
+        /// The algorithm of the second sample, which is the first one by
+        /// default, to compare two runs of an algorithm.
+        #[clap(long)]
+        second_algorithm: Option<Algorithm>,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Estimates what a run of an experiment with the same options needs,
+    /// instead of running it, on the standard output by default: the memory
+    /// of its accumulators at `--max-keys`, the cells of its chi-squared test,
+    /// e.g., the tuples of the cartesian product of the ranges of an
+    /// independence test, and the number of keys that the test needs to
+    /// detect an effect with a given power. Warns, and exits with an error,
+    /// if the run would exhaust the available memory, or stop before it has
+    /// enough keys. The experiment follows with its arguments, e.g.,
+    /// `estimate --max-keys 1000000000 independence-across-ranges -r 99 -r
+    /// 199 -r 299 -i 8`.
+    Estimate {
+        #[clap(flatten)]
+        options: EstimateOptions,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Runs an experiment with workers on other hosts, which `work` runs,
+    /// instead of local ones: hands out their steps, merges the accumulators
+    /// that they send back over TCP, and writes the summaries, as a local run
+    /// does. The experiment follows with its arguments, e.g., `coordinate
+    /// --listen 0.0.0.0:7070 independence-across-ranges -r 9 -r 19 -r 29 -i
+    /// 8`. The options of the run, e.g., `--max-keys`, are those of the
+    /// coordinator, apart from `--threads`, which is that of each worker.
+    Coordinate {
+        /// The address that workers connect to.
+        #[clap(long)]
+        listen: String,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Runs `--threads` workers for the coordinator of an experiment, which
+    /// `coordinate` runs with the same arguments, until it has no steps left.
+    /// The experiment follows with its arguments, e.g., `work --coordinator
+    /// host:7070 independence-across-ranges -r 9 -r 19 -r 29 -i 8`.
+    Work {
+        /// The address of the coordinator.
+        #[clap(long)]
+        coordinator: String,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Runs an experiment for each of the given input sizes, one after the
+    /// other, and writes a result file per size to the output directory, e.g.,
+    /// to characterize short keys. The experiment follows with its arguments,
+    /// whose input size is replaced, e.g., `input-size-sweep -i 4 -i 8 -i 16
+    /// -i 64 -i 1024 regularity -r 99 -i 8`. `--max-duration` is shared by the
+    /// sizes, and `--max-keys` applies to each of them.
+    InputSizeSweep {
+        #[clap(short, long = "input-size-bytes", required = true)]
+        input_sizes_bytes: Vec<usize>,
+        #[clap(subcommand)]
+        experiment: ExperimentCommand,
+    },
+
+    /// Draws charts of result files, next to them: the metrics of the
+    /// summaries as keys are hashed, the throughputs of `speed`, and the
+    /// biases of `range-sweep`.
+    Plot {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+        #[clap(long, value_enum, default_value_t)]
+        format: ImageFormat,
+        /// A TrueType font for the text of charts, which is a system font by
+        /// default.
+        #[clap(long)]
+        font: Option<PathBuf>,
+    },
+    /// Runs the battery of experiments: regularity, collisions, independence
+    /// across ranges and across seeds, avalanche and consistency, one after
+    /// the other, and writes their results to the `suite` subdirectory of the
+    /// output directory, along with a report of them, with their p-values
+    /// adjusted for multiple testing, which is written to `--output` if set.
+    /// `--max-duration` is shared by the experiments, and `--max-keys`
+    /// applies to each of them.
+    Suite {
+        /// The range end of the experiments, apart from the independence
+        /// ones, which use small ranges.
+        #[clap(short, long, default_value_t = 999)]
+        range_end: u64,
+        #[clap(short, long, default_value_t = 8)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+        /// A TrueType font for the text of the charts of the report, which is
+        /// a system font by default.
+        #[clap(long)]
+        font: Option<PathBuf>,
+    },
+    /// Hashes keys with an algorithm on a single thread for `--duration`,
+    /// under a sampling profiler, and writes the flamegraph of its stacks, as
+    /// an SVG image, to the `profile` subdirectory of the output directory,
+    /// or to `--output`. Only available on Unix.
+    Profile {
+        #[clap(short, long, default_value_t = Algorithm::FlipHash64)]
+        algorithm: Algorithm,
+        #[clap(short, long, default_value_t = 999)]
+        range_end: u64,
+        #[clap(short, long, default_value_t = 8)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        /// How long keys are hashed, e.g., 10s or 2m.
+        #[clap(long, default_value = "10s", value_parser = parse_duration)]
+        duration: Duration,
+        /// The number of samples of the stacks per second.
+        #[clap(long, default_value_t = 999)]
+        frequency: i32,
+    },
+    /// Lists the algorithms, with their names in results and their
+    /// parameters, as lines of JSON with `--json`, e.g., for scripts to
+    /// discover them.
+    ListAlgorithms {
+        #[clap(long)]
+        json: bool,
+    },
+    /// Runs the experiment of a manifest again, with its arguments, seed and
+    /// number of threads, and writes its results to the `rerun` subdirectory
+    /// of the output directory, so that they can be compared with those of
+    /// the manifest, e.g., `rerun
+    /// results/regularity/8_bytes_to_range_to_incl_999.manifest.json`.
+    Rerun { manifest: PathBuf },
+    /// Writes an HTML report of the result files in a directory and its
+    /// subdirectories, with their summaries and charts, to `--output`, which
+    /// is `report.html` in the directory by default.
+    Report {
+        #[clap(default_value = RESULT_DIR)]
+        dir: PathBuf,
+        /// A TrueType font for the text of charts, which is a system font by
+        /// default.
+        #[clap(long)]
+        font: Option<PathBuf>,
+    },
+}
+
+/// The number of keys that each algorithm hashes in each experiment of a
+/// suite, unless `--max-keys` or `--max-duration` is set.
+const SUITE_MAX_KEYS: u64 = 100_000_000;
+/// The range ends of `independence-across-ranges` in a suite, which are small
+/// as the number of combinations of hashes is the product of their ranges.
+const SUITE_INDEPENDENCE_RANGE_ENDS: [u64; 3] = [9, 19, 29];
+/// The range end and number of seeds of `independence-across-seeds` in a
+/// suite.
+const SUITE_INDEPENDENCE_RANGE_END: u64 = 9;
+const SUITE_INDEPENDENCE_NUM_SEEDS: usize = 3;
+
+/// Returns the experiments of a suite.
+fn suite_commands(
+    range_end: u64,
+    input_size_bytes: usize,
+    keys: KeyOptions,
+    algorithms: Vec<Algorithm>,
+) -> Vec<ExperimentCommand> {
+    vec![
+        ExperimentCommand::Regularity {
+            range_end,
+            input_size_bytes,
+            test: GoodnessOfFitTest::default(),
+            exact_test_samples: None,
+            bootstrap_samples: None,
+            num_seeds: None,
+            histogram: None,
+            keys: keys.clone(),
+            algorithms: algorithms.clone(),
+        },
+        ExperimentCommand::Collisions {
+            range_end,
+            input_size_bytes,
+            num_seeds: None,
+            keys: keys.clone(),
+            algorithms: algorithms.clone(),
+        },
+        ExperimentCommand::IndependenceAcrossRanges {
+            range_end: SUITE_INDEPENDENCE_RANGE_ENDS.to_vec(),
+            input_size_bytes,
+            test: GoodnessOfFitTest::default(),
+            keys: keys.clone(),
+            algorithms: algorithms.clone(),
+        },
+        ExperimentCommand::IndependenceAcrossSeeds {
+            range_end: SUITE_INDEPENDENCE_RANGE_END,
+            num_seeds: Some(SUITE_INDEPENDENCE_NUM_SEEDS),
+            seeds: Vec::new(),
+            input_size_bytes,
+            test: GoodnessOfFitTest::default(),
+            keys: keys.clone(),
+            algorithms: algorithms.clone(),
+        },
+        ExperimentCommand::Avalanche {
+            range_end,
+            input_size_bytes,
+            keys: keys.clone(),
+            algorithms: algorithms.clone(),
+        },
+        ExperimentCommand::Consistency {
+            range_end: range_end.into(),
+            input_size_bytes,
+            keys,
+            algorithms,
+        },
+    ]
+}
+
+/// The experiments, which write their results to a file.
+#[derive(Subcommand, Clone, Debug)]
+enum ExperimentCommand {
     /// Tests the uniformity of the distribution of hashes using a chi-squared
     /// test.
     Regularity {
@@ -36,6 +559,36 @@ enum Command {
         range_end: u64,
         #[clap(short, long)]
         input_size_bytes: usize,
+        #[clap(long, value_enum, default_value_t)]
+        test: GoodnessOfFitTest,
+        /// Also reports the p-value of the exact multinomial test of
+        /// uniformity, as `exact p-value`, estimated from this number of
+        /// samples, e.g., 10000, for small ranges, for which the chi-squared
+        /// approximation is poor.
+        #[clap(long)]
+        exact_test_samples: Option<NonZeroUsize>,
+        /// Also reports the 95% confidence intervals of the L1 and L2
+        /// distances, as `l1 distance ci low` and `l1 distance ci high`, and
+        /// likewise for L2, from this number of bootstrap samples of the
+        /// counts, e.g., 1000, to compare the distances of algorithms.
+        #[clap(long)]
+        bootstrap_samples: Option<NonZeroUsize>,
+        /// Hashes the keys of each step under one of this number of random
+        /// seeds in turn, and also reports the minimum, median and maximum of
+        /// the metrics of the seeds, the seed of the smallest p-value, and the
+        /// Kolmogorov-Smirnov test of the uniformity of their p-values, to
+        /// catch seeds for which an algorithm behaves unusually. The steps
+        /// have 10^7 keys, unless `--batch-p-values` sets their size.
+        #[clap(long)]
+        num_seeds: Option<NonZeroUsize>,
+        /// Writes the count of every value of each algorithm at the end of
+        /// the run, next to the output file, with the `.<algorithm>.counts`
+        /// extension, followed by `.csv` for CSV files, e.g., to look for
+        /// structure in the bias across the values.
+        #[clap(long, value_enum)]
+        histogram: Option<HistogramFormat>,
+        #[clap(flatten)]
+        keys: KeyOptions,
         #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
         algorithms: Vec<Algorithm>,
     },
@@ -49,17 +602,73 @@ enum Command {
         range_end: u64,
         #[clap(short, long)]
         input_size_bytes: usize,
+        /// Hashes the keys of each step under one of this number of random
+        /// seeds in turn, and also reports the minimum, median and maximum of
+        /// the metrics of the seeds, the seed of the smallest p-value, and the
+        /// Kolmogorov-Smirnov test of the uniformity of their p-values, to
+        /// catch seeds for which an algorithm behaves unusually. The steps
+        /// have 10^7 keys, unless `--batch-p-values` sets their size.
+        #[clap(long)]
+        num_seeds: Option<NonZeroUsize>,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Estimates the number of collisions as `collisions` does, from a sketch
+    /// of the counts whose memory does not depend on the range, so that ranges
+    /// too large to count exactly can be tested. The summaries report the
+    /// standard errors of the estimates.
+    CollisionsSketch {
+        /// The end of the range, which can exceed 64 bits for the algorithms
+        /// whose max range end does, e.g., `flip-hash-xxh3128`.
+        #[clap(short, long)]
+        range_end: u128,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        /// The number of counters of each row of the sketch; the standard error
+        /// of the number of collisions is about `n / sqrt(2 * width * depth)`
+        /// for `n` keys.
+        #[clap(long, default_value_t = 1 << 18)]
+        sketch_width: usize,
+        /// The number of rows of the sketch, which are independent estimates.
+        #[clap(long, default_value_t = 16, value_parser = clap::value_parser!(u64).range(2..))]
+        sketch_depth: u64,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures the largest number of keys of a value and its ratio to the
+    /// mean number of keys per value, as keys are hashed, which bounds the
+    /// imbalance between the values.
+    MaxLoad {
+        #[clap(short, long)]
+        range_end: u64,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
         #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
         algorithms: Vec<Algorithm>,
     },
 
     /// Tests the mutual independence across a given number of ranges, given
-    /// that hashes are pairwise distinct, using a chi-squared test.
+    /// that hashes are pairwise distinct, using a chi-squared test. The range
+    /// ends can be in any order, but must be distinct, and the hash of each
+    /// range is counted in its new values, i.e., those above the next smaller
+    /// range end.
     IndependenceAcrossRanges {
         #[clap(short, long)]
         range_end: Vec<u64>,
         #[clap(short, long)]
         input_size_bytes: usize,
+        #[clap(long, value_enum, default_value_t)]
+        test: GoodnessOfFitTest,
+        #[clap(flatten)]
+        keys: KeyOptions,
         #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
         algorithms: Vec<Algorithm>,
     },
@@ -68,21 +677,382 @@ enum Command {
     IndependenceAcrossSeeds {
         #[clap(short, long)]
         range_end: u64,
+        /// The number of random seeds, unless `--seeds` gives them.
+        #[clap(short, long, required_unless_present = "seeds")]
+        num_seeds: Option<usize>,
+        /// The seeds, e.g., `--seeds 1,2,3`, to test specific ones, e.g.,
+        /// sequential seeds, or seeds that differ in a bit, instead of random
+        /// ones.
+        #[clap(long, value_delimiter = ',', conflicts_with = "num_seeds")]
+        seeds: Vec<u64>,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(long, value_enum, default_value_t)]
+        test: GoodnessOfFitTest,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Tests the independence of the hashes of pairs of distinct but related
+    /// keys, e.g., consecutive integers, using a chi-squared test, to detect
+    /// structure that `regularity` cannot see.
+    SerialCorrelation {
+        #[clap(short, long)]
+        range_end: u64,
+        #[clap(long, value_enum)]
+        relation: KeyRelation,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(long, value_enum, default_value_t)]
+        test: GoodnessOfFitTest,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures how often the hash changes when flipping each bit of the key,
+    /// compared with the probability that two random keys have distinct
+    /// hashes. Keys are hashed with random seeds.
+    Avalanche {
+        #[clap(short, long)]
+        range_end: u64,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures how often the hash of a key changes when flipping each bit of
+    /// the seed, compared with the probability that two random seeds give
+    /// distinct hashes.
+    SeedAvalanche {
+        #[clap(short, long)]
+        range_end: u64,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Checks that when growing the range by one, keys either keep their
+    /// hash or move to the new value, and compares the fraction of keys that
+    /// move with the expected one.
+    Consistency {
+        /// The end of the range, which can exceed 64 bits for the algorithms
+        /// whose max range end does, e.g., `flip-hash-xxh3128`.
+        #[clap(short, long)]
+        range_end: u128,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures the fractions of keys that move when growing the range from
+    /// `..=n` to `..=n+1` and to `..=2n`, with their 95% confidence
+    /// intervals, and compares them with those of consistent hashing, for Flip
+    /// Hash and the baselines by default.
+    Remap {
+        /// The end `n` of the range, which can exceed 64 bits for the
+        /// algorithms whose max range end does.
+        #[clap(short, long)]
+        range_end: u128,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=REMAP_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures the fraction of keys that move when shrinking the range, and
+    /// tests the uniformity of the values that they move to using a
+    /// chi-squared test.
+    Shrink {
+        #[clap(short, long)]
+        range_end: u64,
+        #[clap(short, long)]
+        new_range_end: u64,
         #[clap(short, long)]
-        num_seeds: usize,
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures the bias of the distribution of hashes for each range end of
+    /// a sweep, with the same number of keys per value, and writes a CSV file
+    /// per algorithm, to spot range ends that are pathological.
+    RangeSweep {
+        #[clap(long, default_value_t = 1)]
+        min_range_end: u64,
+        #[clap(long)]
+        max_range_end: u64,
+        #[clap(long, default_value_t = 1)]
+        range_end_step: usize,
+        #[clap(short, long, default_value_t = 100)]
+        num_keys_per_value: u64,
         #[clap(short, long)]
         input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
         #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
         algorithms: Vec<Algorithm>,
     },
+
+    /// Measures the number of keys hashed per second, for each of the given
+    /// range ends and input sizes.
+    Speed {
+        #[clap(short, long, required = true)]
+        range_end: Vec<u64>,
+        #[clap(short, long, required = true)]
+        input_size_bytes: Vec<usize>,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Counts the cycles, instructions, branch misses and cache misses per
+    /// key of hashing keys, with the hardware performance counters of Linux,
+    /// for each of the given range ends and input sizes.
+    PerfCounters {
+        #[clap(short, long, required = true)]
+        range_end: Vec<u64>,
+        #[clap(short, long, required = true)]
+        input_size_bytes: Vec<usize>,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+
+    /// Measures the mean, median and 99th percentile of the time to hash a
+    /// key, for range ends that are evenly spaced on a log scale up to
+    /// `--max-range-end`, to show how the cost of algorithms scales with the
+    /// range end.
+    Latency {
+        #[clap(long, default_value_t = 1_000_000_000)]
+        max_range_end: u64,
+        #[clap(long, default_value_t = 4)]
+        range_ends_per_decade: u32,
+        #[clap(short, long)]
+        input_size_bytes: usize,
+        #[clap(flatten)]
+        keys: KeyOptions,
+        #[clap(short, long, default_values_t=DEFAULT_ALGORITHMS)]
+        algorithms: Vec<Algorithm>,
+    },
+}
+
+impl ExperimentCommand {
+    /// Returns the experiment with keys of `size` bytes.
+    fn with_input_size_bytes(mut self, size: usize) -> Self {
+        match &mut self {
+            ExperimentCommand::Regularity {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Collisions {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::CollisionsSketch {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::MaxLoad {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::IndependenceAcrossRanges {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::IndependenceAcrossSeeds {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::SerialCorrelation {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Avalanche {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::SeedAvalanche {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Consistency {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Remap {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Shrink {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::RangeSweep {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::Latency {
+                input_size_bytes, ..
+            } => *input_size_bytes = size,
+            ExperimentCommand::Speed {
+                input_size_bytes, ..
+            }
+            | ExperimentCommand::PerfCounters {
+                input_size_bytes, ..
+            } => *input_size_bytes = vec![size],
+        }
+        self
+    }
+    /// Returns the experiment without its algorithms, e.g., to identify its
+    /// checkpoints, which may have the counts of any algorithms.
+    fn without_algorithms(mut self) -> Self {
+        match &mut self {
+            ExperimentCommand::Regularity { algorithms, .. }
+            | ExperimentCommand::Collisions { algorithms, .. }
+            | ExperimentCommand::CollisionsSketch { algorithms, .. }
+            | ExperimentCommand::MaxLoad { algorithms, .. }
+            | ExperimentCommand::IndependenceAcrossRanges { algorithms, .. }
+            | ExperimentCommand::IndependenceAcrossSeeds { algorithms, .. }
+            | ExperimentCommand::SerialCorrelation { algorithms, .. }
+            | ExperimentCommand::Avalanche { algorithms, .. }
+            | ExperimentCommand::SeedAvalanche { algorithms, .. }
+            | ExperimentCommand::Consistency { algorithms, .. }
+            | ExperimentCommand::Remap { algorithms, .. }
+            | ExperimentCommand::Shrink { algorithms, .. }
+            | ExperimentCommand::RangeSweep { algorithms, .. }
+            | ExperimentCommand::Speed { algorithms, .. }
+            | ExperimentCommand::PerfCounters { algorithms, .. }
+            | ExperimentCommand::Latency { algorithms, .. } => algorithms.clear(),
+        }
+        self
+    }
 }
 
+/// The algorithms of the experiments, whose descriptions are listed by
+/// `list-algorithms`.
 #[derive(clap::ValueEnum, Clone, Copy, Debug)]
 enum Algorithm {
+    /// Flip Hash of 64-bit integer keys, which are the first 8 bytes of the
+    /// keys.
     FlipHash64,
+    /// Flip Hash of byte keys, hashed with the 64-bit XXH3.
     FlipHashXXH364,
+    /// Flip Hash of byte keys, hashed with the 128-bit XXH3.
     FlipHashXXH3128,
+    /// Jump Hash of 64-bit integer keys, which are the first 8 bytes of the
+    /// keys, under the seed, which is mixed into them.
     JumpHash,
+    /// Ketama, i.e., the consistent hashing ring of libketama, with 160
+    /// points per value, whose servers are named after the seed.
+    Ketama,
+    /// AnchorHash of 64-bit integer keys, which are the first 8 bytes of the
+    /// keys, XORed with the seed, with the smallest power of two of buckets
+    /// that is at least twice the number of values.
+    AnchorHash,
+    /// DxHash of 64-bit integer keys, which are the first 8 bytes of the keys,
+    /// XORed with the seed, with the smallest power of two of buckets that is
+    /// at least the number of values.
+    DxHash,
+    /// Maglev hashing of 64-bit integer keys, which are the first 8 bytes of
+    /// the keys, with a lookup table of `--maglev-table-size` entries, whose
+    /// permutations are derived from the seed.
+    Maglev,
+    /// Rendezvous hashing, i.e., highest random weight hashing, of 64-bit
+    /// integer keys, which are the first 8 bytes of the keys, XORed with the
+    /// seed, in time linear in the number of values.
+    Rendezvous,
+    /// Weighted rendezvous hashing of 64-bit integer keys, which are the
+    /// first 8 bytes of the keys, XORed with the seed, with equal weights.
+    WeightedRendezvous,
+}
+
+/// The description of an algorithm, as listed by `list-algorithms`.
+#[derive(Serialize)]
+struct AlgorithmInfo {
+    /// The value of the algorithm in arguments.
+    id: String,
+    /// The name of the algorithm in results and checkpoints.
+    name: String,
+    description: String,
+    /// Whether the algorithm takes a seed, rather than the benchmarks mixing
+    /// it into the keys or the ring.
+    seeded: bool,
+    /// The number of bits of the hash of keys, from which values are drawn.
+    #[serde(rename = "hash bits")]
+    hash_bits: u32,
+    /// The hash function of keys, if they are not integers already.
+    #[serde(rename = "backend hash")]
+    backend_hash: Option<&'static str>,
+    /// The number of bytes of the keys that are hashed, if not all of them.
+    #[serde(rename = "key bytes")]
+    key_bytes: Option<usize>,
+    #[serde(rename = "max range end")]
+    max_range_end: u128,
+}
+
+impl Algorithm {
+    /// Returns the description of the algorithm.
+    fn info(self) -> AlgorithmInfo {
+        let possible_value = self.to_possible_value().unwrap();
+        let (seeded, hash_bits, backend_hash, key_bytes, max_range_end) = match self {
+            Algorithm::FlipHash64 => (true, 64, None, Some(8), u64::MAX.into()),
+            Algorithm::FlipHashXXH364 => (true, 64, Some("XXH3-64"), None, u64::MAX.into()),
+            Algorithm::FlipHashXXH3128 => (true, 128, Some("XXH3-128"), None, u128::MAX),
+            Algorithm::JumpHash => (true, 64, None, Some(8), u64::MAX.into()),
+            // The ring is searched with the first 32 bits of the MD5 digests.
+            Algorithm::Ketama => (false, 32, Some("MD5"), None, u64::MAX.into()),
+            // The number of buckets must fit in 32 bits.
+            Algorithm::AnchorHash => (false, 64, None, Some(8), (1 << 30) - 1),
+            Algorithm::DxHash => (false, 64, None, Some(8), (1 << 31) - 1),
+            // The default size of the lookup table must fit in 32 bits.
+            Algorithm::Maglev => (
+                false,
+                64,
+                None,
+                Some(8),
+                (u32::MAX / Maglev::DEFAULT_TABLE_SIZE_FACTOR - 1).into(),
+            ),
+            Algorithm::Rendezvous | Algorithm::WeightedRendezvous => {
+                (false, 64, None, Some(8), u32::MAX.into())
+            }
+        };
+        AlgorithmInfo {
+            id: possible_value.get_name().to_owned(),
+            name: self.name(),
+            description: possible_value
+                .get_help()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            seeded,
+            hash_bits,
+            backend_hash,
+            key_bytes,
+            max_range_end,
+        }
+    }
+
+    /// Returns the name of the algorithm in results and checkpoints.
+    fn name(self) -> String {
+        match self {
+            Algorithm::FlipHash64 => FlipHash64.to_string(),
+            Algorithm::FlipHashXXH364 => FlipHashXXH364.to_string(),
+            Algorithm::FlipHashXXH3128 => FlipHashXXH3128.to_string(),
+            Algorithm::JumpHash => JumpHash.to_string(),
+            Algorithm::Ketama => Ketama::default().to_string(),
+            Algorithm::AnchorHash => AnchorHash::default().to_string(),
+            Algorithm::DxHash => DxHash::default().to_string(),
+            Algorithm::Maglev => Maglev::new(None, None).to_string(),
+            Algorithm::Rendezvous => Rendezvous.to_string(),
+            Algorithm::WeightedRendezvous => WeightedRendezvous::default().to_string(),
+        }
+    }
 }
 
 impl fmt::Display for Algorithm {
@@ -95,155 +1065,2048 @@ impl fmt::Display for Algorithm {
                 Algorithm::FlipHashXXH364 => "flip-hash-xxh364",
                 Algorithm::FlipHashXXH3128 => "flip-hash-xxh3128",
                 Algorithm::JumpHash => "jump-hash",
+                Algorithm::Ketama => "ketama",
+                Algorithm::AnchorHash => "anchor-hash",
+                Algorithm::DxHash => "dx-hash",
+                Algorithm::Maglev => "maglev",
+                Algorithm::Rendezvous => "rendezvous",
+                Algorithm::WeightedRendezvous => "weighted-rendezvous",
             }
         )
     }
 }
 
 fn main() {
-    match Command::parse() {
-        Command::Regularity {
+    let mut args = Args::parse();
+    // The manifest of `rerun`, whose arguments replace those of the process.
+    let rerun_manifest = match &args.command {
+        Some(Command::Rerun { manifest: path }) => {
+            let manifest = Manifest::read(path)
+                .unwrap_or_else(|err| panic!("Cannot read {}: {err}", path.display()));
+            if manifest.git_commit != metadata::git_commit() {
+                eprintln!(
+                    "Warning: the commit of {} is {}, which may not give the same results",
+                    path.display(),
+                    manifest.git_commit.as_deref().unwrap_or("unknown")
+                );
+            }
+            Some(manifest)
+        }
+        _ => None,
+    };
+    if let Some(manifest) = &rerun_manifest {
+        args = rerun_args(manifest, args).unwrap_or_else(|err| err.exit());
+    }
+    match (&args.command, &args.config) {
+        (Some(_), Some(_)) => Args::command()
+            .error(ErrorKind::ArgumentConflict, "--config replaces the command")
+            .exit(),
+        (None, None) => Args::command()
+            .error(
+                ErrorKind::MissingSubcommand,
+                "Either a command or --config is required",
+            )
+            .exit(),
+        _ => {}
+    }
+    let campaign = args.config.as_deref().map(|path| {
+        Campaign::read(path).unwrap_or_else(|err| panic!("Cannot read {}: {err}", path.display()))
+    });
+    let rng_seed = args
+        .rng_seed
+        .or(campaign.as_ref().and_then(|campaign| campaign.rng_seed))
+        .unwrap_or_else(rand::random);
+    let metrics_address = args.metrics_address.or(campaign
+        .as_ref()
+        .and_then(|campaign| campaign.metrics_address.clone()));
+    rng::set_seed(rng_seed);
+    if let Some(address) = &metrics_address {
+        metrics::serve(address)
+            .unwrap_or_else(|err| panic!("Cannot serve metrics at {address}: {err}"));
+    }
+    let mut run_options = args.run_options;
+    let mut output_options = args.output_options;
+    // The path of the report of a suite, and the directory of its results.
+    let mut suite_report = None;
+    let commands = match args.command {
+        // The experiments of the campaign.
+        None => Vec::new(),
+        Some(Command::Experiment(command)) => vec![command],
+        Some(Command::MergeResults {
+            checkpoints,
+            experiment,
+        }) => {
+            run_options.mode = Mode::Merge(checkpoints);
+            // So as not to overwrite the results of a run of this machine.
+            output_options.output_dir.push("merged");
+            vec![experiment]
+        }
+        Some(Command::ListAlgorithms { json }) => {
+            let mut output = io::stdout().lock();
+            for algorithm in Algorithm::value_variants() {
+                let info = algorithm.info();
+                if json {
+                    serde_json::to_writer(&mut output, &info).unwrap();
+                    writeln!(output).unwrap();
+                } else {
+                    writeln!(
+                        output,
+                        "{}: {}\n    {}\n    seeded: {}, hash bits: {}, backend hash: {}, \
+                         key bytes: {}, max range end: {}",
+                        info.id,
+                        info.name,
+                        info.description,
+                        info.seeded,
+                        info.hash_bits,
+                        info.backend_hash.unwrap_or("none"),
+                        info.key_bytes
+                            .map_or_else(|| "all".to_owned(), |key_bytes| key_bytes.to_string()),
+                        info.max_range_end,
+                    )
+                    .unwrap();
+                }
+            }
+            return;
+        }
+        Some(Command::Suite {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+            font,
+        }) => {
+            assert!(
+                run_options.resume.is_none(),
+                "Suites cannot be resumed, as they have a checkpoint per experiment"
+            );
+            plot::load_font(font.as_deref()).unwrap();
+            output_options.output_dir.push("suite");
+            let report_path = output_options
+                .output
+                .take()
+                .unwrap_or_else(|| output_options.output_dir.join("report.html"));
+            suite_report = Some((report_path, output_options.output_dir.clone()));
+            if run_options.max_keys.is_none() && run_options.max_duration.is_none() {
+                run_options.max_keys = Some(SUITE_MAX_KEYS);
+            }
+            suite_commands(range_end, input_size_bytes, keys, algorithms)
+        }
+        Some(Command::Plot {
+            files,
+            format,
+            font,
+        }) => {
+            plot::load_font(font.as_deref()).unwrap();
+            for file in files {
+                let chart_paths = plot::plot_results(&file, format)
+                    .unwrap_or_else(|err| panic!("Cannot plot {}: {err}", file.display()));
+                eprintln!("Drew {} charts of {}", chart_paths.len(), file.display());
+            }
+            return;
+        }
+        Some(Command::Profile {
+            algorithm,
+            range_end,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        }) => {
+            let output_path = output_options.output_path(
+                "profile",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}_{algorithm}{}.svg",
+                    keys.file_name_suffix()
+                ),
+            );
+            write_profile(
+                &output_path,
+                algorithm,
+                ..=range_end,
+                input_size_bytes,
+                &keys.keys(),
+                duration,
+                frequency,
+                run_options.maglev_table_size(),
+            );
+            return;
+        }
+        Some(Command::Rerun { .. }) => unreachable!("The arguments of the manifest replace it"),
+        Some(Command::Report { dir, font }) => {
+            plot::load_font(font.as_deref()).unwrap();
+            let output_path = output_options
+                .output
+                .unwrap_or_else(|| dir.join("report.html"));
+            write_report(&dir, &output_path);
+            return;
+        }
+        Some(Command::Compare {
+            first_checkpoint,
+            first_algorithm,
+            second_checkpoint,
+            second_algorithm,
+            experiment,
+        }) => {
+            let second_algorithm = second_algorithm.unwrap_or(first_algorithm);
+            run_options.mode = Mode::Compare([
+                Sample {
+                    algo: first_algorithm.name(),
+                    checkpoint: first_checkpoint.clone(),
+                },
+                Sample {
+                    algo: second_algorithm.name(),
+                    checkpoint: second_checkpoint.unwrap_or(first_checkpoint),
+                },
+            ]);
+            output_options
+                .output
+                .get_or_insert_with(|| PathBuf::from(STDOUT_PATH));
+            vec![experiment]
+        }
+        Some(Command::Estimate {
+            options,
+            experiment,
+        }) => {
+            run_options.mode = Mode::Estimate(options);
+            output_options
+                .output
+                .get_or_insert_with(|| PathBuf::from(STDOUT_PATH));
+            vec![experiment]
+        }
+        Some(Command::Coordinate { listen, experiment }) => {
+            run_options.mode = Mode::Coordinate {
+                address: listen,
+                fingerprint: experiment_fingerprint(&experiment, &run_options),
+            };
+            vec![experiment]
+        }
+        Some(Command::Work {
+            coordinator,
+            experiment,
+        }) => {
+            run_options.mode = Mode::Work {
+                coordinator,
+                fingerprint: experiment_fingerprint(&experiment, &run_options),
+            };
+            // Workers write no results.
+            output_options.output = Some(PathBuf::from(STDOUT_PATH));
+            vec![experiment]
+        }
+        Some(Command::InputSizeSweep {
+            input_sizes_bytes,
+            experiment,
+        }) => {
+            assert!(
+                output_options.output.is_none(),
+                "Input-size sweeps write a result file per size to the output directory"
+            );
+            input_sizes_bytes
+                .into_iter()
+                .map(|size| experiment.clone().with_input_size_bytes(size))
+                .collect()
+        }
+    };
+    let mut runs = match (&campaign, &args.config) {
+        (Some(campaign), Some(path)) => campaign_runs(campaign, path, rng_seed),
+        _ => {
+            let process_args = match &rerun_manifest {
+                Some(manifest) => manifest.args.clone(),
+                None => env::args().collect(),
+            };
+            let metadata = Metadata::new(run_options.num_threads(), rng_seed, process_args);
+            commands
+                .into_iter()
+                .map(|command| {
+                    let mut run_options = run_options.clone();
+                    // The collisions of a suite report neither p-values nor
+                    // biases, which would fail the thresholds on them.
+                    if suite_report.is_some()
+                        && matches!(command, ExperimentCommand::Collisions { .. })
+                    {
+                        run_options.thresholds = run_options.thresholds.without_statistics();
+                    }
+                    ExperimentRun {
+                        metadata: metadata.clone().with_experiment(&command),
+                        command,
+                        run_options,
+                        output_options: output_options.clone(),
+                    }
+                })
+                .collect()
+        }
+    };
+    if let Some(manifest) = &rerun_manifest {
+        runs.retain(|run| format!("{:?}", run.command) == manifest.experiment);
+        assert!(
+            !runs.is_empty(),
+            "The arguments of the manifest no longer give its experiment, {}",
+            manifest.experiment
+        );
+    }
+    // A second interruption exits without waiting for the workers.
+    ctrlc::set_handler(|| {
+        if STOP.swap(true, Ordering::Relaxed) {
+            process::exit(130);
+        }
+        eprintln!("Stopping after the current steps; interrupt again to exit now");
+    })
+    .unwrap();
+    let deadline = run_options
+        .max_duration
+        .map(|max_duration| Instant::now() + max_duration);
+    let num_runs = runs.len();
+    for (index, run) in runs.into_iter().enumerate() {
+        if STOP.load(Ordering::Relaxed) {
+            break;
+        }
+        // The remaining time is shared evenly by the remaining experiments,
+        // so that the time left by those that stop early is not lost.
+        let shared_max_duration = deadline.map(|deadline| {
+            deadline.saturating_duration_since(Instant::now()) / (num_runs - index) as u32
+        });
+        // Each run draws from stream 0 of the seed from its start, as its
+        // workers do from theirs, so that it can be reproduced on its own,
+        // e.g., from its manifest.
+        rng::set_stream(0);
+        let command_run_options = RunOptions {
+            max_duration: match (shared_max_duration, run.run_options.max_duration) {
+                (Some(shared), Some(own)) => Some(shared.min(own)),
+                (shared, own) => shared.or(own),
+            },
+            ..run.run_options
+        };
+        run_command(
+            run.command,
+            &command_run_options,
+            &run.output_options,
+            &run.metadata,
+        );
+    }
+    if let Some((report_path, results_dir)) = suite_report {
+        write_report(&results_dir, &report_path);
+    }
+    if FAILED.load(Ordering::Relaxed) {
+        process::exit(1);
+    }
+}
+
+/// An experiment to run, with the options of its run.
+struct ExperimentRun {
+    command: ExperimentCommand,
+    run_options: RunOptions,
+    output_options: OutputOptions,
+    metadata: Metadata,
+}
+
+/// Returns the arguments that run the experiment of `manifest` again, which
+/// are its arguments, with its seed and number of threads, and with the
+/// metrics address and the output options of `args`, those of `rerun`.
+fn rerun_args(manifest: &Manifest, args: Args) -> Result<Args, clap::Error> {
+    let mut rerun_args = Args::try_parse_from(&manifest.args)?;
+    rerun_args.rng_seed = Some(manifest.rng_seed);
+    rerun_args.run_options.threads = Some(manifest.num_threads.try_into().unwrap());
+    rerun_args.metrics_address = args.metrics_address;
+    // So as not to overwrite the results of the manifest.
+    rerun_args.output_options = args.output_options;
+    rerun_args.output_options.output_dir.push("rerun");
+    Ok(rerun_args)
+}
+
+/// Returns the runs of the experiments of `campaign`, which is read from
+/// `path`, whose arguments are parsed as those of the command line.
+fn campaign_runs(campaign: &Campaign, path: &Path, rng_seed: u64) -> Vec<ExperimentRun> {
+    let program = env::args().next().unwrap_or_default();
+    campaign
+        .experiment_args()
+        .unwrap_or_else(|err| panic!("Invalid {}: {err}", path.display()))
+        .into_iter()
+        .enumerate()
+        .map(|(index, experiment_args)| {
+            let args = iter::once(program.clone())
+                .chain(experiment_args)
+                .collect::<Vec<_>>();
+            let parsed_args = Args::try_parse_from(&args).unwrap_or_else(|err| {
+                eprintln!("Invalid experiment {} of {}:", index + 1, path.display());
+                err.exit()
+            });
+            let (Some(Command::Experiment(command)), None, None, None) = (
+                parsed_args.command,
+                parsed_args.config,
+                parsed_args.rng_seed,
+                parsed_args.metrics_address,
+            ) else {
+                panic!(
+                    "Experiment {} of {} is not an experiment, or sets an option of the process",
+                    index + 1,
+                    path.display()
+                );
+            };
+            ExperimentRun {
+                metadata: Metadata::new(parsed_args.run_options.num_threads(), rng_seed, args)
+                    .with_experiment(&command),
+                command,
+                run_options: parsed_args.run_options,
+                output_options: parsed_args.output_options,
+            }
+        })
+        .collect()
+}
+
+/// Profiles hashing keys with `algorithm`, and writes the flamegraph of the
+/// profile to `output_path`.
+#[allow(clippy::too_many_arguments)]
+fn write_profile(
+    output_path: &Path,
+    algorithm: Algorithm,
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: &Keys,
+    duration: Duration,
+    frequency: i32,
+    maglev_table_size: Option<u32>,
+) {
+    let profile = match algorithm {
+        Algorithm::FlipHash64 => profile::profile(
+            &FlipHash64,
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::FlipHashXXH364 => profile::profile(
+            &FlipHashXXH364,
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::FlipHashXXH3128 => profile::profile(
+            &FlipHashXXH3128,
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::JumpHash => profile::profile(
+            &JumpHash,
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::Ketama => profile::profile(
+            &Ketama::default(),
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::AnchorHash => profile::profile(
+            &AnchorHash::default(),
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::DxHash => profile::profile(
+            &DxHash::default(),
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::Maglev => profile::profile(
+            &Maglev::new(maglev_table_size, None),
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::Rendezvous => profile::profile(
+            &Rendezvous,
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+        Algorithm::WeightedRendezvous => profile::profile(
+            &WeightedRendezvous::default(),
+            range,
+            input_size_bytes,
+            keys,
+            duration,
+            frequency,
+        ),
+    }
+    .unwrap_or_else(|err| panic!("Cannot profile {algorithm}: {err}"));
+    open_output(output_path, false)
+        .map_err(pprof::Error::from)
+        .and_then(|output| profile.write_flamegraph(output))
+        .unwrap_or_else(|err| panic!("Cannot write {}: {err}", output_path.display()));
+    eprintln!(
+        "Wrote the flamegraph of hashing {} keys with {algorithm} to {}",
+        profile.num_keys,
+        output_path.display()
+    );
+}
+
+/// Returns the fingerprint of an experiment, which distributed workers must
+/// share with their coordinator, i.e., its arguments and those of the options
+/// of the run that change what steps accumulate.
+fn experiment_fingerprint(experiment: &ExperimentCommand, run_options: &RunOptions) -> String {
+    format!(
+        "{experiment:?} with Maglev table size {:?}",
+        run_options.maglev_table_size
+    )
+}
+
+/// Returns the identity of the checkpoints of an experiment, which the
+/// checkpoints that a run resumes from or merges must have, i.e., its
+/// fingerprint, without the algorithms, as checkpoints may have the counts of
+/// any algorithms.
+fn checkpoint_experiment(experiment: &ExperimentCommand, run_options: &RunOptions) -> String {
+    experiment_fingerprint(&experiment.clone().without_algorithms(), run_options)
+}
+
+/// Writes the report of the result files in `dir` to `output_path`.
+fn write_report(dir: &Path, output_path: &Path) {
+    let html =
+        report::report(dir).unwrap_or_else(|err| panic!("Cannot report {}: {err}", dir.display()));
+    open_output(output_path, false)
+        .and_then(|mut output| output.write_all(html.as_bytes()))
+        .unwrap_or_else(|err| panic!("Cannot write {}: {err}", output_path.display()));
+    eprintln!(
+        "Wrote the report of {} to {}",
+        dir.display(),
+        output_path.display()
+    );
+}
+
+/// Runs the experiment of `command`.
+fn run_command(
+    command: ExperimentCommand,
+    run_options: &RunOptions,
+    output_options: &OutputOptions,
+    metadata: &Metadata,
+) {
+    let run_options = &RunOptions {
+        checkpoint_experiment: checkpoint_experiment(&command, run_options),
+        ..run_options.clone()
+    };
+    match command {
+        ExperimentCommand::Regularity {
             range_end,
             input_size_bytes,
+            test,
+            exact_test_samples,
+            bootstrap_samples,
+            num_seeds,
+            histogram,
+            keys,
             algorithms,
         } => {
-            let output_dir = format!("{RESULT_DIR}/regularity");
-            create_dir_all(&output_dir).unwrap();
-            let mut output = File::create(format!(
-                "{output_dir}/{input_size_bytes}_bytes_to_range_to_incl_{range_end}"
-            ))
-            .unwrap();
-            let experiment = Regularity::new(..=range_end, input_size_bytes);
-            run_experiment(&mut output, experiment, algorithms);
+            let output_path = output_options.output_path(
+                "regularity",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}{}",
+                    seeds_file_name_suffix(num_seeds),
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Regularity::new(
+                ..=range_end,
+                input_size_bytes,
+                keys.keys(),
+                test,
+                exact_test_samples.map(NonZeroUsize::get),
+                bootstrap_samples.map(NonZeroUsize::get),
+            );
+            let run_options = &RunOptions {
+                num_seeds,
+                histogram: histogram.map(|format| HistogramOptions {
+                    format,
+                    num_values: usize::try_from(range_end).unwrap().checked_add(1).unwrap(),
+                }),
+                ..run_options.clone()
+            };
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata);
         }
-        Command::Collisions {
+        ExperimentCommand::Collisions {
             range_end,
             input_size_bytes,
+            num_seeds,
+            keys,
             algorithms,
         } => {
-            let output_dir = format!("{RESULT_DIR}/collisions");
-            create_dir_all(&output_dir).unwrap();
-            let mut output = File::create(format!(
-                "{output_dir}/{input_size_bytes}_bytes_to_range_to_incl_{range_end}"
-            ))
-            .unwrap();
-            let experiment = Collisions::new(..=range_end, input_size_bytes);
-            run_experiment(&mut output, experiment, algorithms);
+            let output_path = output_options.output_path(
+                "collisions",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}{}",
+                    seeds_file_name_suffix(num_seeds),
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Collisions::new(..=range_end, input_size_bytes, keys.keys());
+            let run_options = &RunOptions {
+                num_seeds,
+                ..run_options.clone()
+            };
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata);
         }
-        Command::IndependenceAcrossRanges {
+        ExperimentCommand::CollisionsSketch {
             range_end,
             input_size_bytes,
+            sketch_width,
+            sketch_depth,
+            keys,
             algorithms,
         } => {
-            let output_dir = format!("{RESULT_DIR}/independence_across_ranges");
-            create_dir_all(&output_dir).unwrap();
-            let mut output = File::create(format!(
-                "{output_dir}/{}_bytes_to_ranges_to_incl_{}",
+            let output_path = output_options.output_path(
+                "collisions_sketch",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}\
+                _sketch_{sketch_width}x{sketch_depth}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            assert_max_range_end(&algorithms, range_end);
+            let experiment = CollisionsSketch::new(
+                ..=range_end,
                 input_size_bytes,
-                range_end.iter().join("_")
-            ))
-            .unwrap();
+                sketch_width,
+                sketch_depth as usize,
+                keys.keys(),
+            );
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata);
+        }
+        ExperimentCommand::MaxLoad {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "max_load",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = MaxLoad::new(..=range_end, input_size_bytes, keys.keys());
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata);
+        }
+        ExperimentCommand::IndependenceAcrossRanges {
+            range_end,
+            input_size_bytes,
+            test,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "independence_across_ranges",
+                format!(
+                    "{}_bytes_to_ranges_to_incl_{}{}",
+                    input_size_bytes,
+                    range_end.iter().join("_"),
+                    keys.file_name_suffix()
+                ),
+            );
             let experiment = IndependenceAcrossRanges::new(
-                range_end
-                    .iter()
-                    .sorted()
-                    .map(|&end| ..=end)
-                    .collect::<Vec<_>>(),
+                range_end.iter().map(|&end| ..=end).collect(),
                 input_size_bytes,
+                keys.keys(),
+                test,
             );
-            run_experiment(&mut output, experiment, algorithms)
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
         }
-        Command::IndependenceAcrossSeeds {
+        ExperimentCommand::IndependenceAcrossSeeds {
             range_end,
             num_seeds,
+            seeds,
             input_size_bytes,
+            test,
+            keys,
             algorithms,
         } => {
-            let output_dir = format!("{RESULT_DIR}/independence_across_seeds");
-            create_dir_all(&output_dir).unwrap();
-            let mut output = File::create(format!(
-                "{output_dir}/{}_bytes_{}_seeds_to_range_to_incl_{}",
-                input_size_bytes, num_seeds, range_end
-            ))
-            .unwrap();
+            let seeds_name = match num_seeds {
+                Some(num_seeds) => format!("{num_seeds}_seeds"),
+                None => format!("seeds_{}", seeds.iter().join("_")),
+            };
+            let output_path = output_options.output_path(
+                "independence_across_seeds",
+                format!(
+                    "{}_bytes_{}_to_range_to_incl_{}{}",
+                    input_size_bytes,
+                    seeds_name,
+                    range_end,
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = match num_seeds {
+                Some(num_seeds) => IndependenceAcrossSeeds::new(
+                    ..=range_end,
+                    num_seeds,
+                    input_size_bytes,
+                    keys.keys(),
+                    test,
+                ),
+                None => IndependenceAcrossSeeds::with_seeds(
+                    ..=range_end,
+                    seeds,
+                    input_size_bytes,
+                    keys.keys(),
+                    test,
+                ),
+            };
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::SerialCorrelation {
+            range_end,
+            relation,
+            input_size_bytes,
+            test,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "serial_correlation",
+                format!(
+                    "{}_bytes_{}_to_range_to_incl_{}{}",
+                    input_size_bytes,
+                    relation.to_possible_value().unwrap().get_name(),
+                    range_end,
+                    keys.file_name_suffix()
+                ),
+            );
             let experiment =
-                IndependenceAcrossSeeds::new(..=range_end, num_seeds, input_size_bytes);
-            run_experiment(&mut output, experiment, algorithms)
+                SerialCorrelation::new(..=range_end, relation, input_size_bytes, keys.keys(), test);
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::Avalanche {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "avalanche",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Avalanche::new(..=range_end, input_size_bytes, keys.keys());
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
         }
+        ExperimentCommand::SeedAvalanche {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "seed_avalanche",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = SeedAvalanche::new(..=range_end, input_size_bytes, keys.keys());
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::Consistency {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "consistency",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            // The range grows by one.
+            assert_max_range_end(&algorithms, range_end.saturating_add(1));
+            let experiment = Consistency::new(..=range_end, input_size_bytes, keys.keys());
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::Remap {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "remap",
+                format!(
+                    "{input_size_bytes}_bytes_to_range_to_incl_{range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            // The range grows to twice its end.
+            let max_range_end = range_end.saturating_mul(2);
+            assert_max_range_end(&algorithms, max_range_end);
+            let experiment = Remap::new(..=range_end, input_size_bytes, keys.keys());
+            let run_options = &RunOptions {
+                max_range_end: u64::try_from(max_range_end).ok(),
+                ..run_options.clone()
+            };
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::Shrink {
+            range_end,
+            new_range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "shrink",
+                format!(
+                    "{input_size_bytes}_bytes_from_range_to_incl_{range_end}_to_{new_range_end}{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Shrink::new(
+                ..=range_end,
+                ..=new_range_end,
+                input_size_bytes,
+                keys.keys(),
+            );
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::RangeSweep {
+            min_range_end,
+            max_range_end,
+            range_end_step,
+            num_keys_per_value,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            assert!(
+                matches!(run_options.mode, Mode::Run),
+                "Range sweeps have no checkpoints"
+            );
+            // The biases of each algorithm are written to their own file.
+            assert!(
+                output_options.output.is_none() || algorithms.len() == 1,
+                "--output needs a single algorithm"
+            );
+            let ranges = (min_range_end..=max_range_end)
+                .step_by(range_end_step)
+                .map(|end| ..=end)
+                .collect::<Vec<_>>();
+            let sweep_keys = keys.keys();
+            for algorithm in algorithms {
+                let biases = match algorithm {
+                    Algorithm::FlipHash64 => range_sweep(
+                        || FlipHash64,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::FlipHashXXH364 => range_sweep(
+                        || FlipHashXXH364,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::FlipHashXXH3128 => range_sweep(
+                        || FlipHashXXH3128,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::JumpHash => range_sweep(
+                        || JumpHash,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::Ketama => range_sweep(
+                        Ketama::default,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::AnchorHash => range_sweep(
+                        AnchorHash::default,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::DxHash => range_sweep(
+                        DxHash::default,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::Maglev => range_sweep(
+                        || Maglev::new(run_options.maglev_table_size(), None),
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::Rendezvous => range_sweep(
+                        || Rendezvous,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                    Algorithm::WeightedRendezvous => range_sweep(
+                        WeightedRendezvous::default,
+                        &ranges,
+                        num_keys_per_value,
+                        input_size_bytes,
+                        &sweep_keys,
+                        run_options.num_threads(),
+                        &STOP,
+                    ),
+                };
+                let output_path = output_options.output_path(
+                    "range_sweep",
+                    format!(
+                        "{input_size_bytes}_bytes_to_ranges_to_incl_{min_range_end}_to_{max_range_end}_by_{range_end_step}_{algorithm}{}.csv",
+                        keys.file_name_suffix()
+                    ),
+                );
+                let mut output = open_output(&output_path, false).unwrap();
+                // The metadata is a comment, which CSV readers can skip, e.g.,
+                // with `comment="#"` in pandas.
+                output.write_all(b"# ").unwrap();
+                serde_json::to_writer(&mut output, &MetadataRecord { metadata }).unwrap();
+                output.write_all(b"\n").unwrap();
+                write_range_biases(&mut output, &biases).unwrap();
+                eprintln!("Swept {} range ends for {}", biases.len(), algorithm);
+                let summary = RangeSweepSummary::new(&biases);
+                check_thresholds(
+                    &algorithm.name(),
+                    run_options.thresholds.failures(summary.num_keys, &summary),
+                );
+                if STOP.load(Ordering::Relaxed) {
+                    break;
+                }
+            }
+        }
+        ExperimentCommand::Speed {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "speed",
+                format!(
+                    "{}_bytes_to_ranges_to_incl_{}{}",
+                    input_size_bytes.iter().join("_"),
+                    range_end.iter().join("_"),
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Speed::new(
+                range_end.iter().map(|&end| ..=end).collect(),
+                input_size_bytes,
+                keys.keys(),
+            );
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::PerfCounters {
+            range_end,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "perf_counters",
+                format!(
+                    "{}_bytes_to_ranges_to_incl_{}{}",
+                    input_size_bytes.iter().join("_"),
+                    range_end.iter().join("_"),
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = PerfCounters::new(
+                range_end.iter().map(|&end| ..=end).collect(),
+                input_size_bytes,
+                keys.keys(),
+            );
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+        ExperimentCommand::Latency {
+            max_range_end,
+            range_ends_per_decade,
+            input_size_bytes,
+            keys,
+            algorithms,
+        } => {
+            let output_path = output_options.output_path(
+                "latency",
+                format!(
+                    "{input_size_bytes}_bytes_to_ranges_to_incl_{max_range_end}_by_{range_ends_per_decade}_per_decade{}",
+                    keys.file_name_suffix()
+                ),
+            );
+            let experiment = Latency::new(
+                log_spaced_range_ends(max_range_end, range_ends_per_decade)
+                    .into_iter()
+                    .map(|end| ..=end)
+                    .collect(),
+                input_size_bytes,
+                keys.keys(),
+            );
+            run_experiment(&output_path, experiment, algorithms, run_options, metadata)
+        }
+    }
+}
+
+/// Panics unless the algorithms hash to ranges that end at `range_end`, e.g.,
+/// beyond 64 bits, before they are run.
+fn assert_max_range_end(algorithms: &[Algorithm], range_end: u128) {
+    for algorithm in algorithms {
+        let max_range_end = algorithm.info().max_range_end;
+        assert!(
+            range_end <= max_range_end,
+            "The max range end of {} is {max_range_end}",
+            algorithm.name()
+        );
+    }
+}
+
+/// Returns the range ends from 1 to `max_range_end` that are evenly spaced on
+/// a log scale, with `per_decade` of them per power of ten, as far as they
+/// are distinct integers.
+fn log_spaced_range_ends(max_range_end: u64, per_decade: u32) -> Vec<u64> {
+    assert!(per_decade > 0);
+    (0..)
+        .map(|index| {
+            10_f64
+                .powf(f64::from(index) / f64::from(per_decade))
+                .round() as u64
+        })
+        .take_while(|&end| end <= max_range_end)
+        .dedup()
+        .collect()
+}
+
+/// Returns the suffix of the names of the output files of a sweep of
+/// `num_seeds` seeds, if any.
+fn seeds_file_name_suffix(num_seeds: Option<NonZeroUsize>) -> String {
+    num_seeds
+        .map(|num_seeds| format!("_{num_seeds}_seeds"))
+        .unwrap_or_default()
+}
+
+/// A line of the output file of an experiment, with the summary of an
+/// algorithm.
+#[derive(Serialize)]
+struct ResultLine<'a, S> {
+    algo: &'a str,
+    #[serde(flatten)]
+    summary: S,
+    /// Whether the metric of `--convergence-metric` is stable, after which
+    /// no more keys are hashed for the algorithm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    converged: Option<bool>,
+    /// The approximate number of bytes of the counts of the algorithm, which
+    /// each worker also holds for the step it hashes.
+    #[serde(rename = "accumulator bytes")]
+    accumulator_bytes: usize,
+    /// The test of the p-values of the batches, with `--batch-p-values`.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    batches: Option<BatchSummary>,
+    /// The distributions of the metrics across seeds, with `--num-seeds`.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    seeds: Option<SeedSummary>,
+    /// The cost of the keys of this run, which merged results do not have.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    cost: Option<Cost>,
+}
+
+/// The time that the workers spent hashing the keys of an algorithm in a run,
+/// including the overhead of the experiment, so that quality and cost can be
+/// traded off from the same results.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+struct Cost {
+    #[serde(skip)]
+    num_keys: u64,
+    /// The sum of the wall-clock times of the steps, which run in parallel,
+    /// and can thus exceed the duration of the run.
+    #[serde(rename = "elapsed seconds")]
+    elapsed_seconds: f64,
+    /// The throughput of a worker, i.e., of a thread.
+    #[serde(rename = "keys per second")]
+    keys_per_second: f64,
+}
+
+impl Cost {
+    fn record(&mut self, num_keys: u64, elapsed: Duration) {
+        self.num_keys += num_keys;
+        self.elapsed_seconds += elapsed.as_secs_f64();
+        self.keys_per_second = self.num_keys as f64 / self.elapsed_seconds;
     }
 }
 
-fn run_experiment<E>(output: &mut impl Write, experiment: E, algorithms: Vec<Algorithm>)
-where
-    E: Experiment + Clone + Send + 'static,
+fn run_experiment<E>(
+    output_path: &Path,
+    experiment: E,
+    algorithms: Vec<Algorithm>,
+    run_options: &RunOptions,
+    metadata: &Metadata,
+) where
+    E: Experiment + Sync,
     <E as Experiment>::Accumulator: Send,
 {
     const STEP_SIZE: u64 = 10_000_000;
+    let step_size = run_options
+        .batch_p_values
+        .map_or(STEP_SIZE, NonZeroU64::get);
+
+    match &run_options.mode {
+        Mode::Run => {}
+        Mode::Merge(checkpoint_paths) => {
+            return merge_checkpoints(
+                output_path,
+                &experiment,
+                &run_options.checkpoint_experiment,
+                checkpoint_paths,
+                metadata,
+                &run_options.thresholds,
+            )
+        }
+        Mode::Compare(samples) => {
+            let mut output = open_output(output_path, false).unwrap();
+            serde_json::to_writer(&mut output, &MetadataRecord { metadata }).unwrap();
+            output.write_all(b"\n").unwrap();
+            serde_json::to_writer(
+                &mut output,
+                &compare_samples(&experiment, &run_options.checkpoint_experiment, samples),
+            )
+            .unwrap();
+            output.write_all(b"\n").unwrap();
+            return;
+        }
+        Mode::Estimate(options) => {
+            let estimate = estimate::estimate(
+                &experiment,
+                algorithms.len(),
+                run_options.num_threads(),
+                step_size,
+                run_options.max_keys,
+                options,
+            );
+            for warning in &estimate.warnings {
+                eprintln!("Warning: {warning}");
+            }
+            if !estimate.warnings.is_empty() {
+                FAILED.store(true, Ordering::Relaxed);
+            }
+            let mut output = open_output(output_path, false).unwrap();
+            serde_json::to_writer(&mut output, &MetadataRecord { metadata }).unwrap();
+            output.write_all(b"\n").unwrap();
+            serde_json::to_writer(&mut output, &estimate).unwrap();
+            output.write_all(b"\n").unwrap();
+            return;
+        }
+        Mode::Work {
+            coordinator,
+            fingerprint,
+        } => {
+            return run_remote_workers(
+                coordinator,
+                fingerprint,
+                &experiment,
+                &algorithms,
+                run_options,
+            )
+        }
+        Mode::Coordinate { .. } => {}
+    }
 
     assert!(!algorithms.is_empty());
+    assert!(
+        run_options.checkpoint_interval.is_none() || output_path != Path::new(STDOUT_PATH),
+        "Checkpoints need an output file"
+    );
+    assert!(
+        run_options.histogram.is_none() || output_path != Path::new(STDOUT_PATH),
+        "Histograms need an output file"
+    );
+    assert!(
+        run_options.snapshot_interval.is_none() || output_path != Path::new(STDOUT_PATH),
+        "Snapshots need an output file"
+    );
+    // The results of resumed runs also depend on their checkpoints, so that
+    // they keep the manifests of the runs that they resume.
+    if matches!(run_options.mode, Mode::Run)
+        && run_options.resume.is_none()
+        && output_path != Path::new(STDOUT_PATH)
+    {
+        manifest::write(output_path, metadata, run_options)
+            .unwrap_or_else(|err| panic!("Cannot write the manifest: {err}"));
+    }
 
-    let (tx, rx) = mpsc::channel();
-    for _ in 0..usize::from(thread::available_parallelism().unwrap()) - 1 {
-        let thread_tx = tx.clone();
-        let thread_experiment = experiment.clone();
-        let thread_algorithms = algorithms.clone();
-        thread::spawn(move || loop {
-            for algorithm in &thread_algorithms {
-                match algorithm {
-                    Algorithm::FlipHash64 => {
-                        thread_tx
-                            .send((
-                                format!("{}", FlipHash64),
-                                thread_experiment.accumulate(&FlipHash64, STEP_SIZE),
-                            ))
-                            .unwrap();
-                    }
-                    Algorithm::FlipHashXXH364 => {
-                        thread_tx
-                            .send((
-                                format!("{}", FlipHashXXH364),
-                                thread_experiment.accumulate(&FlipHashXXH364, STEP_SIZE),
-                            ))
-                            .unwrap();
-                    }
-                    Algorithm::FlipHashXXH3128 => {
-                        thread_tx
-                            .send((
-                                format!("{}", FlipHashXXH3128),
-                                thread_experiment.accumulate(&FlipHashXXH3128, STEP_SIZE),
-                            ))
-                            .unwrap();
-                    }
-                    Algorithm::JumpHash => {
-                        thread_tx
-                            .send((
-                                format!("{}", JumpHash),
-                                thread_experiment.accumulate(&JumpHash, STEP_SIZE),
-                            ))
-                            .unwrap();
+    let num_threads = run_options.num_threads();
+    // The seeds are drawn from the generator of the main thread, so that they
+    // only depend on `--rng-seed`.
+    let seeds = match run_options.num_seeds {
+        Some(num_seeds) => (0..num_seeds.get())
+            .map(|_| thread_rng().next_u64())
+            .collect(),
+        None => vec![0],
+    };
+    let checkpoint = run_options.resume.as_ref().map(|resume_path| {
+        read_checkpoint(resume_path, &run_options.checkpoint_experiment, || {
+            experiment.new_accumulator()
+        })
+        .unwrap_or_else(|err| panic!("Cannot read {}: {err}", resume_path.display()))
+    });
+    let (accumulators, mut num_steps) = match checkpoint {
+        Some(checkpoint) => (checkpoint.accumulators, checkpoint.num_steps),
+        None => (HashMap::new(), HashMap::new()),
+    };
+    let work = Work {
+        experiment: &experiment,
+        algorithms: &algorithms,
+        remaining_keys: algorithms
+            .iter()
+            .map(|_| AtomicU64::new(run_options.max_keys.unwrap_or(u64::MAX)))
+            .collect(),
+        // The steps of resumed runs continue from those of their checkpoints,
+        // so that their keys differ.
+        num_steps: algorithms
+            .iter()
+            .map(|algorithm| AtomicU64::new(num_steps.get(&algorithm.name()).copied().unwrap_or(0)))
+            .collect(),
+        seeds: &seeds,
+        step_size,
+        deadline: run_options
+            .max_duration
+            .map(|max_duration| Instant::now() + max_duration),
+        maglev_table_size: run_options.maglev_table_size(),
+        max_range_end: run_options.max_range_end,
+        abort: AtomicBool::new(false),
+    };
+    // The queue of the steps that the main thread has not merged yet is
+    // bounded, as their accumulators may be large, so that workers wait for
+    // the main thread when it falls behind.
+    let (tx, rx) = mpsc::sync_channel(num_threads);
+    let listener = match &run_options.mode {
+        Mode::Coordinate { address, .. } => Some(
+            TcpListener::bind(address)
+                .unwrap_or_else(|err| panic!("Cannot listen on {address}: {err}")),
+        ),
+        _ => None,
+    };
+    thread::scope(|scope| {
+        let workers = match (listener, &run_options.mode) {
+            (Some(listener), Mode::Coordinate { fingerprint, .. }) => {
+                let tx = tx.clone();
+                let work = &work;
+                vec![scope.spawn(move || {
+                    let _abort_on_panic = AbortOnPanic(&work.abort);
+                    work.accept_remote_workers(scope, &listener, fingerprint, tx);
+                })]
+            }
+            _ => (0..num_threads)
+                .map(|_| {
+                    let tx = tx.clone();
+                    let work = &work;
+                    scope.spawn(move || {
+                        let _abort_on_panic = AbortOnPanic(&work.abort);
+                        work.run_worker(&tx);
+                    })
+                })
+                .collect::<Vec<_>>(),
+        };
+        // The workers hold the only senders, so that the channel closes when
+        // they are all done.
+        drop(tx);
+        let _abort_on_panic = AbortOnPanic(&work.abort);
+        let checkpoint_path = checkpoint_path(output_path);
+        let mut last_checkpoint = Instant::now();
+        let mut output = open_output(output_path, run_options.resume.is_some()).unwrap();
+        serde_json::to_writer(&mut output, &MetadataRecord { metadata }).unwrap();
+        output.write_all(b"\n").unwrap();
+        let mut accumulators = accumulators;
+        let mut convergence = Convergence::new(&run_options.convergence);
+        let mut converged_algos = HashSet::new();
+        let mut batch_p_values = run_options.batch_p_values.map(|_| BatchPValues::default());
+        let mut seed_sweep = run_options
+            .num_seeds
+            .map(|_| SeedSweep::new(&experiment, seeds.clone()));
+        let progress = Progress::new(
+            algorithms.iter().map(|algorithm| algorithm.name()),
+            run_options.max_keys,
+            work.deadline,
+            run_options.convergence.metric(),
+        );
+        let mut costs = HashMap::<String, Cost>::new();
+        let mut snapshots = run_options
+            .snapshot_interval
+            .map(|interval| Snapshots::new(output_path, interval));
+        for Step {
+            algo,
+            step_index,
+            seed_index,
+            accumulator: step_accumulator,
+            elapsed,
+        } in rx
+        {
+            // Steps may be merged out of order, and those that were claimed
+            // before the last one merged are skipped when resuming.
+            let algo_num_steps = num_steps.entry(algo.clone()).or_default();
+            *algo_num_steps = (*algo_num_steps).max(step_index + 1);
+            let cost = costs.entry(algo.clone()).or_default();
+            cost.record(step_accumulator.num_iterations(), elapsed);
+            let algo_accumulator = accumulators
+                .entry(algo.clone())
+                .or_insert_with(|| experiment.new_accumulator());
+            algo_accumulator.merge(&step_accumulator);
+            if let Some(batch_p_values) = &mut batch_p_values {
+                batch_p_values.record(&algo, &experiment.summary(&step_accumulator));
+            }
+            if let Some(seed_sweep) = &mut seed_sweep {
+                seed_sweep.record(&algo, seed_index, &step_accumulator);
+            }
+            if let Some(snapshots) = &mut snapshots {
+                snapshots.record(&algo, &step_accumulator, || experiment.new_accumulator());
+            }
+
+            let summary = experiment.summary(algo_accumulator);
+            let converged = convergence.as_mut().map(|convergence| {
+                let is_stable = convergence.record(&algo, &summary);
+                if is_stable && converged_algos.insert(algo.clone()) {
+                    // The steps that workers already claimed are still recorded.
+                    if let Some(index) = algorithms.iter().position(|a| a.name() == algo) {
+                        work.remaining_keys[index].store(0, Ordering::Relaxed);
                     }
+                    progress.println(&format!("Converged for {algo}"));
                 }
+                converged_algos.contains(&algo)
+            });
+            progress.record(
+                &algo,
+                step_accumulator.num_iterations(),
+                algo_accumulator.num_iterations(),
+                &summary,
+            );
+            let result_line = ResultLine {
+                algo: &algo,
+                summary,
+                converged,
+                accumulator_bytes: algo_accumulator.memory_bytes(),
+                batches: batch_p_values
+                    .as_ref()
+                    .map(|batch_p_values| batch_p_values.summary(&algo)),
+                seeds: seed_sweep
+                    .as_ref()
+                    .map(|seed_sweep| seed_sweep.summary(&algo)),
+                cost: Some(*cost),
+            };
+            metrics::record(
+                &output_path.to_string_lossy(),
+                &algo,
+                algo_accumulator.num_iterations(),
+                &result_line,
+            );
+            serde_json::to_writer(&mut output, &result_line).unwrap();
+            output.write_all(b"\n").unwrap();
+            output.flush().unwrap();
+            if run_options
+                .checkpoint_interval
+                .is_some_and(|interval| last_checkpoint.elapsed() >= interval)
+            {
+                write_checkpoint(
+                    &checkpoint_path,
+                    &run_options.checkpoint_experiment,
+                    &accumulators,
+                    &num_steps,
+                )
+                .unwrap();
+                last_checkpoint = Instant::now();
+            }
+            if let Some(snapshots) = snapshots.as_mut().filter(|snapshots| snapshots.is_due()) {
+                write_checkpoint(
+                    &checkpoint_path,
+                    &run_options.checkpoint_experiment,
+                    &accumulators,
+                    &num_steps,
+                )
+                .unwrap();
+                snapshots
+                    .write(&experiment, &accumulators, metadata)
+                    .unwrap();
             }
+        }
+        progress.finish();
+        if run_options.checkpoint_interval.is_some() || snapshots.is_some() {
+            write_checkpoint(
+                &checkpoint_path,
+                &run_options.checkpoint_experiment,
+                &accumulators,
+                &num_steps,
+            )
+            .unwrap();
+        }
+        if let Some(snapshots) = &mut snapshots {
+            snapshots
+                .write(&experiment, &accumulators, metadata)
+                .unwrap();
+        }
+        // The workers are done, as the channel is closed. The panic of a
+        // worker is propagated once the counts of the others are written to
+        // the checkpoint.
+        for worker in workers {
+            if let Err(payload) = worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+        // The last summaries of the algorithms are the final ones.
+        for (algo, algo_accumulator) in accumulators.iter().sorted_by_key(|(algo, _)| *algo) {
+            eprintln!(
+                "Done with {:e} keys for {}",
+                algo_accumulator.num_iterations(),
+                algo
+            );
+        }
+        for algorithm in &algorithms {
+            let algo = algorithm.name();
+            let algo_accumulator = accumulators
+                .remove(&algo)
+                .unwrap_or_else(|| experiment.new_accumulator());
+            if let (Some(histogram), Some(nonzero_counts)) = (
+                run_options.histogram,
+                algo_accumulator.nonzero_counts_by_value(),
+            ) {
+                let histogram_path = histogram.path(output_path, &algorithm.to_string());
+                histogram
+                    .write(
+                        &histogram_path,
+                        &nonzero_counts,
+                        &MetadataRecord { metadata },
+                    )
+                    .unwrap_or_else(|err| {
+                        panic!("Cannot write {}: {err}", histogram_path.display())
+                    });
+            }
+            check_thresholds(
+                &algo,
+                run_options.thresholds.failures(
+                    algo_accumulator.num_iterations(),
+                    &experiment.summary(&algo_accumulator),
+                ),
+            );
+        }
+    });
+}
+
+/// The work that the workers of an experiment share, which they claim a step
+/// at a time.
+struct Work<'a, E> {
+    experiment: &'a E,
+    algorithms: &'a [Algorithm],
+    /// The number of keys that remain to be hashed for each algorithm.
+    remaining_keys: Vec<AtomicU64>,
+    /// The number of steps that were claimed for each algorithm, which
+    /// select the seeds of the steps in turn, and the streams of the keys of
+    /// local workers.
+    num_steps: Vec<AtomicU64>,
+    /// The seeds that the keys are hashed under, which is only 0 unless
+    /// seeds are swept.
+    seeds: &'a [u64],
+    step_size: u64,
+    deadline: Option<Instant>,
+    maglev_table_size: Option<u32>,
+    max_range_end: Option<u64>,
+    /// Whether the workers stop after their current steps, as a thread
+    /// panicked.
+    abort: AtomicBool,
+}
+
+/// A step of an algorithm, which a worker sends to the main thread.
+struct Step<A> {
+    algo: String,
+    /// The index of the step among those of the algorithm.
+    step_index: u64,
+    /// The index of the seed that the keys were hashed under.
+    seed_index: usize,
+    accumulator: A,
+    elapsed: Duration,
+}
+
+impl<E: Experiment> Work<'_, E> {
+    /// Accumulates steps of the algorithms in turn, and sends them to `tx`,
+    /// until no keys remain, the experiment is stopped, or the receiver is
+    /// gone.
+    fn run_worker(&self, tx: &SyncSender<Step<E::Accumulator>>) {
+        let worker_algorithms = WorkerAlgorithms::new(self.maglev_table_size, self.max_range_end);
+        let result = self.run_steps(tx, |assignment, step_index| {
+            // Each step has its own stream, so that its keys do not depend on
+            // the thread that accumulates it. Stream 0 is that of the main
+            // thread.
+            rng::set_stream(
+                step_index * self.algorithms.len() as u64 + assignment.algorithm_index as u64 + 1,
+            );
+            Keys::restart_thread();
+            let start = Instant::now();
+            let (algo, accumulator) = worker_algorithms.accumulate(
+                self.experiment,
+                self.algorithms[assignment.algorithm_index],
+                assignment.seed,
+                assignment.num_keys,
+            );
+            Ok((algo, accumulator, start.elapsed()))
         });
+        result.unwrap();
+    }
+
+    /// Accepts the workers of other hosts that connect to `listener`, and
+    /// whose experiment has the fingerprint `fingerprint`, each of which then
+    /// accumulates steps as a local worker does, until no keys remain or the
+    /// experiment is stopped.
+    fn accept_remote_workers<'scope>(
+        &'scope self,
+        scope: &'scope thread::Scope<'scope, '_>,
+        listener: &TcpListener,
+        fingerprint: &'scope str,
+        tx: SyncSender<Step<E::Accumulator>>,
+    ) where
+        E: Sync,
+        E::Accumulator: Send,
+    {
+        // Connections are polled, so as to stop accepting them once done.
+        const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+        listener.set_nonblocking(true).unwrap();
+        let mut remote_workers = Vec::new();
+        while !self.is_done() {
+            let (stream, peer) = match listener.accept() {
+                Ok(connection) => connection,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_INTERVAL);
+                    continue;
+                }
+                Err(err) => panic!("Cannot accept workers: {err}"),
+            };
+            stream.set_nonblocking(false).unwrap();
+            // Streams 1 and above are those of the remote workers.
+            let stream_index = remote_workers.len() as u64 + 1;
+            let tx = tx.clone();
+            remote_workers.push(scope.spawn(move || {
+                let _abort_on_panic = AbortOnPanic(&self.abort);
+                let result = WorkerConnection::accept(stream, fingerprint, stream_index).and_then(
+                    |mut connection| {
+                        self.run_steps(&tx, |assignment, _| {
+                            let mut accumulator = self.experiment.new_accumulator();
+                            let (algo, elapsed) =
+                                connection.accumulate(assignment, &mut accumulator)?;
+                            Ok((algo, accumulator, elapsed))
+                        })?;
+                        connection.finish()
+                    },
+                );
+                match result {
+                    Ok(()) => eprintln!("Done with the worker of {peer}"),
+                    Err(err) => eprintln!("Lost the worker of {peer}: {err}"),
+                }
+            }));
+            eprintln!("Accepted a worker from {peer}");
+        }
+        for remote_worker in remote_workers {
+            if let Err(payload) = remote_worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
     }
 
-    let mut accumulators = HashMap::new();
-    for (algo, step_accumulator) in rx {
-        let algo_accumulator = accumulators
-            .entry(algo.clone())
-            .or_insert_with(|| experiment.new_accumulator());
-        algo_accumulator.merge(&step_accumulator);
+    /// Returns whether no more steps are claimed, as no keys remain, or the
+    /// experiment is stopped.
+    fn is_done(&self) -> bool {
+        STOP.load(Ordering::Relaxed)
+            || self.abort.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            || self
+                .remaining_keys
+                .iter()
+                .all(|remaining_keys| remaining_keys.load(Ordering::Relaxed) == 0)
+    }
 
-        output
-            .write_fmt(format_args!("{{\"algo\": \"{algo}\""))
-            .unwrap();
-        experiment.write_summary(output, algo_accumulator).unwrap();
-        output.write_fmt(format_args!("}}\n")).unwrap();
-        output.flush().unwrap();
-        println!(
-            "Processed {:e} keys for {}",
-            algo_accumulator.num_iterations(),
+    /// Claims steps of the algorithms in turn, has `accumulate` accumulate
+    /// them, given their indices among the steps of their algorithms, and
+    /// sends them to `tx`, until no keys remain, the experiment is
+    /// stopped, or the receiver is gone. The keys of a step that fails to
+    /// accumulate are given back, for other workers to claim, and the error
+    /// is returned.
+    fn run_steps(
+        &self,
+        tx: &SyncSender<Step<E::Accumulator>>,
+        mut accumulate: impl FnMut(Assignment, u64) -> io::Result<(String, E::Accumulator, Duration)>,
+    ) -> io::Result<()> {
+        loop {
+            let mut is_done = true;
+            for (algorithm_index, (remaining_keys, num_steps)) in
+                self.remaining_keys.iter().zip(&self.num_steps).enumerate()
+            {
+                if STOP.load(Ordering::Relaxed)
+                    || self.abort.load(Ordering::Relaxed)
+                    || self
+                        .deadline
+                        .is_some_and(|deadline| Instant::now() >= deadline)
+                {
+                    return Ok(());
+                }
+                let Ok(remaining) = remaining_keys.fetch_update(
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                    |remaining| (remaining > 0).then(|| remaining.saturating_sub(self.step_size)),
+                ) else {
+                    continue;
+                };
+                let num_keys = remaining.min(self.step_size);
+                is_done = false;
+                let step_index = num_steps.fetch_add(1, Ordering::Relaxed);
+                let seed_index = (step_index % self.seeds.len() as u64) as usize;
+                let assignment = Assignment {
+                    algorithm_index,
+                    seed: self.seeds[seed_index],
+                    num_keys,
+                };
+                let (algo, accumulator, elapsed) = match accumulate(assignment, step_index) {
+                    Ok(step) => step,
+                    Err(err) => {
+                        // Unless the algorithm converged in the meantime.
+                        let _ = remaining_keys.fetch_update(
+                            Ordering::Relaxed,
+                            Ordering::Relaxed,
+                            |remaining| (remaining > 0).then(|| remaining.saturating_add(num_keys)),
+                        );
+                        return Err(err);
+                    }
+                };
+                let step = Step {
+                    algo,
+                    step_index,
+                    seed_index,
+                    accumulator,
+                    elapsed,
+                };
+                // The main thread panicked.
+                if tx.send(step).is_err() {
+                    return Ok(());
+                }
+            }
+            if is_done {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// The algorithms of a worker, whose rings of Ketama, buckets of AnchorHash
+/// and DxHash, and lookup tables of Maglev are kept across steps, as they are
+/// costly to build.
+struct WorkerAlgorithms {
+    ketama: Ketama,
+    anchor_hash: AnchorHash,
+    dx_hash: DxHash,
+    maglev: Maglev,
+    weighted_rendezvous: WeightedRendezvous,
+}
+
+impl WorkerAlgorithms {
+    fn new(maglev_table_size: Option<u32>, max_range_end: Option<u64>) -> Self {
+        Self {
+            ketama: Ketama::default(),
+            anchor_hash: AnchorHash::new(max_range_end),
+            dx_hash: DxHash::new(max_range_end),
+            maglev: Maglev::new(maglev_table_size, max_range_end),
+            weighted_rendezvous: WeightedRendezvous::default(),
+        }
+    }
+
+    /// Accumulates `num_keys` keys of `algorithm` under `seed`, and returns
+    /// the name of the algorithm with the accumulator.
+    fn accumulate<E: Experiment>(
+        &self,
+        experiment: &E,
+        algorithm: Algorithm,
+        seed: u64,
+        num_keys: u64,
+    ) -> (String, E::Accumulator) {
+        fn accumulate<E: Experiment>(
+            experiment: &E,
+            algorithm: &impl flip_hash_testkit::algo::Algorithm,
+            seed: u64,
+            num_keys: u64,
+        ) -> (String, E::Accumulator) {
+            (
+                algorithm.to_string(),
+                experiment.accumulate(&Reseeded { algorithm, seed }, num_keys),
+            )
+        }
+        match algorithm {
+            Algorithm::FlipHash64 => accumulate(experiment, &FlipHash64, seed, num_keys),
+            Algorithm::FlipHashXXH364 => accumulate(experiment, &FlipHashXXH364, seed, num_keys),
+            Algorithm::FlipHashXXH3128 => accumulate(experiment, &FlipHashXXH3128, seed, num_keys),
+            Algorithm::JumpHash => accumulate(experiment, &JumpHash, seed, num_keys),
+            Algorithm::Ketama => accumulate(experiment, &self.ketama, seed, num_keys),
+            Algorithm::AnchorHash => accumulate(experiment, &self.anchor_hash, seed, num_keys),
+            Algorithm::DxHash => accumulate(experiment, &self.dx_hash, seed, num_keys),
+            Algorithm::Maglev => accumulate(experiment, &self.maglev, seed, num_keys),
+            Algorithm::Rendezvous => accumulate(experiment, &Rendezvous, seed, num_keys),
+            Algorithm::WeightedRendezvous => {
+                accumulate(experiment, &self.weighted_rendezvous, seed, num_keys)
+            }
+        }
+    }
+}
+
+/// Runs `run_options.num_threads()` workers that accumulate the steps of
+/// `experiment` that the coordinator at `coordinator` hands out, until it has
+/// none left, or the experiment is stopped, in which case the coordinator
+/// hands out the current steps to other workers.
+fn run_remote_workers<E: Experiment + Sync>(
+    coordinator: &str,
+    fingerprint: &str,
+    experiment: &E,
+    algorithms: &[Algorithm],
+    run_options: &RunOptions,
+) {
+    let work_for_coordinator = || -> io::Result<u64> {
+        let (mut connection, stream_index) =
+            CoordinatorConnection::connect(coordinator, fingerprint)?;
+        rng::set_stream(stream_index);
+        let worker_algorithms =
+            WorkerAlgorithms::new(run_options.maglev_table_size(), run_options.max_range_end);
+        let mut num_steps = 0;
+        while let Some(assignment) = connection.next_assignment()? {
+            if STOP.load(Ordering::Relaxed) {
+                break;
+            }
+            let algorithm = *algorithms
+                .get(assignment.algorithm_index)
+                .ok_or_else(|| io::Error::other("Unknown algorithm"))?;
+            let start = Instant::now();
+            let (algo, accumulator) = worker_algorithms.accumulate(
+                experiment,
+                algorithm,
+                assignment.seed,
+                assignment.num_keys,
+            );
+            connection.send_step(&algo, &accumulator, start.elapsed())?;
+            num_steps += 1;
+        }
+        Ok(num_steps)
+    };
+    thread::scope(|scope| {
+        for _ in 0..run_options.num_threads() {
+            scope.spawn(|| match work_for_coordinator() {
+                Ok(num_steps) => eprintln!("Done with {num_steps} steps for {coordinator}"),
+                Err(err) => eprintln!("Cannot work for {coordinator}: {err}"),
+            });
+        }
+    });
+}
+
+/// Sets a flag when dropped while the thread panics, so that the other
+/// threads stop.
+struct AbortOnPanic<'a>(&'a AtomicBool);
+
+impl Drop for AbortOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Reports the failures of the thresholds of an algorithm, if any, after
+/// which the benchmarks exit with a non-zero code.
+fn check_thresholds(algo: &str, failures: Vec<String>) {
+    if !failures.is_empty() {
+        eprintln!("{algo} fails the thresholds: {}", failures.join(", "));
+        FAILED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Merges the accumulators of the checkpoints of `experiment`, whose identity
+/// in checkpoints is `checkpoint_experiment`, and writes their summaries to
+/// `output_path`, and the merged accumulators to a checkpoint next to it,
+/// unless it is the standard output.
+fn merge_checkpoints<E: Experiment>(
+    output_path: &Path,
+    experiment: &E,
+    checkpoint_experiment: &str,
+    checkpoint_paths: &[PathBuf],
+    metadata: &Metadata,
+    thresholds: &Thresholds,
+) {
+    let mut accumulators = HashMap::<String, E::Accumulator>::new();
+    // Resuming the merged checkpoint continues from the last steps of all the
+    // merged ones.
+    let mut num_steps = HashMap::<String, u64>::new();
+    for checkpoint_path in checkpoint_paths {
+        let checkpoint = read_checkpoint(checkpoint_path, checkpoint_experiment, || {
+            experiment.new_accumulator()
+        })
+        .unwrap_or_else(|err| panic!("Cannot read {}: {err}", checkpoint_path.display()));
+        for (algo, accumulator) in checkpoint.accumulators {
+            accumulators
+                .entry(algo)
+                .or_insert_with(|| experiment.new_accumulator())
+                .merge(&accumulator);
+        }
+        for (algo, algo_num_steps) in checkpoint.num_steps {
+            let merged_num_steps = num_steps.entry(algo).or_default();
+            *merged_num_steps = (*merged_num_steps).max(algo_num_steps);
+        }
+    }
+
+    let mut output = open_output(output_path, false).unwrap();
+    serde_json::to_writer(&mut output, &MetadataRecord { metadata }).unwrap();
+    output.write_all(b"\n").unwrap();
+    for (algo, accumulator) in accumulators.iter().sorted_by_key(|(algo, _)| *algo) {
+        serde_json::to_writer(
+            &mut output,
+            &ResultLine {
+                algo,
+                summary: experiment.summary(accumulator),
+                converged: None,
+                accumulator_bytes: accumulator.memory_bytes(),
+                batches: None,
+                seeds: None,
+                cost: None,
+            },
+        )
+        .unwrap();
+        output.write_all(b"\n").unwrap();
+        eprintln!(
+            "Merged {:e} keys for {}",
+            accumulator.num_iterations(),
             algo
         );
+        check_thresholds(
+            algo,
+            thresholds.failures(
+                accumulator.num_iterations(),
+                &experiment.summary(accumulator),
+            ),
+        );
+    }
+    output.flush().unwrap();
+    if output_path != Path::new(STDOUT_PATH) {
+        write_checkpoint(
+            &checkpoint_path(output_path),
+            checkpoint_experiment,
+            &accumulators,
+            &num_steps,
+        )
+        .unwrap();
+    }
+}
+
+/// Returns the path of the checkpoint of the output file at `output_path`.
+fn checkpoint_path(output_path: &Path) -> PathBuf {
+    let mut checkpoint_path = output_path.as_os_str().to_owned();
+    checkpoint_path.push(".checkpoint");
+    PathBuf::from(checkpoint_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, panic, slice, sync::Once};
+
+    use super::*;
+
+    /// Seeds the generators once for all the tests, so that the keys of a
+    /// stream can be drawn again.
+    fn set_seed() {
+        static SEED: Once = Once::new();
+        SEED.call_once(|| rng::set_seed(42));
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("flip_hash_benchmarks_{name}_{}", process::id()))
+    }
+
+    /// Returns the identity in checkpoints of the experiment of `args`.
+    fn parse_checkpoint_experiment(args: &str) -> String {
+        let args = Args::try_parse_from(iter::once("flip_hash_benchmarks").chain(args.split(' ')))
+            .unwrap();
+        let Some(Command::Experiment(command)) = &args.command else {
+            unreachable!();
+        };
+        checkpoint_experiment(command, &args.run_options)
+    }
+
+    fn regularity(range_end: u64) -> Regularity {
+        Regularity::new(
+            ..=range_end,
+            8,
+            Keys::default(),
+            GoodnessOfFitTest::default(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn merged_checkpoints_are_a_single_run() {
+        set_seed();
+        let experiment = regularity(9);
+        let checkpoint_experiment = parse_checkpoint_experiment("regularity -r 9 -i 8");
+        // The two runs hash the keys of the single run, one after the other.
+        rng::set_stream(7);
+        let single_run = experiment.accumulate(&JumpHash, 2000);
+        rng::set_stream(7);
+        let runs = [
+            experiment.accumulate(&JumpHash, 1000),
+            experiment.accumulate(&JumpHash, 1000),
+        ];
+
+        let paths = [temp_path("merge_a"), temp_path("merge_b")];
+        for ((path, run), num_steps) in iter::zip(&paths, runs).zip([3, 5]) {
+            write_checkpoint(
+                path,
+                &checkpoint_experiment,
+                &HashMap::from([(JumpHash.to_string(), run)]),
+                &HashMap::from([(JumpHash.to_string(), num_steps)]),
+            )
+            .unwrap();
+        }
+        let output_path = temp_path("merged");
+        merge_checkpoints(
+            &output_path,
+            &experiment,
+            &checkpoint_experiment,
+            &paths,
+            &Metadata::new(1, 42, Vec::new()),
+            &Thresholds::default(),
+        );
+        let output = fs::read_to_string(&output_path).unwrap();
+        let merged = read_checkpoint(
+            &checkpoint_path(&output_path),
+            &checkpoint_experiment,
+            || experiment.new_accumulator(),
+        );
+        for path in paths
+            .iter()
+            .chain([&output_path, &checkpoint_path(&output_path)])
+        {
+            fs::remove_file(path).unwrap();
+        }
+
+        let mut merged = merged.unwrap();
+        let accumulator = merged.accumulators.remove(&JumpHash.to_string()).unwrap();
+        assert_eq!(accumulator.num_iterations(), 2000);
+        assert_eq!(
+            accumulator.nonzero_counts_by_value(),
+            single_run.nonzero_counts_by_value()
+        );
+        assert_eq!(merged.num_steps, HashMap::from([(JumpHash.to_string(), 5)]));
+        // The summary is that of the single run.
+        let result_line =
+            serde_json::from_str::<serde_json::Value>(output.lines().nth(1).unwrap()).unwrap();
+        let serde_json::Value::Object(summary) =
+            serde_json::to_value(experiment.summary(&single_run)).unwrap()
+        else {
+            unreachable!();
+        };
+        // The floats of the result line are parsed, and may be off by a
+        // rounding error.
+        for (field, value) in summary {
+            match (result_line[&field].as_f64(), value.as_f64()) {
+                (Some(merged), Some(single)) => {
+                    assert!((merged - single).abs() <= 1e-12 * single.abs(), "{field}")
+                }
+                _ => assert_eq!(result_line[&field], value, "{field}"),
+            }
+        }
+    }
+
+    #[test]
+    fn checkpoints_of_other_experiments_are_not_merged() {
+        let experiment = parse_checkpoint_experiment("regularity -r 9 -i 8");
+        // The algorithms of checkpoints do not matter.
+        assert_eq!(
+            parse_checkpoint_experiment("regularity -r 9 -i 8 -a jump-hash"),
+            experiment
+        );
+        for other_experiment in [
+            "regularity -r 99 -i 8",
+            "regularity -r 9 -i 16",
+            "regularity -r 9 -i 8 --key-distribution sequential",
+            "collisions -r 9 -i 8",
+        ] {
+            assert_ne!(parse_checkpoint_experiment(other_experiment), experiment);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid checkpoint: of another experiment")]
+    fn checkpoints_of_other_ranges_are_rejected() {
+        let path = temp_path("merge_range_99");
+        write_checkpoint(
+            &path,
+            &parse_checkpoint_experiment("regularity -r 99 -i 8"),
+            &HashMap::from([(JumpHash.to_string(), regularity(99).new_accumulator())]),
+            &HashMap::new(),
+        )
+        .unwrap();
+        let output_path = temp_path("merged_range_9");
+        let result = panic::catch_unwind(|| {
+            merge_checkpoints(
+                &output_path,
+                &regularity(9),
+                &parse_checkpoint_experiment("regularity -r 9 -i 8"),
+                slice::from_ref(&path),
+                &Metadata::new(1, 42, Vec::new()),
+                &Thresholds::default(),
+            )
+        });
+        fs::remove_file(&path).unwrap();
+        if let Err(err) = result {
+            panic::resume_unwind(err);
+        }
     }
 }
@@ -0,0 +1,168 @@
+//! The manifests of runs, which are written next to their result files, with
+//! the [`EXTENSION`] extension followed by `.json`, so that a result can be
+//! reproduced exactly with `rerun`.
+//!
+//! A manifest has the metadata of the run, i.e., the versions, the arguments,
+//! the seed and the number of threads that the results depend on, along with
+//! the experiment and the options of the run as they were resolved, defaults
+//! included. `rerun` parses the arguments again, with the seed and number of
+//! threads of the manifest, and only runs the experiment of the manifest,
+//! e.g., one of a suite.
+
+use std::{
+    error::Error,
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::Metadata;
+
+pub(crate) const EXTENSION: &str = "manifest";
+
+/// The generator of the threads, which are streams of the seed.
+const GENERATOR: &str = "ChaCha12";
+
+#[derive(Serialize)]
+struct ManifestRecord<'a> {
+    /// The command that reruns the experiment, from the directory of the run.
+    #[serde(rename = "rerun command")]
+    rerun_command: String,
+    #[serde(flatten)]
+    metadata: &'a Metadata,
+    generator: &'static str,
+    #[serde(rename = "run options")]
+    run_options: String,
+}
+
+/// What `rerun` reads from a manifest.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Manifest {
+    #[serde(rename = "git commit")]
+    pub(crate) git_commit: Option<String>,
+    #[serde(rename = "num threads")]
+    pub(crate) num_threads: usize,
+    #[serde(rename = "rng seed")]
+    pub(crate) rng_seed: u64,
+    pub(crate) args: Vec<String>,
+    pub(crate) experiment: String,
+}
+
+impl Manifest {
+    pub(crate) fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Returns the path of the manifest of the result file at `output_path`.
+fn manifest_path(output_path: &Path) -> PathBuf {
+    let mut path = output_path.as_os_str().to_owned();
+    path.push(format!(".{EXTENSION}.json"));
+    PathBuf::from(path)
+}
+
+/// Writes the manifest of the run of `metadata`, whose resolved options are
+/// `run_options`, next to its result file at `output_path`.
+pub(crate) fn write(
+    output_path: &Path,
+    metadata: &Metadata,
+    run_options: &impl Debug,
+) -> io::Result<()> {
+    let path = manifest_path(output_path);
+    let mut output = BufWriter::new(File::create(&path)?);
+    serde_json::to_writer_pretty(
+        &mut output,
+        &ManifestRecord {
+            rerun_command: format!("cargo run -r -- rerun {}", path.display()),
+            metadata,
+            generator: GENERATOR,
+            run_options: format!("{run_options:?}"),
+        },
+    )?;
+    output.write_all(b"\n")?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use clap::Parser;
+
+    use super::*;
+    use crate::{rerun_args, Args, Command};
+
+    #[test]
+    fn reruns_resolve_the_same_arguments() {
+        let process_args = [
+            "flip_hash_benchmarks",
+            "regularity",
+            "-r",
+            "99",
+            "-i",
+            "8",
+            "-a",
+            "jump-hash",
+            "--max-keys",
+            "1000",
+            "--checkpoint-interval",
+            "10m",
+        ]
+        .map(str::to_owned)
+        .to_vec();
+        let args = Args::try_parse_from(&process_args).unwrap();
+        let Some(Command::Experiment(command)) = &args.command else {
+            unreachable!();
+        };
+        let metadata = Metadata::new(3, 42, process_args.clone()).with_experiment(command);
+        let output_path = env::temp_dir().join(format!(
+            "flip_hash_benchmarks_manifest_{}.jsonl",
+            std::process::id()
+        ));
+        write(&output_path, &metadata, &args.run_options).unwrap();
+        let path = manifest_path(&output_path);
+        let manifest = Manifest::read(&path);
+        fs::remove_file(&path).unwrap();
+        let manifest = manifest.unwrap();
+        assert_eq!(manifest.args, process_args);
+        assert_eq!(manifest.rng_seed, 42);
+        assert_eq!(manifest.num_threads, 3);
+        assert_eq!(manifest.experiment, format!("{command:?}"));
+
+        let rerun = Args::try_parse_from([
+            "flip_hash_benchmarks",
+            "rerun",
+            path.to_str().unwrap(),
+            "--output-dir",
+            "results/elsewhere",
+        ])
+        .unwrap();
+        let rerun_args = rerun_args(&manifest, rerun).unwrap();
+        let Some(Command::Experiment(rerun_command)) = &rerun_args.command else {
+            unreachable!();
+        };
+        assert_eq!(format!("{rerun_command:?}"), manifest.experiment);
+        assert_eq!(rerun_args.rng_seed, Some(42));
+        let mut run_options = args.run_options;
+        run_options.threads = Some(3.try_into().unwrap());
+        assert_eq!(
+            format!("{:?}", rerun_args.run_options),
+            format!("{run_options:?}")
+        );
+        assert_eq!(
+            rerun_args.output_options.output_dir,
+            Path::new("results/elsewhere/rerun")
+        );
+    }
+
+    #[test]
+    fn manifests_are_next_to_their_results() {
+        assert_eq!(
+            manifest_path(Path::new("results/regularity/8_bytes_to_range_to_incl_99")),
+            Path::new("results/regularity/8_bytes_to_range_to_incl_99.manifest.json")
+        );
+    }
+}
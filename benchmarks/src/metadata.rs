@@ -0,0 +1,139 @@
+//! The metadata of runs, which are written at the start of the result files,
+//! so that results can be traced back to the code and machine that produced
+//! them.
+
+use std::{
+    fmt::Debug,
+    fs,
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+/// The metadata of a run. Whatever cannot be determined, e.g., the commit
+/// outside of a Git checkout, is `null`.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Metadata {
+    /// The commit of the checkout that the benchmarks were built from, with
+    /// a `-dirty` suffix if it has uncommitted changes.
+    #[serde(rename = "git commit")]
+    git_commit: Option<String>,
+    #[serde(rename = "flip hash version")]
+    flip_hash_version: Option<&'static str>,
+    #[serde(rename = "benchmarks version")]
+    benchmarks_version: &'static str,
+    hostname: Option<String>,
+    #[serde(rename = "cpu model")]
+    cpu_model: Option<String>,
+    #[serde(rename = "num threads")]
+    num_threads: usize,
+    /// The seed of the generators, which is drawn from the entropy of the
+    /// system unless `--rng-seed` sets it.
+    #[serde(rename = "rng seed")]
+    rng_seed: u64,
+    args: Vec<String>,
+    /// The experiment that the arguments resolve to, with the defaults of
+    /// its parameters, as several experiments may share the arguments, e.g.,
+    /// those of a suite.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    experiment: Option<String>,
+    /// The number of seconds since the Unix epoch.
+    #[serde(rename = "start time")]
+    start_time: u64,
+}
+
+impl Metadata {
+    /// Returns the metadata of a run with the command-line arguments `args`,
+    /// e.g., those of the process.
+    pub(crate) fn new(num_threads: usize, rng_seed: u64, args: Vec<String>) -> Self {
+        Self {
+            git_commit: git_commit(),
+            flip_hash_version: flip_hash_version(),
+            benchmarks_version: env!("CARGO_PKG_VERSION"),
+            hostname: hostname(),
+            cpu_model: cpu_model(),
+            num_threads,
+            rng_seed,
+            args,
+            experiment: None,
+            start_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        }
+    }
+
+    /// Returns the metadata of the run of `experiment`, whose `Debug`
+    /// representation identifies it.
+    pub(crate) fn with_experiment(self, experiment: &impl Debug) -> Self {
+        Self {
+            experiment: Some(format!("{experiment:?}")),
+            ..self
+        }
+    }
+}
+
+/// The header record of JSON result files, which is the first line of every
+/// run, including resumed ones.
+#[derive(Serialize)]
+pub(crate) struct MetadataRecord<'a> {
+    pub(crate) metadata: &'a Metadata,
+}
+
+/// Returns the commit of the checkout, see [`Metadata`].
+pub(crate) fn git_commit() -> Option<String> {
+    let repository = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let commit = command_output(
+        Command::new("git")
+            .arg("-C")
+            .arg(repository)
+            .args(["rev-parse", "HEAD"]),
+    )?;
+    let status = command_output(Command::new("git").arg("-C").arg(repository).args([
+        "status",
+        "--porcelain",
+        "--untracked-files=no",
+    ]));
+    Some(match status {
+        Some(status) if !status.is_empty() => format!("{commit}-dirty"),
+        _ => commit,
+    })
+}
+
+/// Returns the version of flip_hash, which is a path dependency, from its
+/// manifest.
+fn flip_hash_version() -> Option<&'static str> {
+    include_str!("../../Cargo.toml")
+        .lines()
+        .find_map(|line| line.strip_prefix("version = \"")?.strip_suffix('"'))
+}
+
+fn hostname() -> Option<String> {
+    fs::read_to_string("/etc/hostname")
+        .ok()
+        .map(|hostname| hostname.trim().to_owned())
+        .filter(|hostname| !hostname.is_empty())
+        .or_else(|| command_output(&mut Command::new("hostname")))
+}
+
+fn cpu_model() -> Option<String> {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|cpuinfo| {
+            cpuinfo.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == "model name").then(|| value.trim().to_owned())
+            })
+        })
+        .or_else(|| command_output(Command::new("sysctl").args(["-n", "machdep.cpu.brand_string"])))
+}
+
+/// Returns the trimmed standard output of `command`, if it succeeds.
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
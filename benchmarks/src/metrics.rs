@@ -0,0 +1,110 @@
+//! The metrics of the running experiments, served over HTTP in the text
+//! format of Prometheus with `--metrics-address`, so that runs of days can be
+//! watched on dashboards: the number of keys, the p-values of the summaries
+//! and the throughput of each algorithm.
+//!
+//! The metrics are labeled with the output file of the experiment, so that
+//! those of the experiments of a suite are told apart.
+
+use std::{io, net::ToSocketAddrs, sync::OnceLock, thread};
+
+use prometheus::{Encoder, GaugeVec, Opts, Registry, TextEncoder};
+use serde::Serialize;
+use serde_json::Value;
+use tiny_http::{Header, Response, Server};
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+struct Metrics {
+    registry: Registry,
+    num_keys: GaugeVec,
+    p_values: GaugeVec,
+    keys_per_second: GaugeVec,
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("flip_hash".to_owned()), None)?;
+        let gauge = |name: &str, help: &str, labels: &[&str]| {
+            let gauge = GaugeVec::new(Opts::new(name, help), labels)?;
+            registry.register(Box::new(gauge.clone()))?;
+            Ok::<_, prometheus::Error>(gauge)
+        };
+        Ok(Self {
+            num_keys: gauge(
+                "experiment_keys",
+                "The number of keys of the counts of an algorithm.",
+                &["experiment", "algo"],
+            )?,
+            p_values: gauge(
+                "experiment_p_value",
+                "A p-value of the summary of an algorithm, e.g., `g p-value`.",
+                &["experiment", "algo", "test"],
+            )?,
+            keys_per_second: gauge(
+                "experiment_keys_per_second",
+                "The throughput of a worker hashing the keys of an algorithm.",
+                &["experiment", "algo"],
+            )?,
+            registry,
+        })
+    }
+}
+
+/// Serves the metrics at `address`, from a background thread.
+///
+/// # Panics
+///
+/// Panics if the metrics are already served.
+pub(crate) fn serve(address: impl ToSocketAddrs) -> io::Result<()> {
+    let server = Server::http(address).map_err(io::Error::other)?;
+    METRICS
+        .set(Metrics::new().map_err(io::Error::other)?)
+        .unwrap_or_else(|_| panic!("The metrics are already served"));
+    thread::spawn(move || {
+        let metrics = METRICS.get().unwrap();
+        let content_type = Header::from_bytes("Content-Type", TextEncoder.format_type()).unwrap();
+        for request in server.incoming_requests() {
+            let mut body = Vec::new();
+            TextEncoder
+                .encode(&metrics.registry.gather(), &mut body)
+                .unwrap();
+            // The client may be gone.
+            let _ = request.respond(Response::from_data(body).with_header(content_type.clone()));
+        }
+    });
+    Ok(())
+}
+
+/// Records the state of `algo` in `experiment` after a step, if the metrics
+/// are served: its number of keys, and, from the line of its result, its
+/// p-values, i.e., the fields whose names end with `p-value`, and its
+/// throughput.
+pub(crate) fn record(experiment: &str, algo: &str, num_keys: u64, result: &impl Serialize) {
+    let Some(metrics) = METRICS.get() else {
+        return;
+    };
+    metrics
+        .num_keys
+        .with_label_values(&[experiment, algo])
+        .set(num_keys as f64);
+    let Ok(Value::Object(result)) = serde_json::to_value(result) else {
+        return;
+    };
+    for (field, value) in &result {
+        let Some(value) = value.as_f64() else {
+            continue;
+        };
+        if field == "keys per second" {
+            metrics
+                .keys_per_second
+                .with_label_values(&[experiment, algo])
+                .set(value);
+        } else if field.ends_with("p-value") {
+            metrics
+                .p_values
+                .with_label_values(&[experiment, algo, field])
+                .set(value);
+        }
+    }
+}
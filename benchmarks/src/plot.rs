@@ -0,0 +1,527 @@
+//! Charts of result files: the metrics of experiments as keys are hashed, the
+//! throughputs of `speed`, the latencies of `latency`, and the biases of
+//! `range-sweep`.
+//!
+//! Charts are written next to the result files, with the name of the chart
+//! as a suffix. Axes that span orders of magnitude are logarithmic: their
+//! values are the base-10 logarithms of the data, labeled with the data.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use plotters::{
+    coord::Shift,
+    prelude::*,
+    style::{register_font, FontStyle},
+};
+use serde_json::Value;
+
+/// The format of charts.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ImageFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+/// The fonts that are used if none is given, on Linux and macOS.
+const DEFAULT_FONT_PATHS: [&str; 4] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+];
+const FONT_FAMILY: &str = "sans-serif";
+const SIZE: (u32, u32) = (1024, 640);
+
+/// Registers the font of the text of charts, which is `path`, or the first
+/// of [`DEFAULT_FONT_PATHS`] that exists.
+pub(crate) fn load_font(path: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let path = match path {
+        Some(path) => path.to_owned(),
+        None => DEFAULT_FONT_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .ok_or("No font found, use --font to set one")?,
+    };
+    let bytes = fs::read(&path).map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+    register_font(FONT_FAMILY, FontStyle::Normal, Box::leak(bytes.into()))
+        .map_err(|_| format!("Invalid font {}", path.display()))?;
+    Ok(())
+}
+
+/// Draws the charts of the result file at `path` next to it, and returns
+/// their paths.
+pub(crate) fn plot_results(
+    path: &Path,
+    format: ImageFormat,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let results = Results::read(path)?;
+    let name = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    results
+        .charts(&name)
+        .iter()
+        .map(|chart| {
+            let chart_path = chart_path(path, &chart.name, format);
+            match format {
+                ImageFormat::Svg => {
+                    chart.draw(&SVGBackend::new(&chart_path, SIZE).into_drawing_area())?
+                }
+                ImageFormat::Png => {
+                    chart.draw(&BitMapBackend::new(&chart_path, SIZE).into_drawing_area())?
+                }
+            }
+            Ok(chart_path)
+        })
+        .collect()
+}
+
+/// A JSON object, e.g., a summary.
+pub(crate) type Object = serde_json::Map<String, Value>;
+
+/// The contents of a result file.
+pub(crate) enum Results {
+    /// The summaries of a JSON file of an experiment.
+    Summaries {
+        metadata: Option<Object>,
+        /// The summaries of each algorithm, in order.
+        summaries: BTreeMap<String, Vec<Object>>,
+    },
+    /// The biases of a CSV file of `range-sweep`.
+    RangeBiases {
+        metadata: Option<Object>,
+        header: Vec<String>,
+        rows: Vec<Vec<f64>>,
+    },
+}
+
+impl Results {
+    /// Reads the result file at `path`, which is a CSV file of `range-sweep`
+    /// or a JSON file of another experiment.
+    pub(crate) fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let mut metadata = None;
+        if path.extension().is_some_and(|extension| extension == "csv") {
+            let mut lines = contents.lines();
+            let mut header = None;
+            for line in lines.by_ref() {
+                if let Some(comment) = line.strip_prefix('#') {
+                    metadata = metadata.or(parse_metadata(comment.trim()));
+                } else {
+                    header = Some(line.split(',').map(str::to_owned).collect());
+                    break;
+                }
+            }
+            let rows = lines
+                .map(|line| {
+                    line.split(',')
+                        .map(str::parse::<f64>)
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Results::RangeBiases {
+                metadata,
+                header: header.ok_or("No header")?,
+                rows,
+            })
+        } else {
+            let mut summaries = BTreeMap::<String, Vec<Object>>::new();
+            for line in contents.lines().filter(|line| !line.is_empty()) {
+                let Value::Object(mut summary) = serde_json::from_str(line)? else {
+                    return Err("Expected an object".into());
+                };
+                if let Some(Value::String(algo)) = summary.remove("algo") {
+                    summaries.entry(algo).or_default().push(summary);
+                } else if metadata.is_none() {
+                    metadata = parse_metadata(line);
+                }
+            }
+            Ok(Results::Summaries {
+                metadata,
+                summaries,
+            })
+        }
+    }
+
+    /// Returns the metadata of the first run of the file.
+    pub(crate) fn metadata(&self) -> Option<&Object> {
+        match self {
+            Results::Summaries { metadata, .. } | Results::RangeBiases { metadata, .. } => {
+                metadata.as_ref()
+            }
+        }
+    }
+
+    /// Returns the charts of the results, whose series are named `name` if
+    /// they are not those of algorithms.
+    pub(crate) fn charts(&self, name: &str) -> Vec<Chart> {
+        match self {
+            Results::Summaries { summaries, .. } => summary_charts(summaries),
+            Results::RangeBiases { header, rows, .. } => range_bias_charts(name, header, rows),
+        }
+    }
+}
+
+fn parse_metadata(line: &str) -> Option<Object> {
+    match serde_json::from_str::<Value>(line).ok()? {
+        Value::Object(mut record) => match record.remove("metadata")? {
+            Value::Object(metadata) => Some(metadata),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A named series of points.
+type Series = (String, Vec<(f64, f64)>);
+
+fn summary_charts(summaries: &BTreeMap<String, Vec<Object>>) -> Vec<Chart> {
+    let Some(first) = summaries.values().flatten().next() else {
+        return Vec::new();
+    };
+    let mut charts = first
+        .iter()
+        .filter(|(metric, value)| *metric != "num keys" && value.is_number())
+        .map(|(metric, _)| Chart {
+            name: metric.clone(),
+            kind: ChartKind::Lines {
+                x_label: "num keys",
+                y_label: metric.clone(),
+                series: summaries
+                    .iter()
+                    .map(|(algo, algo_summaries)| {
+                        let points = algo_summaries
+                            .iter()
+                            .filter_map(|summary| {
+                                Some((
+                                    summary.get("num keys")?.as_f64()?,
+                                    summary.get(metric)?.as_f64()?,
+                                ))
+                            })
+                            .collect();
+                        (algo.clone(), points)
+                    })
+                    .collect(),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    // The throughputs of the last summary of each algorithm, by input size.
+    if first.contains_key("throughputs") {
+        let mut series = BTreeMap::<(String, u64), Vec<(f64, f64)>>::new();
+        for (algo, algo_summaries) in summaries {
+            let Some(Value::Array(throughputs)) = algo_summaries.last().unwrap().get("throughputs")
+            else {
+                continue;
+            };
+            for throughput in throughputs {
+                let field = |name| throughput.get(name).and_then(Value::as_f64);
+                if let (Some(range_end), Some(input_size_bytes), Some(keys_per_second)) = (
+                    field("range end"),
+                    field("input size bytes"),
+                    field("keys per second"),
+                ) {
+                    series
+                        .entry((algo.clone(), input_size_bytes as u64))
+                        .or_default()
+                        .push((range_end + 1.0, keys_per_second));
+                }
+            }
+        }
+        charts.push(Chart {
+            name: "throughputs".to_owned(),
+            kind: ChartKind::Lines {
+                x_label: "range len",
+                y_label: "keys per second".to_owned(),
+                series: series
+                    .into_iter()
+                    .map(|((algo, input_size_bytes), points)| {
+                        (format!("{algo}, {input_size_bytes} bytes"), points)
+                    })
+                    .collect(),
+            },
+        });
+    }
+
+    // The mean and 99th percentile latencies of the last summary of each
+    // algorithm, by range end.
+    if first.contains_key("latencies") {
+        let mut series = Vec::new();
+        for (algo, algo_summaries) in summaries {
+            let Some(Value::Array(latencies)) = algo_summaries.last().unwrap().get("latencies")
+            else {
+                continue;
+            };
+            for metric in ["mean nanoseconds", "p99 nanoseconds"] {
+                let points = latencies
+                    .iter()
+                    .filter_map(|latency| {
+                        Some((
+                            latency.get("range end")?.as_f64()? + 1.0,
+                            latency.get(metric)?.as_f64()?,
+                        ))
+                    })
+                    .collect();
+                series.push((
+                    format!("{algo}, {}", metric.split(' ').next().unwrap()),
+                    points,
+                ));
+            }
+        }
+        charts.push(Chart {
+            name: "latencies".to_owned(),
+            kind: ChartKind::Lines {
+                x_label: "range len",
+                y_label: "nanoseconds per key".to_owned(),
+                series,
+            },
+        });
+    }
+    charts
+}
+
+fn range_bias_charts(name: &str, header: &[String], rows: &[Vec<f64>]) -> Vec<Chart> {
+    let column = |name| header.iter().position(|column| column == name);
+    let (Some(range_end), Some(max_bias), Some(p_value)) =
+        (column("range end"), column("max bias"), column("p-value"))
+    else {
+        return Vec::new();
+    };
+    let max_biases = rows.iter().map(|row| row[max_bias]).collect::<Vec<_>>();
+    let largest_max_bias = max_biases.iter().copied().fold(0.0, f64::max);
+    vec![
+        Chart {
+            name: "max bias".to_owned(),
+            kind: ChartKind::Lines {
+                x_label: "range end",
+                y_label: "max bias".to_owned(),
+                series: vec![(
+                    name.to_owned(),
+                    rows.iter()
+                        .map(|row| (row[range_end], row[max_bias]))
+                        .collect(),
+                )],
+            },
+        },
+        Chart {
+            name: "max bias histogram".to_owned(),
+            kind: ChartKind::Histogram {
+                x_label: "max bias",
+                values: max_biases,
+                range: 0.0..largest_max_bias.max(f64::MIN_POSITIVE),
+            },
+        },
+        // The p-values are uniform if the hashes are.
+        Chart {
+            name: "p-value histogram".to_owned(),
+            kind: ChartKind::Histogram {
+                x_label: "p-value",
+                values: rows.iter().map(|row| row[p_value]).collect(),
+                range: 0.0..1.0,
+            },
+        },
+    ]
+}
+
+/// Returns the path of the chart `name` of the result file at `path`.
+fn chart_path(path: &Path, name: &str, format: ImageFormat) -> PathBuf {
+    // Only the extension of CSV files is removed, as the names of the other
+    // result files may contain dots, e.g., those of Zipf exponents.
+    let mut chart_path = if path.extension().is_some_and(|extension| extension == "csv") {
+        path.with_extension("").into_os_string()
+    } else {
+        path.as_os_str().to_owned()
+    };
+    chart_path.push(format!(
+        "_{}.{}",
+        name.replace(' ', "_"),
+        match format {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Png => "png",
+        }
+    ));
+    PathBuf::from(chart_path)
+}
+
+/// A chart of results, which is titled with its name.
+pub(crate) struct Chart {
+    pub(crate) name: String,
+    kind: ChartKind,
+}
+
+enum ChartKind {
+    /// Lines through the points of each series.
+    Lines {
+        x_label: &'static str,
+        y_label: String,
+        series: Vec<Series>,
+    },
+    /// The histogram of `values`, over `range`.
+    Histogram {
+        x_label: &'static str,
+        values: Vec<f64>,
+        range: Range<f64>,
+    },
+}
+
+impl Chart {
+    /// Returns the chart as an SVG document.
+    pub(crate) fn to_svg(&self) -> Result<String, Box<dyn Error>> {
+        let mut svg = String::new();
+        self.draw(&SVGBackend::with_string(&mut svg, SIZE).into_drawing_area())?;
+        Ok(svg)
+    }
+
+    fn draw<DB>(&self, root: &DrawingArea<DB, Shift>) -> Result<(), Box<dyn Error>>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+        match &self.kind {
+            ChartKind::Lines {
+                x_label,
+                y_label,
+                series,
+            } => {
+                let points = || series.iter().flat_map(|(_, points)| points);
+                let x_axis = Axis::new(points().map(|&(x, _)| x));
+                let y_axis = Axis::new(points().map(|&(_, y)| y));
+                let mut chart = ChartBuilder::on(root)
+                    .caption(&self.name, (FONT_FAMILY, 24))
+                    .margin(16)
+                    .x_label_area_size(48)
+                    .y_label_area_size(80)
+                    .build_cartesian_2d(x_axis.range.clone(), y_axis.range.clone())?;
+                chart
+                    .configure_mesh()
+                    .x_desc(*x_label)
+                    .y_desc(y_label)
+                    .x_label_formatter(&|&x| x_axis.label(x))
+                    .y_label_formatter(&|&y| y_axis.label(y))
+                    .draw()?;
+                for (index, (name, points)) in series.iter().enumerate() {
+                    let color = Palette99::pick(index).to_rgba();
+                    chart
+                        .draw_series(LineSeries::new(
+                            points
+                                .iter()
+                                .map(|&(x, y)| (x_axis.scale(x), y_axis.scale(y))),
+                            color.stroke_width(2),
+                        ))?
+                        .label(name)
+                        .legend(move |(x, y)| {
+                            PathElement::new([(x, y), (x + 16, y)], color.stroke_width(2))
+                        });
+                }
+                chart
+                    .configure_series_labels()
+                    .background_style(WHITE.mix(0.8))
+                    .border_style(BLACK)
+                    .draw()?;
+            }
+            ChartKind::Histogram {
+                x_label,
+                values,
+                range,
+            } => {
+                const NUM_BINS: usize = 20;
+                let bin_width = (range.end - range.start) / NUM_BINS as f64;
+                let mut counts = [0_u64; NUM_BINS];
+                for value in values {
+                    let bin = ((value - range.start) / bin_width) as usize;
+                    counts[bin.min(NUM_BINS - 1)] += 1;
+                }
+                let max_count = counts.iter().copied().max().unwrap_or(0);
+                let mut chart = ChartBuilder::on(root)
+                    .caption(&self.name, (FONT_FAMILY, 24))
+                    .margin(16)
+                    .x_label_area_size(48)
+                    .y_label_area_size(80)
+                    .build_cartesian_2d(range.clone(), 0.0..(max_count.max(1) as f64 * 1.05))?;
+                chart
+                    .configure_mesh()
+                    .x_desc(*x_label)
+                    .y_desc("count")
+                    .draw()?;
+                chart.draw_series(counts.iter().enumerate().map(|(bin, &count)| {
+                    let start = range.start + bin as f64 * bin_width;
+                    Rectangle::new(
+                        [(start, 0.0), (start + bin_width, count as f64)],
+                        BLUE.mix(0.6).filled(),
+                    )
+                }))?;
+            }
+        }
+        root.present()?;
+        Ok(())
+    }
+}
+
+/// An axis, which is logarithmic if its values are positive and span at
+/// least two orders of magnitude.
+struct Axis {
+    is_log: bool,
+    /// The range of the scaled values.
+    range: Range<f64>,
+}
+
+impl Axis {
+    fn new(values: impl Iterator<Item = f64> + Clone) -> Self {
+        let (min, max) = values
+            .filter(|value| value.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        if min > max {
+            return Self {
+                is_log: false,
+                range: 0.0..1.0,
+            };
+        }
+        let is_log = min > 0.0 && max / min >= 100.0;
+        let (start, end) = if is_log {
+            (min.log10(), max.log10())
+        } else {
+            (min, max)
+        };
+        // Flat series are centered.
+        let margin = if end > start {
+            (end - start) * 0.05
+        } else {
+            0.5
+        };
+        Self {
+            is_log,
+            range: start - margin..end + margin,
+        }
+    }
+
+    fn scale(&self, value: f64) -> f64 {
+        if self.is_log {
+            value.log10()
+        } else {
+            value
+        }
+    }
+
+    fn label(&self, scaled_value: f64) -> String {
+        if self.is_log {
+            format!("{:.1e}", 10_f64.powf(scaled_value))
+        } else if scaled_value != 0.0 && !(1e-2..1e4).contains(&scaled_value.abs()) {
+            format!("{scaled_value:.2e}")
+        } else {
+            format!("{scaled_value:.3}")
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Profiles of hashing keys with an algorithm, as flamegraphs, so that its
+//! hot spots can be found without setting up `perf` or other tools.
+//!
+//! The profiler samples the stacks of the process on a timer, with
+//! `setitimer`, so that it is only available on Unix.
+
+use std::{
+    hint::black_box,
+    io::Write,
+    ops::RangeToInclusive,
+    time::{Duration, Instant},
+};
+
+use flip_hash_testkit::{algo::Algorithm, keys::Keys};
+use pprof::{ProfilerGuardBuilder, Report};
+
+/// The number of keys that are drawn, then hashed, at once, so that the
+/// samples of drawing them can be told apart from those of hashing them.
+const BATCH_LEN: usize = 1024;
+
+/// The profile of hashing a number of keys.
+pub(crate) struct Profile {
+    pub(crate) num_keys: u64,
+    report: Report,
+}
+
+impl Profile {
+    /// Writes the flamegraph of the profile, as an SVG image.
+    pub(crate) fn write_flamegraph(&self, output: impl Write) -> pprof::Result<()> {
+        self.report.flamegraph(output)
+    }
+}
+
+/// Hashes keys of `keys` to `range` with `algorithm` on the current thread,
+/// for `duration`, sampling its stacks `frequency` times per second, and
+/// returns the profile.
+///
+/// The state of the algorithm, if any, is built before profiling, so that
+/// the profile is that of hashing only.
+pub(crate) fn profile(
+    algorithm: &impl Algorithm,
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: &Keys,
+    duration: Duration,
+    frequency: i32,
+) -> pprof::Result<Profile> {
+    algorithm.build(0, range);
+    let mut batch = vec![0; BATCH_LEN * input_size_bytes];
+    let guard = ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    let deadline = Instant::now() + duration;
+    let mut num_keys = 0;
+    while Instant::now() < deadline {
+        for key in batch.chunks_exact_mut(input_size_bytes) {
+            keys.fill(key);
+        }
+        for key in batch.chunks_exact(input_size_bytes) {
+            black_box(algorithm.hash(black_box(key), 0, range));
+        }
+        num_keys += BATCH_LEN as u64;
+    }
+    Ok(Profile {
+        num_keys,
+        report: guard.report().build()?,
+    })
+}
@@ -0,0 +1,130 @@
+//! The progress of experiments on the standard error, with a bar per
+//! algorithm of the keys it hashed, its current metrics, and the estimated
+//! time until the end of the run.
+//!
+//! When the standard error is not a terminal, e.g., in CI, the bars are
+//! hidden and a line is written after every step instead.
+
+use std::time::Instant;
+
+use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
+use serde::Serialize;
+use serde_json::Value;
+
+/// The bars of the algorithms of an experiment.
+pub(crate) struct Progress<'a> {
+    bars: MultiProgress,
+    /// The bar of each algorithm, with its name.
+    algo_bars: Vec<(String, ProgressBar)>,
+    deadline: Option<Instant>,
+    /// The field of the summaries that is shown along with the p-values.
+    metric: Option<&'a str>,
+}
+
+impl<'a> Progress<'a> {
+    /// Returns the progress of `algos`, whose bars are full after
+    /// `max_keys` keys, if set.
+    pub(crate) fn new(
+        algos: impl IntoIterator<Item = String>,
+        max_keys: Option<u64>,
+        deadline: Option<Instant>,
+        metric: Option<&'a str>,
+    ) -> Self {
+        let bars = MultiProgress::new();
+        let style = ProgressStyle::with_template(if max_keys.is_some() {
+            "{prefix:>28} [{bar:30}] {human_pos}/{human_len} keys {msg}"
+        } else {
+            "{prefix:>28} {human_pos} keys {msg}"
+        })
+        .unwrap()
+        .progress_chars("=> ");
+        let algo_bars = algos
+            .into_iter()
+            .map(|algo| {
+                let bar = bars.add(
+                    max_keys
+                        .map_or_else(ProgressBar::no_length, ProgressBar::new)
+                        .with_style(style.clone())
+                        .with_prefix(algo.clone()),
+                );
+                (algo, bar)
+            })
+            .collect();
+        Self {
+            bars,
+            algo_bars,
+            deadline,
+            metric,
+        }
+    }
+
+    /// Records a step of `num_keys` keys of `algo`, which hashed
+    /// `total_num_keys` keys in total, after which its summary is `summary`.
+    pub(crate) fn record(
+        &self,
+        algo: &str,
+        num_keys: u64,
+        total_num_keys: u64,
+        summary: &impl Serialize,
+    ) {
+        let Some((_, bar)) = self.algo_bars.iter().find(|(name, _)| name == algo) else {
+            return;
+        };
+        bar.inc(num_keys);
+        let metrics = self.metrics(summary);
+        if self.bars.is_hidden() {
+            eprintln!(
+                "Processed {:e} keys for {algo}{}",
+                total_num_keys,
+                metrics
+                    .iter()
+                    .map(|metric| format!(", {metric}"))
+                    .collect::<String>()
+            );
+            return;
+        }
+        let eta = [
+            bar.length().map(|_| bar.eta()),
+            self.deadline
+                .map(|deadline| deadline.saturating_duration_since(Instant::now())),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        bar.set_message(
+            eta.map(|eta| format!("ETA {}", HumanDuration(eta)))
+                .into_iter()
+                .chain(metrics)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+
+    /// Writes a line above the bars.
+    pub(crate) fn println(&self, line: &str) {
+        if self.bars.is_hidden() {
+            eprintln!("{line}");
+        } else {
+            self.bars.println(line).unwrap();
+        }
+    }
+
+    /// Leaves the bars as they are, below which the final lines are written.
+    pub(crate) fn finish(&self) {
+        for (_, bar) in &self.algo_bars {
+            bar.abandon();
+        }
+    }
+
+    /// Returns the texts of the metric and of the p-values of a summary.
+    fn metrics(&self, summary: &impl Serialize) -> Vec<String> {
+        let Ok(Value::Object(summary)) = serde_json::to_value(summary) else {
+            return Vec::new();
+        };
+        summary
+            .iter()
+            .filter(|(field, _)| field.ends_with("p-value") || Some(field.as_str()) == self.metric)
+            .filter_map(|(field, value)| Some(format!("{field} {:.3e}", value.as_f64()?)))
+            .collect()
+    }
+}
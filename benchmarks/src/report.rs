@@ -0,0 +1,460 @@
+//! A self-contained HTML report of the result files of a run, with the
+//! tables of their summaries and their charts inlined as SVG, e.g., to attach
+//! to changes of the hash functions.
+
+use std::{
+    error::Error,
+    fmt::Write,
+    fs, iter,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::{
+    histogram, manifest,
+    plot::{Object, Results},
+    snapshot,
+};
+
+/// The extensions of files that are not results, such as the checkpoints,
+/// the charts of results, and the counts of values, which are also the
+/// extensions of the stems of CSV files of counts.
+const IGNORED_EXTENSIONS: [&str; 9] = [
+    "checkpoint",
+    manifest::EXTENSION,
+    snapshot::EXTENSION,
+    "tmp",
+    "svg",
+    "png",
+    "html",
+    "ipynb",
+    histogram::EXTENSION,
+];
+
+/// The p-values of range sweeps below which ranges are counted as biased.
+const SIGNIFICANCE_LEVEL: f64 = 0.01;
+/// The false discovery rate that the Benjamini-Hochberg procedure controls
+/// across the p-values of the final summaries of all the result files.
+const FALSE_DISCOVERY_RATE: f64 = 0.01;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 1100px; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #f4f4f4; }
+svg { display: block; max-width: 100%; height: auto; }
+details { margin: 1em 0; }
+";
+
+/// Returns the HTML report of the result files in `dir` and its
+/// subdirectories. Files that cannot be read as results are skipped with a
+/// warning.
+pub(crate) fn report(dir: &Path) -> Result<String, Box<dyn Error>> {
+    let mut paths = Vec::new();
+    find_result_files(dir, &mut paths)?;
+    paths.sort();
+
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Flip Hash benchmarks: {title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Flip Hash benchmarks: {title}</h1>",
+        title = escape(&dir.display().to_string()),
+    )?;
+    if paths.is_empty() {
+        writeln!(html, "<p>No result files.</p>")?;
+    }
+    // The name of the file, the algorithm, the field and the value of each
+    // p-value of the final summaries.
+    let mut p_values = Vec::new();
+    for path in paths {
+        let results = match Results::read(&path) {
+            Ok(results) => results,
+            Err(err) => {
+                eprintln!("Skipping {}: {err}", path.display());
+                continue;
+            }
+        };
+        let name = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        writeln!(html, "<h2>{}</h2>", escape(&name))?;
+        write_results(&mut html, &path, &results)?;
+        p_values.extend(
+            final_p_values(&results)
+                .into_iter()
+                .map(|(algo, field, p_value)| (name.clone(), algo, field, p_value)),
+        );
+    }
+    if !p_values.is_empty() {
+        write_multiple_testing(&mut html, p_values)?;
+    }
+    writeln!(html, "</body>\n</html>")?;
+    Ok(html)
+}
+
+fn find_result_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+        if path.is_dir() {
+            find_result_files(&path, paths)?;
+        } else if ![
+            path.extension(),
+            Path::new(path.file_stem().unwrap_or_default()).extension(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|extension| {
+            IGNORED_EXTENSIONS
+                .iter()
+                .any(|ignored| extension == *ignored)
+        }) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn write_results(html: &mut String, path: &Path, results: &Results) -> Result<(), Box<dyn Error>> {
+    if let Some(metadata) = results.metadata() {
+        writeln!(html, "<details>\n<summary>Metadata</summary>")?;
+        write_table(
+            html,
+            &["field", "value"],
+            metadata
+                .iter()
+                .map(|(field, value)| vec![escape(field), escape(&value_text(value))]),
+        )?;
+        writeln!(html, "</details>")?;
+    }
+
+    match results {
+        Results::Summaries { summaries, .. } => {
+            // The last summary of each algorithm, which is of the most keys.
+            let last_summaries = summaries
+                .iter()
+                .filter_map(|(algo, algo_summaries)| Some((algo, algo_summaries.last()?)))
+                .collect::<Vec<_>>();
+            let Some((_, first)) = last_summaries.first() else {
+                writeln!(html, "<p>No summaries.</p>")?;
+                return Ok(());
+            };
+            let fields = first
+                .iter()
+                .filter(|(_, value)| !value.is_array() && !value.is_object())
+                .map(|(field, _)| field.as_str())
+                .collect::<Vec<_>>();
+            write_table(
+                html,
+                &[&["algo"], fields.as_slice()].concat(),
+                last_summaries.iter().map(|(algo, summary)| {
+                    [escape(algo)]
+                        .into_iter()
+                        .chain(fields.iter().map(|field| field_text(summary, field)))
+                        .collect()
+                }),
+            )?;
+            // Arrays of objects, e.g., the throughputs of `speed`, are tables
+            // of their own.
+            for (field, value) in first.iter() {
+                let Value::Array(items) = value else {
+                    continue;
+                };
+                let Some(Value::Object(first_item)) = items.first() else {
+                    continue;
+                };
+                let item_fields = first_item.keys().map(String::as_str).collect::<Vec<_>>();
+                writeln!(html, "<h3>{}</h3>", escape(field))?;
+                write_table(
+                    html,
+                    &[&["algo"], item_fields.as_slice()].concat(),
+                    last_summaries
+                        .iter()
+                        .flat_map(|(algo, summary)| {
+                            let items = match summary.get(field) {
+                                Some(Value::Array(items)) => items.as_slice(),
+                                _ => &[],
+                            };
+                            items.iter().filter_map(Value::as_object).map(|item| {
+                                [escape(algo)]
+                                    .into_iter()
+                                    .chain(item_fields.iter().map(|field| field_text(item, field)))
+                                    .collect()
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                )?;
+            }
+        }
+        Results::RangeBiases { header, rows, .. } => {
+            let column = |name| header.iter().position(|column| column == name);
+            let mut table = vec![vec![escape("num ranges"), escape(&rows.len().to_string())]];
+            if let (Some(range_end), Some(max_bias)) = (column("range end"), column("max bias")) {
+                if let Some(worst) = rows
+                    .iter()
+                    .max_by(|a, b| a[max_bias].total_cmp(&b[max_bias]))
+                {
+                    table.push(vec![
+                        escape("largest max bias"),
+                        number_text(worst[max_bias]),
+                    ]);
+                    table.push(vec![
+                        escape("range end of largest max bias"),
+                        number_text(worst[range_end]),
+                    ]);
+                }
+            }
+            if let Some(p_value) = column("p-value") {
+                let num_significant = rows
+                    .iter()
+                    .filter(|row| row[p_value] < SIGNIFICANCE_LEVEL)
+                    .count();
+                table.push(vec![
+                    escape(&format!("fraction of p-values < {SIGNIFICANCE_LEVEL}")),
+                    number_text(num_significant as f64 / rows.len().max(1) as f64),
+                ]);
+            }
+            write_table(html, &["statistic", "value"], table)?;
+        }
+    }
+
+    let name = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    for chart in results.charts(&name) {
+        match chart.to_svg() {
+            Ok(svg) => writeln!(html, "{svg}")?,
+            Err(err) => eprintln!("Cannot draw {} of {}: {err}", chart.name, path.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the algorithm, the test and the value of each p-value of the
+/// final summaries of the algorithms, apart from the statistics of the
+/// p-values of seeds, which are not p-values of tests themselves, or of each
+/// range of a sweep, whose algorithm is only in the name of its file.
+fn final_p_values(results: &Results) -> Vec<(String, String, f64)> {
+    match results {
+        Results::Summaries { summaries, .. } => summaries
+            .iter()
+            .filter_map(|(algo, algo_summaries)| Some((algo, algo_summaries.last()?)))
+            .flat_map(|(algo, summary)| {
+                summary
+                    .iter()
+                    .filter(|(field, _)| field.ends_with("p-value") && !field.starts_with("seed "))
+                    .filter_map(move |(field, value)| {
+                        Some((algo.clone(), field.clone(), value.as_f64()?))
+                    })
+            })
+            .collect(),
+        Results::RangeBiases { header, rows, .. } => {
+            let column = |name| header.iter().position(|column| column == name);
+            let (Some(range_end), Some(p_value)) = (column("range end"), column("p-value")) else {
+                return Vec::new();
+            };
+            rows.iter()
+                .filter(|row| !row[p_value].is_nan())
+                .map(|row| {
+                    (
+                        String::new(),
+                        format!("p-value of range end {}", row[range_end]),
+                        row[p_value],
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+/// Writes the p-values of all the result files, with their adjustments by the
+/// Benjamini-Hochberg procedure, as a suite runs dozens of tests, some of
+/// which are expected to have small p-values by chance.
+fn write_multiple_testing(
+    html: &mut String,
+    mut p_values: Vec<(String, String, String, f64)>,
+) -> Result<(), Box<dyn Error>> {
+    p_values.sort_by(|a, b| a.3.total_cmp(&b.3));
+    let adjusted_p_values = benjamini_hochberg(
+        &p_values
+            .iter()
+            .map(|&(.., p_value)| p_value)
+            .collect::<Vec<_>>(),
+    );
+    let num_discoveries = adjusted_p_values
+        .iter()
+        .filter(|&&p_value| p_value <= FALSE_DISCOVERY_RATE)
+        .count();
+    writeln!(html, "<h2>Multiple testing</h2>")?;
+    writeln!(
+        html,
+        "<p>{num_discoveries} of the {} p-values are significant at a false discovery rate \
+         of {FALSE_DISCOVERY_RATE}, with the Benjamini-Hochberg procedure.</p>",
+        p_values.len(),
+    )?;
+    write_table(
+        html,
+        &[
+            "file",
+            "algo",
+            "test",
+            "p-value",
+            "adjusted p-value",
+            "significant",
+        ],
+        iter::zip(p_values, adjusted_p_values).map(
+            |((name, algo, field, p_value), adjusted_p_value)| {
+                vec![
+                    escape(&name),
+                    escape(&algo),
+                    escape(&field),
+                    number_text(p_value),
+                    number_text(adjusted_p_value),
+                    escape(if adjusted_p_value <= FALSE_DISCOVERY_RATE {
+                        "yes"
+                    } else {
+                        "no"
+                    }),
+                ]
+            },
+        ),
+    )
+}
+
+/// Returns the Benjamini-Hochberg adjusted p-values of sorted p-values, which
+/// are at most a false discovery rate for the p-values that are significant at
+/// that rate.
+fn benjamini_hochberg(sorted_p_values: &[f64]) -> Vec<f64> {
+    let num_p_values = sorted_p_values.len();
+    let mut adjusted_p_values = sorted_p_values
+        .iter()
+        .enumerate()
+        .map(|(index, &p_value)| p_value * num_p_values as f64 / (index + 1) as f64)
+        .collect::<Vec<_>>();
+    // The adjusted p-values are the running minima from the largest p-value,
+    // so that they are monotonic.
+    let mut min = 1.0_f64;
+    for adjusted_p_value in adjusted_p_values.iter_mut().rev() {
+        min = min.min(*adjusted_p_value);
+        *adjusted_p_value = min;
+    }
+    adjusted_p_values
+}
+
+/// Writes a table of `rows`, whose cells are already escaped.
+fn write_table(
+    html: &mut String,
+    header: &[&str],
+    rows: impl IntoIterator<Item = Vec<String>>,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(html, "<table>\n<tr>")?;
+    for column in header {
+        write!(html, "<th>{}</th>", escape(column))?;
+    }
+    writeln!(html, "</tr>")?;
+    for row in rows {
+        write!(html, "<tr>")?;
+        for cell in row {
+            write!(html, "<td>{cell}</td>")?;
+        }
+        writeln!(html, "</tr>")?;
+    }
+    writeln!(html, "</table>")?;
+    Ok(())
+}
+
+/// Returns the escaped text of the field of an object, which is empty if the
+/// object has no such field.
+fn field_text(object: &Object, field: &str) -> String {
+    object
+        .get(field)
+        .map_or_else(String::new, |value| escape(&value_text(value)))
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if !number.is_u64() && !number.is_i64() => number_text(float),
+            _ => number.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+/// Returns the text of a float, with 4 significant digits.
+fn number_text(number: f64) -> String {
+    if number != 0.0 && !(1e-3..1e6).contains(&number.abs()) {
+        format!("{number:.3e}")
+    } else if number.fract() == 0.0 {
+        format!("{number}")
+    } else {
+        let precision = 3 - number.abs().log10().floor().clamp(-3.0, 3.0) as i32;
+        format!("{number:.*}", precision.max(0) as usize)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benjamini_hochberg_adjusted_p_values() {
+        // The adjusted p-values of R's `p.adjust(p, method = "BH")`.
+        let p_values = [
+            0.001, 0.008, 0.039, 0.041, 0.042, 0.06, 0.074, 0.205, 0.212, 0.216,
+        ];
+        let expected_adjusted_p_values = [
+            0.01,
+            0.04,
+            0.084,
+            0.084,
+            0.084,
+            0.1,
+            0.74 / 7.0,
+            0.216,
+            0.216,
+            0.216,
+        ];
+        for (adjusted_p_value, expected_adjusted_p_value) in
+            iter::zip(benjamini_hochberg(&p_values), expected_adjusted_p_values)
+        {
+            assert!((adjusted_p_value - expected_adjusted_p_value).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn benjamini_hochberg_caps_at_1() {
+        assert_eq!(benjamini_hochberg(&[0.6, 0.9, 1.0]), [1.0, 1.0, 1.0]);
+        assert!(benjamini_hochberg(&[]).is_empty());
+    }
+}
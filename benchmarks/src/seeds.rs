@@ -0,0 +1,124 @@
+//! The distributions of the metrics of an experiment across seeds, whose
+//! keys are hashed in steps under each seed in turn, to catch seeds for which
+//! an algorithm behaves unusually, which the metrics of all the keys average
+//! out.
+
+use std::collections::{BTreeMap, HashMap};
+
+use flip_hash_testkit::{acc::Accumulator, exp::Experiment};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::batches::{p_value, uniformity_ks_test};
+
+/// The accumulators of each seed of each algorithm, and the summaries of
+/// those with keys.
+pub(crate) struct SeedSweep<'a, E: Experiment> {
+    experiment: &'a E,
+    seeds: Vec<u64>,
+    accumulators: HashMap<String, Vec<E::Accumulator>>,
+    summaries: HashMap<String, Vec<Option<Map<String, Value>>>>,
+}
+
+/// The distributions of the metrics of an algorithm across seeds.
+#[derive(Serialize)]
+pub(crate) struct SeedSummary {
+    #[serde(rename = "num seeds")]
+    num_seeds: usize,
+    /// The seed of the smallest p-value, if the summaries have p-values.
+    #[serde(rename = "worst seed", skip_serializing_if = "Option::is_none")]
+    worst_seed: Option<u64>,
+    /// The minimum, median and maximum of each metric, as `seed min`, `seed
+    /// median` and `seed max` followed by its name, and the
+    /// Kolmogorov-Smirnov test of the uniformity of the p-values of the
+    /// seeds, as `seed ks p-value`.
+    #[serde(flatten)]
+    metrics: BTreeMap<String, f64>,
+}
+
+impl<'a, E: Experiment> SeedSweep<'a, E> {
+    pub(crate) fn new(experiment: &'a E, seeds: Vec<u64>) -> Self {
+        Self {
+            experiment,
+            seeds,
+            accumulators: HashMap::new(),
+            summaries: HashMap::new(),
+        }
+    }
+
+    /// Records a step of `algo` under the seed of index `seed_index`.
+    pub(crate) fn record(&mut self, algo: &str, seed_index: usize, accumulator: &E::Accumulator) {
+        let seed_accumulator = &mut self.accumulators.entry(algo.to_owned()).or_insert_with(|| {
+            self.seeds
+                .iter()
+                .map(|_| self.experiment.new_accumulator())
+                .collect()
+        })[seed_index];
+        seed_accumulator.merge(accumulator);
+        let Ok(Value::Object(summary)) =
+            serde_json::to_value(self.experiment.summary(seed_accumulator))
+        else {
+            panic!("The summaries are not objects");
+        };
+        self.summaries
+            .entry(algo.to_owned())
+            .or_insert_with(|| vec![None; self.seeds.len()])[seed_index] = Some(summary);
+    }
+
+    /// Returns the distributions of the metrics of `algo` across the seeds
+    /// with keys.
+    pub(crate) fn summary(&self, algo: &str) -> SeedSummary {
+        let summaries = self
+            .summaries
+            .get(algo)
+            .into_iter()
+            .flatten()
+            .zip(&self.seeds)
+            .filter_map(|(summary, &seed)| Some((summary.as_ref()?, seed)))
+            .collect::<Vec<_>>();
+        let mut metrics = BTreeMap::new();
+        if let Some((first_summary, _)) = summaries.first() {
+            for field in first_summary.keys().filter(|&field| field != "num keys") {
+                let mut values = summaries
+                    .iter()
+                    .filter_map(|(summary, _)| summary.get(field)?.as_f64())
+                    .collect::<Vec<_>>();
+                if values.is_empty() {
+                    continue;
+                }
+                values.sort_unstable_by(f64::total_cmp);
+                metrics.insert(format!("seed min {field}"), values[0]);
+                metrics.insert(format!("seed median {field}"), median(&values));
+                metrics.insert(format!("seed max {field}"), values[values.len() - 1]);
+            }
+        }
+        let p_values = summaries
+            .iter()
+            .filter_map(|&(summary, seed)| Some((p_value(summary)?.as_f64()?, seed)))
+            .collect::<Vec<_>>();
+        let worst_seed = p_values
+            .iter()
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|&(_, seed)| seed);
+        let (_, ks_p_value) =
+            uniformity_ks_test(p_values.iter().map(|&(p_value, _)| p_value).collect());
+        if let Some(ks_p_value) = ks_p_value {
+            metrics.insert("seed ks p-value".to_owned(), ks_p_value);
+        }
+        SeedSummary {
+            num_seeds: summaries.len(),
+            worst_seed,
+            metrics,
+        }
+    }
+}
+
+/// Returns the median of sorted values.
+fn median(values: &[f64]) -> f64 {
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
@@ -0,0 +1,126 @@
+//! Snapshots of the summaries of a run at an interval, e.g., of a quality
+//! monitor that runs indefinitely, which other tools can read at any time, as
+//! they replace the previous ones at once.
+//!
+//! A snapshot has the summary of all the keys of each algorithm, and that of
+//! the keys hashed since the previous snapshot, so that recent changes are not
+//! drowned out by the keys before them.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use flip_hash_testkit::{acc::Accumulator, exp::Experiment};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::metadata::{Metadata, MetadataRecord};
+
+pub(crate) const EXTENSION: &str = "snapshot";
+
+/// A line of a snapshot, with a summary of an algorithm.
+#[derive(Serialize)]
+struct SnapshotLine<'a, S> {
+    algo: &'a str,
+    /// Whether the summary is of all the keys, or of the last interval.
+    window: &'static str,
+    /// The duration of the window, since the start of the run or the
+    /// previous snapshot.
+    #[serde(rename = "window seconds")]
+    window_seconds: f64,
+    #[serde(flatten)]
+    summary: S,
+}
+
+/// The snapshots of a run, with the accumulators of the keys hashed since the
+/// previous one.
+pub(crate) struct Snapshots<A> {
+    path: PathBuf,
+    interval: Duration,
+    start: Instant,
+    last_snapshot: Instant,
+    interval_accumulators: HashMap<String, A>,
+}
+
+impl<A: Accumulator> Snapshots<A> {
+    /// Returns the snapshots of the run whose output file is `output_path`,
+    /// which are written next to it, with the [`EXTENSION`] extension.
+    pub(crate) fn new(output_path: &Path, interval: Duration) -> Self {
+        let mut path = output_path.as_os_str().to_owned();
+        path.push(".");
+        path.push(EXTENSION);
+        let now = Instant::now();
+        Self {
+            path: PathBuf::from(path),
+            interval,
+            start: now,
+            last_snapshot: now,
+            interval_accumulators: HashMap::new(),
+        }
+    }
+
+    /// Records the accumulator of a step of `algo`.
+    pub(crate) fn record(
+        &mut self,
+        algo: &str,
+        step_accumulator: &A,
+        new_accumulator: impl Fn() -> A,
+    ) {
+        self.interval_accumulators
+            .entry(algo.to_owned())
+            .or_insert_with(new_accumulator)
+            .merge(step_accumulator);
+    }
+
+    /// Returns whether the next snapshot is due.
+    pub(crate) fn is_due(&self) -> bool {
+        self.last_snapshot.elapsed() >= self.interval
+    }
+
+    /// Writes the snapshot of `accumulators`, the merged ones of the
+    /// algorithms, through a temporary file, and starts the next interval.
+    pub(crate) fn write<E: Experiment<Accumulator = A>>(
+        &mut self,
+        experiment: &E,
+        accumulators: &HashMap<String, A>,
+        metadata: &Metadata,
+    ) -> io::Result<()> {
+        let temporary_path = self.path.with_extension(format!("{EXTENSION}.tmp"));
+        let mut output = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut output, &MetadataRecord { metadata })?;
+        output.write_all(b"\n")?;
+        for (algo, accumulator) in accumulators.iter().sorted_by_key(|(algo, _)| *algo) {
+            serde_json::to_writer(
+                &mut output,
+                &SnapshotLine {
+                    algo,
+                    window: "total",
+                    window_seconds: self.start.elapsed().as_secs_f64(),
+                    summary: experiment.summary(accumulator),
+                },
+            )?;
+            output.write_all(b"\n")?;
+            if let Some(interval_accumulator) = self.interval_accumulators.get(algo) {
+                serde_json::to_writer(
+                    &mut output,
+                    &SnapshotLine {
+                        algo,
+                        window: "interval",
+                        window_seconds: self.last_snapshot.elapsed().as_secs_f64(),
+                        summary: experiment.summary(interval_accumulator),
+                    },
+                )?;
+                output.write_all(b"\n")?;
+            }
+        }
+        output.into_inner()?.sync_all()?;
+        fs::rename(temporary_path, &self.path)?;
+        self.interval_accumulators.clear();
+        self.last_snapshot = Instant::now();
+        Ok(())
+    }
+}
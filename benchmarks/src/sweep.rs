@@ -0,0 +1,191 @@
+use std::{
+    io,
+    ops::RangeToInclusive,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+use flip_hash_testkit::{
+    acc::{Accumulator, Moments},
+    algo::Algorithm,
+    exp::chi_squared_uniformity_test_p_value,
+    keys::Keys,
+    rng,
+};
+use serde::Serialize;
+
+/// The bias of the hashes of a number of keys to a range.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RangeBias {
+    range: RangeToInclusive<u64>,
+    num_keys: u64,
+    /// The largest relative difference between the number of keys of a
+    /// value and the expected one.
+    max_bias: f64,
+    /// The standard deviation of the relative differences between the numbers
+    /// of keys of the values and the expected one.
+    bias_std_dev: f64,
+    /// The L2 distance between the distribution of hashes and the uniform
+    /// one.
+    l2_distance: f64,
+    p_value: f64,
+}
+
+/// Hashes `num_keys_per_value` keys of `keys` per value of each range of `ranges`,
+/// so that the biases of the ranges are measured with the same precision,
+/// and returns the biases in the order of `ranges`.
+///
+/// The ranges are spread over `num_threads` threads, each of which hashes
+/// keys with its own algorithm, from `new_algorithm`. Given a seed, the
+/// biases do not depend on the number of threads.
+///
+/// Once `stop` is set, the threads stop after their current ranges, and only
+/// the biases of the ranges before the first unmeasured one are returned.
+pub(crate) fn range_sweep<A: Algorithm>(
+    new_algorithm: impl Fn() -> A + Sync,
+    ranges: &[RangeToInclusive<u64>],
+    num_keys_per_value: u64,
+    input_size_bytes: usize,
+    keys: &Keys,
+    num_threads: usize,
+    stop: &AtomicBool,
+) -> Vec<RangeBias> {
+    let next_index = AtomicUsize::new(0);
+    let mut biases = thread::scope(|scope| {
+        let workers = (0..num_threads.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let algorithm = new_algorithm();
+                    let mut biases = Vec::new();
+                    loop {
+                        if stop.load(Ordering::Relaxed) {
+                            return biases;
+                        }
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(&range) = ranges.get(index) else {
+                            return biases;
+                        };
+                        // Each range has its own stream, so that its bias does not
+                        // depend on the thread that measures it.
+                        rng::set_stream(index as u64 + 1);
+                        Keys::restart_thread();
+                        biases.push((
+                            index,
+                            range_bias(
+                                &algorithm,
+                                range,
+                                num_keys_per_value,
+                                input_size_bytes,
+                                keys,
+                            ),
+                        ));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    biases.sort_by_key(|&(index, _)| index);
+    biases
+        .into_iter()
+        .enumerate()
+        .take_while(|&(position, (index, _))| position == index)
+        .map(|(_, (_, bias))| bias)
+        .collect()
+}
+
+fn range_bias(
+    algorithm: &impl Algorithm,
+    range: RangeToInclusive<u64>,
+    num_keys_per_value: u64,
+    input_size_bytes: usize,
+    keys: &Keys,
+) -> RangeBias {
+    let range_len = usize::try_from(range.end).unwrap().checked_add(1).unwrap();
+    let num_keys = num_keys_per_value * range_len as u64;
+    let mut counts = vec![0_u64; range_len];
+    let mut bytes = vec![0; input_size_bytes];
+    for _ in 0..num_keys {
+        keys.fill(&mut bytes);
+        counts[usize::try_from(algorithm.hash(&bytes, 0, range)).unwrap()] += 1;
+    }
+
+    let mut loads = Moments::new();
+    counts.iter().for_each(|&c| loads.record(c as f64));
+    let max_bias = [loads.min(), loads.max()]
+        .map(|load| (load / num_keys_per_value as f64 - 1.0).abs())
+        .into_iter()
+        .fold(0.0, f64::max);
+    let l2_distance = counts
+        .iter()
+        .map(|&c| c as f64 / num_keys as f64)
+        .map(|p| (p - 1.0 / range_len as f64).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    let p_value = if range_len > 1 {
+        chi_squared_uniformity_test_p_value(&counts)
+    } else {
+        1.0
+    };
+    RangeBias {
+        range,
+        num_keys,
+        max_bias,
+        bias_std_dev: loads.std_dev() / num_keys_per_value as f64,
+        l2_distance,
+        p_value,
+    }
+}
+
+/// The summary of a range sweep, over all its range ends.
+#[derive(Serialize)]
+pub(crate) struct RangeSweepSummary {
+    #[serde(rename = "num keys")]
+    pub(crate) num_keys: u64,
+    /// The largest max bias of the range ends.
+    #[serde(rename = "max bias")]
+    max_bias: f64,
+    /// The smallest p-value of the range ends, multiplied by their number,
+    /// which is the Bonferroni correction of the tests of each range end.
+    #[serde(rename = "p-value")]
+    p_value: f64,
+}
+
+impl RangeSweepSummary {
+    pub(crate) fn new(biases: &[RangeBias]) -> Self {
+        Self {
+            num_keys: biases.iter().map(|bias| bias.num_keys).sum(),
+            max_bias: biases.iter().map(|bias| bias.max_bias).fold(0.0, f64::max),
+            p_value: (biases.iter().map(|bias| bias.p_value).fold(1.0, f64::min)
+                * biases.len() as f64)
+                .min(1.0),
+        }
+    }
+}
+
+/// Writes `biases` as CSV, one range per line.
+pub(crate) fn write_range_biases(
+    output: &mut impl io::Write,
+    biases: &[RangeBias],
+) -> Result<(), io::Error> {
+    writeln!(
+        output,
+        "range end,num keys,max bias,bias std dev,l2 distance,p-value"
+    )?;
+    for bias in biases {
+        writeln!(
+            output,
+            "{},{},{:e},{:e},{:e},{}",
+            bias.range.end,
+            bias.num_keys,
+            bias.max_bias,
+            bias.bias_std_dev,
+            bias.l2_distance,
+            bias.p_value
+        )?;
+    }
+    output.flush()
+}
@@ -0,0 +1,146 @@
+//! Thresholds of quality that the final results of algorithms must meet, so
+//! that changes to the hash functions can be gated, e.g., in CI, by the exit
+//! code of the benchmarks.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// The thresholds, which are not checked unless set.
+#[derive(clap::Args, Clone, Debug, Default)]
+pub(crate) struct Thresholds {
+    /// Fails if a p-value of the final summary of an algorithm is below this,
+    /// e.g., 1e-6. The p-values of range sweeps are Bonferroni-corrected for
+    /// the number of range ends.
+    #[clap(long = "assert-p-value-min", global = true)]
+    p_value_min: Option<f64>,
+    /// Fails if the max bias of the final summary of an algorithm, or of a
+    /// range end of a range sweep, is above this.
+    #[clap(long = "assert-max-bias", global = true)]
+    max_bias: Option<f64>,
+    /// Fails if an algorithm hashes fewer keys, e.g., as `--max-duration`
+    /// stops it first, so that underpowered tests do not pass.
+    #[clap(long = "assert-min-keys", global = true)]
+    min_keys: Option<u64>,
+}
+
+impl Thresholds {
+    /// Returns the thresholds that are not on the statistics of the
+    /// summaries, e.g., for the experiments of a suite that report none.
+    pub(crate) fn without_statistics(&self) -> Self {
+        Self {
+            min_keys: self.min_keys,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the failures of the final summary of an algorithm that hashed
+    /// `num_keys` keys, which are empty if it meets the thresholds.
+    ///
+    /// All the fields whose names end with `p-value` are p-values. Those
+    /// that are `null`, e.g., before any key is hashed, or that are left out,
+    /// e.g., without enough keys to compute them, are not checked, and a set
+    /// threshold that no field is checked against fails, so that the gate
+    /// does not pass without checking anything.
+    pub(crate) fn failures(&self, num_keys: u64, summary: &impl Serialize) -> Vec<String> {
+        let mut failures = Vec::new();
+        if let Some(min_keys) = self.min_keys {
+            if num_keys < min_keys {
+                failures.push(format!("{num_keys} keys < {min_keys}"));
+            }
+        }
+        let mut num_p_values = 0;
+        let mut num_max_biases = 0;
+        if let Ok(Value::Object(summary)) = serde_json::to_value(summary) {
+            for (field, value) in &summary {
+                let Some(value) = value.as_f64() else {
+                    continue;
+                };
+                if field.ends_with("p-value") {
+                    num_p_values += 1;
+                    if let Some(p_value_min) = self.p_value_min {
+                        if value < p_value_min {
+                            failures.push(format!("{field} {value:.3e} < {p_value_min}"));
+                        }
+                    }
+                } else if field == "max bias" {
+                    num_max_biases += 1;
+                    if let Some(max_bias) = self.max_bias {
+                        if value > max_bias {
+                            failures.push(format!("{field} {value:.3e} > {max_bias}"));
+                        }
+                    }
+                }
+            }
+        }
+        if self.p_value_min.is_some() && num_p_values == 0 {
+            failures.push("no p-value to check".to_string());
+        }
+        if self.max_bias.is_some() && num_max_biases == 0 {
+            failures.push("no max bias to check".to_string());
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            p_value_min: Some(1e-6),
+            max_bias: Some(0.01),
+            min_keys: Some(1000),
+        }
+    }
+
+    #[test]
+    fn summaries_that_meet_the_thresholds_pass() {
+        let summary = json!({
+            "max bias": 0.005,
+            "chi-squared p-value": 0.3,
+            "ks p-value": 1e-3,
+        });
+        assert!(thresholds().failures(1000, &summary).is_empty());
+        assert!(Thresholds::default().failures(0, &json!(null)).is_empty());
+    }
+
+    #[test]
+    fn summaries_that_do_not_meet_the_thresholds_fail() {
+        let summary = json!({
+            "max bias": 0.02,
+            "chi-squared p-value": 1e-9,
+            "ks p-value": 0.5,
+        });
+        assert_eq!(
+            thresholds().failures(999, &summary),
+            [
+                "999 keys < 1000",
+                "chi-squared p-value 1.000e-9 < 0.000001",
+                "max bias 2.000e-2 > 0.01",
+            ]
+        );
+    }
+
+    #[test]
+    fn summaries_without_the_fields_of_the_thresholds_fail() {
+        let summary = json!({ "max bias": null, "ks p-value": null, "num keys": 0 });
+        assert_eq!(
+            thresholds().failures(1000, &summary),
+            ["no p-value to check", "no max bias to check"]
+        );
+        assert_eq!(
+            thresholds().failures(1000, &json!([0.5])),
+            ["no p-value to check", "no max bias to check"]
+        );
+        let thresholds = Thresholds {
+            p_value_min: Some(1e-6),
+            ..Thresholds::default()
+        };
+        assert!(thresholds
+            .failures(0, &json!({ "p-value": 0.5 }))
+            .is_empty());
+    }
+}
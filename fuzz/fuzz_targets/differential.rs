@@ -0,0 +1,54 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use flip_hash::{
+    flip_hash_64_with_seed, flip_hash_const_xxh3_128_with_seed, flip_hash_const_xxh3_64_with_seed,
+    flip_hash_xxh3_128_with_seed, flip_hash_xxh3_64_with_seed, reference,
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    key: &'a [u8],
+    seed: u64,
+    range_end_64: u64,
+    range_end_128: u128,
+}
+
+fuzz_target!(|input: Input| {
+    let Input {
+        key,
+        seed,
+        range_end_64,
+        range_end_128,
+    } = input;
+
+    let u64_key = key
+        .iter()
+        .take(8)
+        .fold(0_u64, |k, &byte| k << 8 | u64::from(byte));
+    assert_eq!(
+        flip_hash_64_with_seed(u64_key, seed, ..=range_end_64),
+        reference::flip_hash_64_with_seed(u64_key, seed, ..=range_end_64)
+    );
+
+    let reference_hash_64 = reference::flip_hash_xxh3_64_with_seed(key, seed, ..=range_end_64);
+    assert_eq!(
+        flip_hash_xxh3_64_with_seed(key, seed, ..=range_end_64),
+        reference_hash_64
+    );
+    assert_eq!(
+        flip_hash_const_xxh3_64_with_seed(key, seed, ..=range_end_64),
+        reference_hash_64
+    );
+
+    let reference_hash_128 = reference::flip_hash_xxh3_128_with_seed(key, seed, ..=range_end_128);
+    assert_eq!(
+        flip_hash_xxh3_128_with_seed(key, seed, ..=range_end_128),
+        reference_hash_128
+    );
+    assert_eq!(
+        flip_hash_const_xxh3_128_with_seed(key, seed, ..=range_end_128),
+        reference_hash_128
+    );
+});
@@ -0,0 +1,32 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use flip_hash::{flip_hash_64, flip_hash_64_with_seed};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    key: u64,
+    seed: u64,
+    range_end: u64,
+}
+
+fuzz_target!(|input: Input| {
+    let Input {
+        key,
+        seed,
+        range_end,
+    } = input;
+
+    let hash = flip_hash_64_with_seed(key, seed, ..=range_end);
+    assert!(hash <= range_end);
+    if let Some(next_range_end) = range_end.checked_add(1) {
+        let next_hash = flip_hash_64_with_seed(key, seed, ..=next_range_end);
+        assert!(next_hash == hash || next_hash == next_range_end);
+    }
+
+    assert_eq!(
+        flip_hash_64(key, ..=range_end),
+        flip_hash_64_with_seed(key, 0, ..=range_end)
+    );
+});
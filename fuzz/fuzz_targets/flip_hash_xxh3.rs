@@ -0,0 +1,47 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use flip_hash::{
+    flip_hash_const_xxh3_128_with_seed, flip_hash_const_xxh3_64_with_seed,
+    flip_hash_xxh3_128_with_seed, flip_hash_xxh3_64_with_seed,
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    key: &'a [u8],
+    seed: u64,
+    range_end_64: u64,
+    range_end_128: u128,
+}
+
+// Checks that the hash is in the range and stable when the range grows by one.
+macro_rules! check {
+    ($flip_hash_with_seed: ident, $key: expr, $seed: expr, $range_end: expr) => {{
+        let hash = $flip_hash_with_seed($key, $seed, ..=$range_end);
+        assert!(hash <= $range_end);
+        if let Some(next_range_end) = $range_end.checked_add(1) {
+            let next_hash = $flip_hash_with_seed($key, $seed, ..=next_range_end);
+            assert!(next_hash == hash || next_hash == next_range_end);
+        }
+        hash
+    }};
+}
+
+fuzz_target!(|input: Input| {
+    let Input {
+        key,
+        seed,
+        range_end_64,
+        range_end_128,
+    } = input;
+
+    assert_eq!(
+        check!(flip_hash_xxh3_64_with_seed, key, seed, range_end_64),
+        check!(flip_hash_const_xxh3_64_with_seed, key, seed, range_end_64)
+    );
+    assert_eq!(
+        check!(flip_hash_xxh3_128_with_seed, key, seed, range_end_128),
+        check!(flip_hash_const_xxh3_128_with_seed, key, seed, range_end_128)
+    );
+});
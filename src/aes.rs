@@ -0,0 +1,179 @@
+//! A hash function of byte slices that is built on AES rounds, in the manner
+//! of GxHash, and that is hardware-accelerated where AES-NI is available.
+//!
+//! The same hash function is implemented in software, so that the hashes do
+//! not depend on the CPU that computes them. The hardware implementation is
+//! selected at runtime if the `std` feature is enabled, and at compile time
+//! otherwise.
+
+/// Round keys of the finalization, from the hexadecimal digits of pi.
+const FINALIZATION_KEYS: [[u8; 16]; 3] = [
+    [
+        0x24, 0x3F, 0x6A, 0x88, 0x85, 0xA3, 0x08, 0xD3, 0x13, 0x19, 0x8A, 0x2E, 0x03, 0x70, 0x73,
+        0x44,
+    ],
+    [
+        0xA4, 0x09, 0x38, 0x22, 0x29, 0x9F, 0x31, 0xD0, 0x08, 0x2E, 0xFA, 0x98, 0xEC, 0x4E, 0x6C,
+        0x89,
+    ],
+    [
+        0x45, 0x28, 0x21, 0xE6, 0x38, 0xD0, 0x13, 0x77, 0xBE, 0x54, 0x66, 0xCF, 0x34, 0xE9, 0x0C,
+        0x6C,
+    ],
+];
+
+#[inline(always)]
+pub(crate) fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
+    hash_tweaked(
+        key,
+        seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+    )
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "aes"))]
+#[inline(always)]
+fn hash_tweaked(key: &[u8], tweak: u64) -> u64 {
+    // SAFETY: AES-NI is enabled at compile time.
+    unsafe { x86_64::hash(key, tweak) }
+}
+
+#[cfg(all(target_arch = "x86_64", not(target_feature = "aes"), feature = "std"))]
+#[inline(always)]
+fn hash_tweaked(key: &[u8], tweak: u64) -> u64 {
+    if std::is_x86_feature_detected!("aes") {
+        // SAFETY: AES-NI is available.
+        unsafe { x86_64::hash(key, tweak) }
+    } else {
+        soft::hash(key, tweak)
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", any(target_feature = "aes", feature = "std"))))]
+#[inline(always)]
+fn hash_tweaked(key: &[u8], tweak: u64) -> u64 {
+    soft::hash(key, tweak)
+}
+
+/// Splits `key` into 16-byte blocks, the last one being padded with zeros.
+#[inline(always)]
+fn blocks(key: &[u8]) -> impl Iterator<Item = [u8; 16]> + '_ {
+    key.chunks(16).map(|chunk| {
+        let mut block = [0; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block
+    })
+}
+
+/// The initial state, which depends on the length of the key so that padding
+/// does not cause collisions.
+#[inline(always)]
+fn initial_state(key: &[u8], tweak: u64) -> [u8; 16] {
+    let mut state = [0; 16];
+    state[..8].copy_from_slice(&tweak.to_le_bytes());
+    state[8..].copy_from_slice(&(key.len() as u64).to_le_bytes());
+    state
+}
+
+#[inline(always)]
+fn fold(state: [u8; 16]) -> u64 {
+    u64::from_le_bytes(state[..8].try_into().unwrap())
+        ^ u64::from_le_bytes(state[8..].try_into().unwrap())
+}
+
+pub(crate) mod soft {
+    use super::{blocks, fold, initial_state, FINALIZATION_KEYS};
+
+    #[rustfmt::skip]
+    const SBOX: [u8; 256] = [
+        0x63, 0x7C, 0x77, 0x7B, 0xF2, 0x6B, 0x6F, 0xC5, 0x30, 0x01, 0x67, 0x2B, 0xFE, 0xD7, 0xAB, 0x76,
+        0xCA, 0x82, 0xC9, 0x7D, 0xFA, 0x59, 0x47, 0xF0, 0xAD, 0xD4, 0xA2, 0xAF, 0x9C, 0xA4, 0x72, 0xC0,
+        0xB7, 0xFD, 0x93, 0x26, 0x36, 0x3F, 0xF7, 0xCC, 0x34, 0xA5, 0xE5, 0xF1, 0x71, 0xD8, 0x31, 0x15,
+        0x04, 0xC7, 0x23, 0xC3, 0x18, 0x96, 0x05, 0x9A, 0x07, 0x12, 0x80, 0xE2, 0xEB, 0x27, 0xB2, 0x75,
+        0x09, 0x83, 0x2C, 0x1A, 0x1B, 0x6E, 0x5A, 0xA0, 0x52, 0x3B, 0xD6, 0xB3, 0x29, 0xE3, 0x2F, 0x84,
+        0x53, 0xD1, 0x00, 0xED, 0x20, 0xFC, 0xB1, 0x5B, 0x6A, 0xCB, 0xBE, 0x39, 0x4A, 0x4C, 0x58, 0xCF,
+        0xD0, 0xEF, 0xAA, 0xFB, 0x43, 0x4D, 0x33, 0x85, 0x45, 0xF9, 0x02, 0x7F, 0x50, 0x3C, 0x9F, 0xA8,
+        0x51, 0xA3, 0x40, 0x8F, 0x92, 0x9D, 0x38, 0xF5, 0xBC, 0xB6, 0xDA, 0x21, 0x10, 0xFF, 0xF3, 0xD2,
+        0xCD, 0x0C, 0x13, 0xEC, 0x5F, 0x97, 0x44, 0x17, 0xC4, 0xA7, 0x7E, 0x3D, 0x64, 0x5D, 0x19, 0x73,
+        0x60, 0x81, 0x4F, 0xDC, 0x22, 0x2A, 0x90, 0x88, 0x46, 0xEE, 0xB8, 0x14, 0xDE, 0x5E, 0x0B, 0xDB,
+        0xE0, 0x32, 0x3A, 0x0A, 0x49, 0x06, 0x24, 0x5C, 0xC2, 0xD3, 0xAC, 0x62, 0x91, 0x95, 0xE4, 0x79,
+        0xE7, 0xC8, 0x37, 0x6D, 0x8D, 0xD5, 0x4E, 0xA9, 0x6C, 0x56, 0xF4, 0xEA, 0x65, 0x7A, 0xAE, 0x08,
+        0xBA, 0x78, 0x25, 0x2E, 0x1C, 0xA6, 0xB4, 0xC6, 0xE8, 0xDD, 0x74, 0x1F, 0x4B, 0xBD, 0x8B, 0x8A,
+        0x70, 0x3E, 0xB5, 0x66, 0x48, 0x03, 0xF6, 0x0E, 0x61, 0x35, 0x57, 0xB9, 0x86, 0xC1, 0x1D, 0x9E,
+        0xE1, 0xF8, 0x98, 0x11, 0x69, 0xD9, 0x8E, 0x94, 0x9B, 0x1E, 0x87, 0xE9, 0xCE, 0x55, 0x28, 0xDF,
+        0x8C, 0xA1, 0x89, 0x0D, 0xBF, 0xE6, 0x42, 0x68, 0x41, 0x99, 0x2D, 0x0F, 0xB0, 0x54, 0xBB, 0x16,
+    ];
+
+    /// Multiplication by 2 in GF(2^8).
+    #[inline(always)]
+    fn xtime(b: u8) -> u8 {
+        (b << 1) ^ (((b >> 7) & 1) * 0x1B)
+    }
+
+    /// One AES encryption round, as computed by the `AESENC` instruction.
+    #[inline(always)]
+    pub(crate) fn aesenc(state: [u8; 16], round_key: [u8; 16]) -> [u8; 16] {
+        // The state is stored column by column.
+        let mut shifted = [0; 16];
+        for (i, b) in shifted.iter_mut().enumerate() {
+            let (row, column) = (i % 4, i / 4);
+            *b = SBOX[state[row + 4 * ((column + row) % 4)] as usize];
+        }
+        let mut mixed = [0; 16];
+        for column in 0..4 {
+            let a = &shifted[4 * column..4 * column + 4];
+            let m = &mut mixed[4 * column..4 * column + 4];
+            let all = a[0] ^ a[1] ^ a[2] ^ a[3];
+            for row in 0..4 {
+                m[row] = a[row] ^ all ^ xtime(a[row] ^ a[(row + 1) % 4]);
+            }
+        }
+        for (m, k) in mixed.iter_mut().zip(round_key) {
+            *m ^= k;
+        }
+        mixed
+    }
+
+    #[inline(always)]
+    pub(crate) fn hash(key: &[u8], tweak: u64) -> u64 {
+        let mut state = initial_state(key, tweak);
+        for block in blocks(key) {
+            state = aesenc(state, block);
+        }
+        for round_key in FINALIZATION_KEYS {
+            state = aesenc(state, round_key);
+        }
+        fold(state)
+    }
+}
+
+#[cfg(all(
+    target_arch = "x86_64",
+    any(target_feature = "aes", feature = "std", test)
+))]
+pub(crate) mod x86_64 {
+    use core::arch::x86_64::{__m128i, _mm_aesenc_si128, _mm_loadu_si128, _mm_storeu_si128};
+
+    use super::{blocks, fold, initial_state, FINALIZATION_KEYS};
+
+    #[inline(always)]
+    unsafe fn load(bytes: &[u8; 16]) -> __m128i {
+        _mm_loadu_si128(bytes.as_ptr() as *const __m128i)
+    }
+
+    /// # Safety
+    ///
+    /// AES-NI must be available.
+    #[target_feature(enable = "aes")]
+    pub(crate) unsafe fn hash(key: &[u8], tweak: u64) -> u64 {
+        let mut state = load(&initial_state(key, tweak));
+        for block in blocks(key) {
+            state = _mm_aesenc_si128(state, load(&block));
+        }
+        for round_key in &FINALIZATION_KEYS {
+            state = _mm_aesenc_si128(state, load(round_key));
+        }
+        let mut bytes = [0; 16];
+        _mm_storeu_si128(bytes.as_mut_ptr() as *mut __m128i, state);
+        fold(bytes)
+    }
+}
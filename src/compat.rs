@@ -0,0 +1,159 @@
+//! Consistent hash functions of other libraries, reimplemented bit for bit,
+//! so that services can agree with the ones that use those libraries, e.g.,
+//! while migrating to Flip Hash.
+//!
+//! They are not variants of Flip Hash and do not hash keys to the same
+//! values.
+
+use alloc::{format, vec::Vec};
+use core::{fmt, ops::RangeToInclusive};
+
+use crate::FlipHashError;
+
+/// Hashes `input` to a bucket of `..buckets`, as Guava's
+/// `Hashing.consistentHash(long, int)` does, i.e., with Jump Hash and Guava's
+/// linear congruential generator.
+///
+/// `input` is the two's complement of the `long` that is passed to Guava.
+/// `Hashing.consistentHash(HashCode, int)` hashes `hashCode.padToLong()`.
+///
+/// Unlike Flip Hash, it has a time complexity that is logarithmic in the
+/// number of buckets.
+///
+/// # Panics
+///
+/// Panics if `buckets` is zero or larger than `i32::MAX`, as Guava only
+/// accepts positive `int`s.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::compat::guava_consistent_hash;
+///
+/// assert_eq!(guava_consistent_hash(10863919174838991, 11), 6);
+/// // Hashing.consistentHash(-2L, 100)
+/// assert_eq!(guava_consistent_hash(-2_i64 as u64, 100), 35);
+/// ```
+pub fn guava_consistent_hash(input: u64, buckets: u32) -> u32 {
+    assert!(
+        buckets > 0 && buckets <= i32::MAX as u32,
+        "buckets must be in 1..=i32::MAX: {buckets}"
+    );
+    let mut generator = LinearCongruentialGenerator(input);
+    let mut candidate = 0;
+    loop {
+        // Casting from f64 to i32 saturates, as it does in Java.
+        let next = ((candidate + 1) as f64 / generator.next_double()) as i32;
+        if next >= 0 && (next as u32) < buckets {
+            candidate = next;
+        } else {
+            return candidate as u32;
+        }
+    }
+}
+
+/// Guava's `LinearCongruentialGenerator`, an LCG with the multiplier of
+/// 64-bit MMIX.
+struct LinearCongruentialGenerator(u64);
+
+impl LinearCongruentialGenerator {
+    fn next_double(&mut self) -> f64 {
+        self.0 = self.0.wrapping_mul(2862933555777941757).wrapping_add(1);
+        // Java's int addition wraps around, which makes the double negative
+        // if the 31 high bits of the state are all ones.
+        ((self.0 >> 33) as i32).wrapping_add(1) as f64 / (1_u64 << 31) as f64
+    }
+}
+
+/// A consistent hash ring of servers that is compatible with libketama, the
+/// ring of most memcached clients (e.g., spymemcached's
+/// `KetamaNodeLocator`, or libmemcached with
+/// `MEMCACHED_BEHAVIOR_KETAMA_WEIGHTED`), when all servers have the same
+/// weight.
+///
+/// Every server is mapped to 160 points of the ring, which are taken from the
+/// MD5 digests of `"{server}-{i}"` for `i` in `0..40`, and a key is hashed to
+/// the server of the first point that is not lower than the first 4 bytes of
+/// its MD5 digest.
+///
+/// libmemcached names the servers on the default port 11211 by their hosts
+/// alone, so those servers must be given as `"{host}"` to match it. Its
+/// unweighted `MEMCACHED_BEHAVIOR_KETAMA` takes a single point per digest,
+/// and does not match.
+///
+/// The servers are identified by their indices: [`KetamaRing::hash`] hashes
+/// keys to values of [`KetamaRing::range`], as the Flip Hash functions do
+/// given a range, so that a ring can replace them, or be replaced by them.
+/// Unlike Flip Hash, the ring is not regular, and hashing a key has a time
+/// complexity that is logarithmic in the number of servers.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::compat::KetamaRing;
+///
+/// let ring = KetamaRing::new(["10.0.0.1:11211", "10.0.0.2:11211", "10.0.0.3:11211"])?;
+/// let hash = ring.hash(b"key");
+///
+/// assert!(ring.range().contains(&hash));
+/// # Ok::<(), flip_hash::FlipHashError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct KetamaRing {
+    /// The points of the ring and the indices of their servers, sorted by
+    /// point.
+    points: Vec<(u32, u64)>,
+    range: RangeToInclusive<u64>,
+}
+
+impl KetamaRing {
+    const NUM_DIGESTS_PER_SERVER: usize = 40;
+
+    /// Builds the ring of `servers`, which are usually formatted as
+    /// `"{host}:{port}"`, as in the configuration files of libketama.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlipHashError::EmptyRange`] if there are no servers.
+    pub fn new<S: fmt::Display>(
+        servers: impl IntoIterator<Item = S>,
+    ) -> Result<Self, FlipHashError> {
+        let mut points = Vec::new();
+        let mut num_servers = 0_u64;
+        for server in servers {
+            for i in 0..Self::NUM_DIGESTS_PER_SERVER {
+                let digest = md5::compute(format!("{server}-{i}"));
+                for point in digest.chunks_exact(4) {
+                    points.push((u32::from_le_bytes(point.try_into().unwrap()), num_servers));
+                }
+            }
+            num_servers += 1;
+        }
+        if num_servers == 0 {
+            return Err(FlipHashError::EmptyRange);
+        }
+        // The sort is stable so that, if points collide, the ring does not
+        // depend on the sorting algorithm.
+        points.sort_by_key(|&(point, _)| point);
+        Ok(KetamaRing {
+            points,
+            range: ..=num_servers - 1,
+        })
+    }
+
+    /// Returns the range of the hashes, i.e., of the indices of the servers.
+    #[inline]
+    pub fn range(&self) -> RangeToInclusive<u64> {
+        self.range
+    }
+
+    /// Hashes `key` to the index of a server, a value of
+    /// [`KetamaRing::range`].
+    #[inline]
+    pub fn hash(&self, key: &[u8]) -> u64 {
+        let digest = md5::compute(key);
+        let hash = u32::from_le_bytes(digest[..4].try_into().unwrap());
+        let index = self.points.partition_point(|&(point, _)| point < hash);
+        self.points.get(index).unwrap_or(&self.points[0]).1
+    }
+}
@@ -0,0 +1,28 @@
+//! The errors of the fallible functions of this crate.
+
+use core::fmt;
+
+/// An error of the fallible functions of this crate, which rejects invalid
+/// inputs rather than panicking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FlipHashError {
+    /// There are no values to hash keys to, e.g., a ring has no servers.
+    EmptyRange,
+    /// The range has values that the output type cannot represent.
+    RangeTooLarge,
+    /// A weight is not a positive finite number.
+    InvalidWeight,
+}
+
+impl fmt::Display for FlipHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FlipHashError::EmptyRange => "the range is empty",
+            FlipHashError::RangeTooLarge => "the range does not fit in the output type",
+            FlipHashError::InvalidWeight => "a weight is not a positive finite number",
+        })
+    }
+}
+
+impl core::error::Error for FlipHashError {}
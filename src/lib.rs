@@ -84,176 +84,143 @@
 //! | `..=1000000` | 5.5 ns | 45 ns |
 //! | `..=1000000000` | 6.4 ns | 69 ns |
 #![no_std]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 use core::ops::RangeToInclusive;
 
-macro_rules! flip_hash {
-    ($hash_fn: path, $key: expr, $seed: expr, $range: expr, $max_num_iterations: expr) => {
-        match $range.end {
-            0 => 0,
-            _ => {
-                let pow2_mask = !0 >> $range.end.leading_zeros(); // == 2^r - 1
-                let hash = $hash_fn($key, $seed, 0, 0);
-                match flip_hash_pow2!($hash_fn, $key, $seed, hash, pow2_mask) {
-                    flip_hash_pow2 if flip_hash_pow2 <= $range.end => flip_hash_pow2,
-                    _ => {
-                        let mut iteration_index = 1; // i
-                        if let Some(draw) = loop {
-                            if iteration_index > $max_num_iterations {
-                                break None;
-                            }
-                            let draw = $hash_fn($key, $seed, $range.end.ilog2(), iteration_index)
-                                & pow2_mask;
-                            if draw <= pow2_mask >> 1 {
-                                break None;
-                            } else if draw <= $range.end {
-                                break Some(draw);
-                            }
-                            iteration_index += 1;
-                        } {
-                            draw
-                        } else {
-                            flip_hash_pow2!($hash_fn, $key, $seed, hash, pow2_mask >> 1)
-                        }
-                    }
-                }
-            }
-        }
-    };
-}
+pub use error::FlipHashError;
+#[cfg(feature = "keyed")]
+pub use v1::flip_hash_keyed_64;
+pub use v1::{
+    flip_hash_64, flip_hash_64_as, flip_hash_64_many, flip_hash_64_many_with_seed,
+    flip_hash_64_multi_seed, flip_hash_64_with_replica, flip_hash_64_with_seed,
+    flip_hash_64_with_seed_as, replica_seed, FixedFlipRange,
+};
+#[cfg(feature = "aes")]
+pub use v1::{flip_hash_aes_64, flip_hash_aes_64_with_seed};
+#[cfg(feature = "xxh3")]
+pub use v1::{
+    flip_hash_const_xxh3_128, flip_hash_const_xxh3_128_with_seed, flip_hash_const_xxh3_64,
+    flip_hash_const_xxh3_64_with_seed, flip_hash_str, flip_hash_str_with_seed, flip_hash_xxh3_128,
+    flip_hash_xxh3_128_with_seed, flip_hash_xxh3_64, flip_hash_xxh3_64_with_seed,
+};
+#[cfg(feature = "murmur2")]
+pub use v1::{flip_hash_murmur2_64, flip_hash_murmur2_64_with_seed};
+#[cfg(feature = "uuid")]
+pub use v1::{flip_hash_uuid, flip_hash_uuid_with_seed};
+#[cfg(feature = "rayon")]
+pub use v1::{par_flip_hash_histogram, par_flip_hash_many};
 
-macro_rules! flip_hash_pow2 {
-    ($hash_fn: path, $key: expr, $seed: expr, $hash: expr, $pow2_mask: expr) => {
-        match $hash & $pow2_mask {
-            0 => 0,
-            masked_hash => {
-                let flipper = $hash_fn($key, $seed, masked_hash.ilog2(), 0)
-                    & !0 >> masked_hash.leading_zeros() >> 1;
-                masked_hash ^ flipper
-            }
-        }
-    };
-}
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-/// Hashes `key` to a value of `range`, uniformly and with stability.
+#[cfg(feature = "aes")]
+mod aes;
+#[cfg(feature = "compat")]
+pub mod compat;
+mod error;
+#[cfg(feature = "murmur2")]
+mod murmur2;
+#[cfg(any(test, fuzzing))]
+#[doc(hidden)]
+pub mod reference;
+#[cfg(feature = "alloc")]
+pub mod reshard;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "keyed")]
+mod siphash;
+#[cfg(feature = "tower")]
+pub mod tower;
+pub mod v1;
+#[cfg(kani)]
+mod verification;
+#[cfg(feature = "alloc")]
+pub mod virtual_nodes;
+#[cfg(feature = "alloc")]
+pub mod working_set;
+#[cfg(feature = "xxh3")]
+mod xxh3;
+
+/// Returns the probability that a key is hashed to `bucket` given `range`,
+/// assuming that the underlying hash function behaves as a random function.
+///
+/// Flip Hash is not exactly regular: the number of values that it draws
+/// before falling back to a hash to the largest power of two that is not
+/// larger than the range end is bounded (by 64, for all variants), which
+/// slightly favors the lower half of the range. This function accounts for
+/// that, so that the worst-case imbalance can be bounded analytically. The
+/// bias never exceeds a relative `2^-64` of the uniform probability.
 ///
 /// # Example
 ///
 /// ```
-/// use flip_hash::flip_hash_64;
+/// use flip_hash::bucket_probability;
 ///
-/// let key = 15960427081186311679;
-/// let hash_17 = flip_hash_64(key, ..=17);
-/// let hash_18 = flip_hash_64(key, ..=18);
-///
-/// assert!(hash_17 <= 17);
-/// assert!(hash_18 == hash_17 || hash_18 == 18);
+/// let p = bucket_probability(3, ..=17);
+/// assert!((p - 1.0 / 18.0).abs() < 1e-15);
+/// assert_eq!(bucket_probability(18, ..=17), 0.0);
 /// ```
-#[inline]
-pub const fn flip_hash_64(key: u64, range: RangeToInclusive<u64>) -> u64 {
-    flip_hash_64_with_seed(key, 0, range)
-}
-
-#[inline]
-pub const fn flip_hash_64_with_seed(key: u64, seed: u64, range: RangeToInclusive<u64>) -> u64 {
-    const MAX_NUM_ITERATIONS: u32 = 64;
-    #[inline(always)]
-    const fn hash(key: u64, seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
-        // Inspired by https://mostlymangling.blogspot.com/2019/12/stronger-better-morer-moremur-better.html
-        let mut k = key ^ seed;
-        k = k.wrapping_mul(bit_len as u64 * 2 + 1);
-        k = (k ^ (k >> 27)).wrapping_mul(0x3C79AC492BA7B653);
-        k = k.wrapping_mul(iteration_index as u64 * 2 + 1);
-        k = (k ^ (k >> 33)).wrapping_mul(0x1C69B3F74AC4AE35);
-        k ^ (k >> 27)
-    }
-    flip_hash!(hash, key, seed, range, MAX_NUM_ITERATIONS)
-}
-
-#[cfg(feature = "xxh3")]
-const XXH3_MAX_NUM_ITERATIONS: u32 = 64;
-
-#[cfg(feature = "xxh3")]
-#[inline]
-pub fn flip_hash_xxh3_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
-    flip_hash_xxh3_64_with_seed(key, 0, range)
-}
-
-#[cfg(feature = "xxh3")]
-#[inline]
-pub fn flip_hash_xxh3_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
-    #[inline(always)]
-    fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
-        xxhash_rust::xxh3::xxh3_64_with_seed(
-            key,
-            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
-        )
+pub fn bucket_probability(bucket: u64, range: RangeToInclusive<u64>) -> f64 {
+    if bucket > range.end {
+        return 0.0;
+    } else if range.end == 0 {
+        return 1.0;
     }
-    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
-}
-
-#[cfg(feature = "xxh3")]
-#[inline]
-pub const fn flip_hash_const_xxh3_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
-    flip_hash_const_xxh3_64_with_seed(key, 0, range)
-}
+    // 2^(r-1) <= range.end < 2^r
+    let r = u64::BITS - range.end.leading_zeros();
+    let pow2 = (1_u128 << r) as f64;
+    let half_pow2 = (1_u128 << (r - 1)) as f64;
+    let range_len = range.end as f64 + 1.0;
 
-#[cfg(feature = "xxh3")]
-#[inline]
-pub const fn flip_hash_const_xxh3_64_with_seed(
-    key: &[u8],
-    seed: u64,
-    range: RangeToInclusive<u64>,
-) -> u64 {
-    #[inline(always)]
-    const fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
-        xxhash_rust::const_xxh3::xxh3_64_with_seed(
-            key,
-            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
-        )
+    // Probability that the hash to ..2^r is out of the range, which is also
+    // the probability that a draw is out of the range and in the upper half
+    // of ..2^r, so that another value is drawn.
+    let out_of_range = (pow2 - range_len) / pow2;
+    let mut all_draws_out_of_range = 1.0;
+    for _ in 0..v1::MAX_NUM_ITERATIONS {
+        all_draws_out_of_range *= out_of_range;
     }
-    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
-}
+    // Expected number of draws given that the hash to ..2^r is out of the range.
+    let num_draws = (1.0 - all_draws_out_of_range) / (1.0 - out_of_range);
 
-#[cfg(feature = "xxh3")]
-#[inline]
-pub fn flip_hash_xxh3_128(key: &[u8], range: RangeToInclusive<u128>) -> u128 {
-    flip_hash_xxh3_128_with_seed(key, 0, range)
-}
-
-#[cfg(feature = "xxh3")]
-#[inline]
-pub fn flip_hash_xxh3_128_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
-    #[inline(always)]
-    fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u128 {
-        xxhash_rust::xxh3::xxh3_128_with_seed(
-            key,
-            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
-        )
+    if (bucket as f64) < half_pow2 {
+        // Either the hash to ..2^r, or the fallback hash to ..2^(r-1), which
+        // happens when a draw is in the lower half or when all draws are out of
+        // the range.
+        let fallback = 0.5 * num_draws + all_draws_out_of_range;
+        1.0 / pow2 + out_of_range * fallback / half_pow2
+    } else {
+        // Either the hash to ..2^r or a draw.
+        1.0 / pow2 + out_of_range * num_draws / pow2
     }
-    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
 }
 
-#[cfg(feature = "xxh3")]
-#[inline]
-pub const fn flip_hash_const_xxh3_128(key: &[u8], range: RangeToInclusive<u128>) -> u128 {
-    flip_hash_const_xxh3_128_with_seed(key, 0, range)
-}
-
-#[cfg(feature = "xxh3")]
+/// Returns the partition of `key` out of `num_partitions`, as assigned by the
+/// default partitioner of Apache Kafka's clients, i.e.,
+/// `toPositive(murmur2(key)) % numPartitions`.
+///
+/// This is not a consistent hash: changing the number of partitions remaps
+/// most keys. It is meant to interoperate with topics that are partitioned by
+/// standard Kafka clients, e.g., while migrating them to
+/// [`flip_hash_murmur2_64`].
+///
+/// # Panics
+///
+/// Panics if `num_partitions` is zero.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::kafka_partition;
+///
+/// assert_eq!(kafka_partition(b"foobar", 12), 6);
+/// ```
+#[cfg(feature = "murmur2")]
 #[inline]
-pub const fn flip_hash_const_xxh3_128_with_seed(
-    key: &[u8],
-    seed: u64,
-    range: RangeToInclusive<u128>,
-) -> u128 {
-    #[inline(always)]
-    const fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u128 {
-        xxhash_rust::const_xxh3::xxh3_128_with_seed(
-            key,
-            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
-        )
-    }
-    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
+pub fn kafka_partition(key: &[u8], num_partitions: u32) -> u32 {
+    (murmur2::murmur2(key) & 0x7FFFFFFF) % num_partitions
 }
 
 #[cfg(test)]
@@ -332,6 +299,75 @@ mod tests {
         u64,
         u128
     );
+    #[cfg(feature = "aes")]
+    wrap!(
+        flip_hash_aes_64,
+        flip_hash_aes_64_with_seed,
+        Bytes,
+        u64,
+        u64
+    );
+    #[cfg(feature = "murmur2")]
+    wrap!(
+        flip_hash_murmur2_64,
+        flip_hash_murmur2_64_with_seed,
+        Bytes,
+        u64,
+        u64
+    );
+    // The keyed variant takes its secret by reference, and has no default.
+    #[cfg(feature = "keyed")]
+    fn flip_hash_keyed_64_with_secret(
+        key: &Bytes,
+        secret: [u8; 16],
+        range: RangeToInclusive<u64>,
+    ) -> u64 {
+        super::flip_hash_keyed_64(key.into(), &secret, range)
+    }
+
+    // Small replica indices, so that the replicas of a key are likely to
+    // have close indices.
+    fn flip_hash_64_with_replica(
+        key: &U64Key,
+        replica_index: u8,
+        range: RangeToInclusive<u64>,
+    ) -> u64 {
+        super::flip_hash_64_with_replica(key.into(), replica_index.into(), range)
+    }
+
+    // Homogeneize the types of the reference implementations the same way.
+    mod reference {
+        use super::*;
+
+        macro_rules! wrap_reference {
+            ($flip_hash_with_seed_fn: ident, $key_ty: ty, $seed_ty: ty, $hash_ty: ty) => {
+                pub(super) fn $flip_hash_with_seed_fn(
+                    key: &$key_ty,
+                    seed: $seed_ty,
+                    range: RangeToInclusive<$hash_ty>,
+                ) -> $hash_ty {
+                    crate::reference::$flip_hash_with_seed_fn(key.into(), seed, range)
+                }
+            };
+        }
+        wrap_reference!(flip_hash_64_with_seed, U64Key, u64, u64);
+        #[cfg(feature = "xxh3")]
+        wrap_reference!(flip_hash_xxh3_64_with_seed, Bytes, u64, u64);
+        #[cfg(feature = "xxh3")]
+        wrap_reference!(flip_hash_xxh3_128_with_seed, Bytes, u64, u128);
+        #[cfg(feature = "aes")]
+        wrap_reference!(flip_hash_aes_64_with_seed, Bytes, u64, u64);
+        #[cfg(feature = "murmur2")]
+        wrap_reference!(flip_hash_murmur2_64_with_seed, Bytes, u64, u64);
+        #[cfg(feature = "keyed")]
+        pub(super) fn flip_hash_keyed_64_with_secret(
+            key: &Bytes,
+            secret: [u8; 16],
+            range: RangeToInclusive<u64>,
+        ) -> u64 {
+            crate::reference::flip_hash_keyed_64(key.into(), &secret, range)
+        }
+    }
 
     #[derive(Arbitrary, Debug)]
     struct U64Key(u64);
@@ -384,6 +420,14 @@ mod tests {
         test_case(flip_hash_xxh3_128, flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128, flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(
+        feature = "aes",
+        test_case(flip_hash_aes_64, flip_hash_aes_64_with_seed)
+    )]
+    #[cfg_attr(
+        feature = "murmur2",
+        test_case(flip_hash_murmur2_64, flip_hash_murmur2_64_with_seed)
+    )]
     fn default_seed<K, S, H>(
         flip_hash: impl Fn(&K, RangeToInclusive<H>) -> H,
         flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
@@ -405,6 +449,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn zero_range_input<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
     where
         K: Arbitrary,
@@ -424,7 +471,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
-
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn full_range_input<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
     where
         K: Arbitrary,
@@ -444,6 +493,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn in_range<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
     where
         K: Arbitrary,
@@ -463,6 +515,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn monotonicity<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
     where
         K: Arbitrary,
@@ -489,6 +544,140 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
+    fn stability<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
+    where
+        K: Arbitrary,
+        S: Copy + Arbitrary + 'static,
+        H: PrimInt + Arbitrary + 'static,
+    {
+        proptest!(ProptestConfig::with_cases(100000), |(key: K, seed: S, range in mostly_small_ranges())| {
+            prop_assume!(range.end < H::max_value());
+            let next_range_end = range.end + one();
+            let hash = flip_hash_with_seed(&key, seed, range);
+            let next_hash = flip_hash_with_seed(&key, seed, ..=next_range_end);
+            prop_assert!(next_hash == hash || next_hash == next_range_end);
+        });
+    }
+
+    // Golden vectors of tests/v1.rs, so that the hashes of a key under a
+    // constant seed cannot depend on anything else, e.g., on the process.
+    #[test_case(flip_hash_64_with_seed, U64Key(10427592028180905159), 1, 1000, 770; "u64 key")]
+    #[test_case(
+        flip_hash_64_with_seed,
+        U64Key(u64::MAX),
+        0,
+        u64::MAX,
+        4668610942802735782;
+        "u64 key full range"
+    )]
+    #[cfg_attr(
+        feature = "xxh3",
+        test_case(flip_hash_xxh3_64_with_seed, Bytes(b"a".to_vec()), 1, 1000, 569; "xxh3 64"),
+        test_case(
+            flip_hash_xxh3_64_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u64::MAX,
+            10592897119071777987;
+            "xxh3 64 full range"
+        ),
+        test_case(flip_hash_const_xxh3_64_with_seed, Bytes(b"a".to_vec()), 1, 1000, 569; "const xxh3 64"),
+        test_case(
+            flip_hash_const_xxh3_64_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u64::MAX,
+            10592897119071777987;
+            "const xxh3 64 full range"
+        ),
+        test_case(flip_hash_xxh3_128_with_seed, Bytes(b"a".to_vec()), 1, 1000, 569; "xxh3 128"),
+        test_case(
+            flip_hash_xxh3_128_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u128::MAX,
+            140566099310334973002148666559493830111;
+            "xxh3 128 full range"
+        ),
+        test_case(flip_hash_const_xxh3_128_with_seed, Bytes(b"a".to_vec()), 1, 1000, 569; "const xxh3 128"),
+        test_case(
+            flip_hash_const_xxh3_128_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u128::MAX,
+            140566099310334973002148666559493830111;
+            "const xxh3 128 full range"
+        )
+    )]
+    #[cfg_attr(
+        feature = "aes",
+        test_case(flip_hash_aes_64_with_seed, Bytes(b"a".to_vec()), 1, 1000, 315; "aes 64"),
+        test_case(
+            flip_hash_aes_64_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u64::MAX,
+            14155385725055599346;
+            "aes 64 full range"
+        )
+    )]
+    #[cfg_attr(
+        feature = "murmur2",
+        test_case(flip_hash_murmur2_64_with_seed, Bytes(b"a".to_vec()), 1, 1000, 309; "murmur2 64"),
+        test_case(
+            flip_hash_murmur2_64_with_seed,
+            Bytes(b"flip hash".to_vec()),
+            42,
+            u64::MAX,
+            6805075313396413093;
+            "murmur2 64 full range"
+        )
+    )]
+    #[cfg_attr(
+        feature = "keyed",
+        test_case(
+            flip_hash_keyed_64_with_secret,
+            Bytes(b"a".to_vec()),
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            1000,
+            848;
+            "keyed 64"
+        ),
+        test_case(
+            flip_hash_keyed_64_with_secret,
+            Bytes(b"flip hash".to_vec()),
+            [0xFF; 16],
+            u64::MAX,
+            1592525807951942417;
+            "keyed 64 full range"
+        )
+    )]
+    fn determinism<K, S, H>(
+        flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
+        key: K,
+        seed: S,
+        range_end: H,
+        hash: H,
+    ) where
+        H: PrimInt + Debug,
+    {
+        assert_eq!(flip_hash_with_seed(&key, seed, ..=range_end), hash);
+    }
+
+    #[test_case(flip_hash_64_with_seed)]
+    #[cfg_attr(
+        feature = "xxh3",
+        test_case(flip_hash_xxh3_64_with_seed),
+        test_case(flip_hash_const_xxh3_64_with_seed),
+        test_case(flip_hash_xxh3_128_with_seed),
+        test_case(flip_hash_const_xxh3_128_with_seed)
+    )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn regularity<K, S, H>(flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H)
     where
         S: Copy + Debug,
@@ -531,6 +720,10 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
+    #[test_case(flip_hash_64_with_replica)]
     fn pairwise_independence_across_seeds<K, S, H>(
         flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
     ) where
@@ -549,6 +742,10 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
+    #[test_case(flip_hash_64_with_replica)]
     fn three_wise_independence_across_seeds<K, S, H>(
         flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
     ) where
@@ -586,8 +783,7 @@ mod tests {
             |test_case| {
                 // ranges = [..=1, ..=1] => [[0, 0], [0, 1], [1, 0], [1, 1]
                 let mut num_cooccurrences =
-                    iter::repeat(range_inclusive(zero(), test_case.range.end))
-                        .take(N)
+                    iter::repeat_n(range_inclusive(zero(), test_case.range.end), N)
                         .multi_cartesian_product()
                         .map(<[H; N]>::try_from)
                         .map(Result::unwrap)
@@ -615,6 +811,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn pairwise_independence_given_distinct_hashes<K, S, H>(
         flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
     ) where
@@ -633,6 +832,9 @@ mod tests {
         test_case(flip_hash_xxh3_128_with_seed),
         test_case(flip_hash_const_xxh3_128_with_seed)
     )]
+    #[cfg_attr(feature = "aes", test_case(flip_hash_aes_64_with_seed))]
+    #[cfg_attr(feature = "murmur2", test_case(flip_hash_murmur2_64_with_seed))]
+    #[cfg_attr(feature = "keyed", test_case(flip_hash_keyed_64_with_secret))]
     fn three_wise_independence_given_distinct_hashes<K, S, H>(
         flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
     ) where
@@ -755,7 +957,7 @@ mod tests {
         }
     }
 
-    fn chi_squared_uniformity_test_p_value(num_occurrences: &Vec<u64>) -> f64 {
+    fn chi_squared_uniformity_test_p_value(num_occurrences: &[u64]) -> f64 {
         let expected_count =
             num_occurrences.iter().sum::<u64>() as f64 / num_occurrences.len() as f64;
 
@@ -786,17 +988,21 @@ mod tests {
                 .for_each(|p| *p /= n);
             p.iter()
                 .for_each(|p_i| assert!((p_i.values().sum::<f64>() - 1.0).abs() < 1e-2));
+            // The values that were never observed would make cells with no
+            // expected occurrences, which are left out along with the degrees
+            // of freedom of those values.
+            p.iter_mut().for_each(|p_i| p_i.retain(|_, p| *p > 0.0));
             (p, n)
         };
 
         let statistic = num_cooccurrences
             .iter()
-            .map(|(i, &o)| {
+            .filter_map(|(i, &o)| {
                 let joint_probability = iter::zip(&marginal_probabilities, i)
-                    .map(|(p_i, i_i)| *p_i.get(&i_i).unwrap())
-                    .product::<f64>();
+                    .map(|(p_i, i_i)| p_i.get(&i_i))
+                    .product::<Option<f64>>()?;
                 let e = joint_probability * num_samples;
-                (o as f64 - e).powi(2) / e
+                Some((o as f64 - e).powi(2) / e)
             })
             .sum::<f64>();
 
@@ -817,6 +1023,30 @@ mod tests {
             .cdf(statistic)
     }
 
+    #[test]
+    fn chi_squared_mutual_independence_test_p_value_of_unobserved_values() {
+        let mut num_cooccurrences = HashMap::from([
+            ([0, 0], 30),
+            ([0, 1], 10),
+            ([1, 0], 20),
+            ([1, 1], 40),
+            ([2, 0], 25),
+            ([2, 1], 25),
+        ]);
+        // The statistic is 50/3, with 2 degrees of freedom.
+        let p_value = (-25.0_f64 / 3.0).exp();
+        let assert_p_value = |num_cooccurrences: &HashMap<[u8; 2], u64>| {
+            let actual = chi_squared_mutual_independence_test_p_value(num_cooccurrences);
+            assert!((actual - p_value).abs() < 1e-9 * p_value, "{actual}");
+        };
+        assert_p_value(&num_cooccurrences);
+        // The values that are never observed, of either variable, do not
+        // change the p-value.
+        num_cooccurrences.extend([([0, 2], 0), ([1, 2], 0), ([2, 2], 0)]);
+        num_cooccurrences.extend([([3, 0], 0), ([3, 1], 0), ([3, 2], 0)]);
+        assert_p_value(&num_cooccurrences);
+    }
+
     #[cfg(feature = "xxh3")]
     #[cfg_attr(
         feature = "xxh3",
@@ -836,6 +1066,644 @@ mod tests {
         });
     }
 
+    #[test_case(flip_hash_64_with_seed, reference::flip_hash_64_with_seed)]
+    #[cfg_attr(
+        feature = "xxh3",
+        test_case(flip_hash_xxh3_64_with_seed, reference::flip_hash_xxh3_64_with_seed),
+        test_case(
+            flip_hash_const_xxh3_64_with_seed,
+            reference::flip_hash_xxh3_64_with_seed
+        ),
+        test_case(flip_hash_xxh3_128_with_seed, reference::flip_hash_xxh3_128_with_seed),
+        test_case(
+            flip_hash_const_xxh3_128_with_seed,
+            reference::flip_hash_xxh3_128_with_seed
+        )
+    )]
+    #[cfg_attr(
+        feature = "aes",
+        test_case(flip_hash_aes_64_with_seed, reference::flip_hash_aes_64_with_seed)
+    )]
+    #[cfg_attr(
+        feature = "murmur2",
+        test_case(
+            flip_hash_murmur2_64_with_seed,
+            reference::flip_hash_murmur2_64_with_seed
+        )
+    )]
+    #[cfg_attr(
+        feature = "keyed",
+        test_case(
+            flip_hash_keyed_64_with_secret,
+            reference::flip_hash_keyed_64_with_secret
+        )
+    )]
+    fn reference_compatibility<K, S, H>(
+        flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
+        reference_flip_hash_with_seed: impl Fn(&K, S, RangeToInclusive<H>) -> H,
+    ) where
+        K: Arbitrary,
+        S: Copy + Arbitrary + 'static,
+        H: PrimInt + Arbitrary + 'static,
+    {
+        proptest!(ProptestConfig::with_cases(100000), |(key: K, seed: S, range in mostly_small_ranges())| {
+            prop_assert_eq!(flip_hash_with_seed(&key, seed, range), reference_flip_hash_with_seed(&key, seed, range));
+        });
+    }
+
+    #[test]
+    fn as_compatibility() {
+        proptest!(|(key: u64, seed: u64, range in mostly_small_ranges::<u32>())| {
+            let hash = super::flip_hash_64_with_seed(key, seed, ..=range.end.into());
+            prop_assert_eq!(super::flip_hash_64_with_seed_as(key, seed, ..=range.end.into()), Ok(hash as u32));
+        });
+    }
+
+    #[test]
+    fn as_range_too_large() {
+        proptest!(|(key: u64, seed: u64)| {
+            prop_assert!(super::flip_hash_64_with_seed_as::<u8>(key, seed, ..=255).is_ok());
+            prop_assert_eq!(
+                super::flip_hash_64_with_seed_as::<u8>(key, seed, ..=256),
+                Err(super::FlipHashError::RangeTooLarge)
+            );
+            prop_assert_eq!(
+                super::flip_hash_64_with_seed_as::<i8>(key, seed, ..=128),
+                Err(super::FlipHashError::RangeTooLarge)
+            );
+        });
+    }
+
+    #[test]
+    fn multi_seed_compatibility() {
+        proptest!(|(key: u64, seeds: Vec<u64>, range in mostly_small_ranges())| {
+            let mut hashes = vec![0; seeds.len()];
+            super::flip_hash_64_multi_seed(key, &seeds, range, &mut hashes);
+            for (&seed, &hash) in iter::zip(&seeds, &hashes) {
+                prop_assert_eq!(hash, super::flip_hash_64_with_seed(key, seed, range));
+            }
+        });
+    }
+
+    #[test]
+    fn replica_compatibility() {
+        proptest!(|(key: u64, replica_index: u32, range in mostly_small_ranges())| {
+            prop_assert_eq!(
+                super::flip_hash_64_with_replica(key, replica_index, range),
+                super::flip_hash_64_with_seed(key, super::replica_seed(replica_index), range)
+            );
+            prop_assert_eq!(
+                super::flip_hash_64_with_replica(key, 0, range),
+                super::flip_hash_64(key, range)
+            );
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reshard_plan() {
+        use core::num::NonZeroU64;
+
+        use super::reshard::plan_reshard_with_seed;
+
+        proptest!(|(
+            key_counts: Vec<(u64, u8)>,
+            seed: u64,
+            old_range in mostly_small_ranges(),
+            new_range in mostly_small_ranges(),
+            max_moves_per_batch in 1..100_u64,
+        )| {
+            let key_counts = key_counts
+                .into_iter()
+                .map(|(key, count)| (key, count.into()))
+                .collect::<HashMap<u64, u64>>();
+            let batches = plan_reshard_with_seed(
+                old_range,
+                new_range,
+                key_counts.iter().map(|(&key, &count)| (key, count)),
+                seed,
+                NonZeroU64::new(max_moves_per_batch).unwrap(),
+            );
+
+            let moves = batches
+                .iter()
+                .flat_map(|batch| &batch.moves)
+                .flat_map(|m| m.keys.iter().map(move |&key| (m.from, m.to, key)))
+                .collect::<Vec<_>>();
+            prop_assert!(moves.windows(2).all(|w| w[0] < w[1]));
+            let expected_moves = key_counts
+                .iter()
+                .filter(|(_, &count)| count > 0)
+                .map(|(&key, _)| {
+                    (
+                        super::flip_hash_64_with_seed(key, seed, old_range),
+                        super::flip_hash_64_with_seed(key, seed, new_range),
+                        key,
+                    )
+                })
+                .filter(|&(from, to, _)| from != to)
+                .sorted()
+                .collect::<Vec<_>>();
+            prop_assert_eq!(&moves, &expected_moves);
+            for &(from, to, _) in &moves {
+                prop_assert!(from > new_range.end || to > old_range.end);
+            }
+
+            for batch in &batches {
+                let keys = batch.moves.iter().flat_map(|m| &m.keys).collect::<Vec<_>>();
+                prop_assert!(!keys.is_empty());
+                prop_assert!(batch.count() <= max_moves_per_batch || keys.len() == 1);
+                for m in &batch.moves {
+                    prop_assert_eq!(m.count, m.keys.iter().map(|key| key_counts[key]).sum::<u64>());
+                }
+            }
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn virtual_nodes() {
+        use super::virtual_nodes::VirtualNodes;
+
+        proptest!(|(weights in prop::collection::vec(0.01..100.0, 1..20), num_buckets in 1..5000_usize, key: u64)| {
+            let nodes = VirtualNodes::new(weights.iter().copied().enumerate(), num_buckets).unwrap();
+            prop_assert_eq!(nodes.range(), ..=num_buckets as u64 - 1);
+            prop_assert_eq!(nodes.node(num_buckets as u64), None);
+            prop_assert_eq!(
+                Some(nodes.hash(key)),
+                nodes.node(super::flip_hash_64(key, nodes.range()))
+            );
+            let total_weight = weights.iter().sum::<f64>();
+            let mut total_num_buckets = 0;
+            for (node, weight) in weights.iter().enumerate() {
+                let quota = weight / total_weight * num_buckets as f64;
+                let node_num_buckets = nodes.num_buckets(&node);
+                prop_assert!((node_num_buckets as f64 - quota).abs() < 1.0 + 1e-9);
+                total_num_buckets += node_num_buckets;
+            }
+            prop_assert_eq!(total_num_buckets, num_buckets);
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn virtual_nodes_reweight() {
+        use super::virtual_nodes::VirtualNodes;
+
+        proptest!(|(
+            weights in prop::collection::vec(0.01..100.0, 1..20),
+            new_weights in prop::collection::vec(prop::option::of(0.01..100.0), 1..20),
+            num_buckets in 1..5000_usize,
+        )| {
+            let new_weights = new_weights
+                .into_iter()
+                .enumerate()
+                .filter_map(|(node, weight)| Some((node, weight?)))
+                .collect::<Vec<_>>();
+            prop_assume!(!new_weights.is_empty());
+            let nodes = VirtualNodes::new(weights.iter().copied().enumerate(), num_buckets).unwrap();
+            let reweighted_nodes = nodes.reweight(new_weights.iter().copied()).unwrap();
+            let expected_reweighted_nodes = VirtualNodes::new(new_weights.iter().copied(), num_buckets).unwrap();
+
+            // Only as many buckets as nodes lose are reassigned.
+            let num_lost_buckets = (0..weights.len().max(new_weights.len()))
+                .map(|node| {
+                    nodes
+                        .num_buckets(&node)
+                        .saturating_sub(reweighted_nodes.num_buckets(&node))
+                })
+                .sum::<usize>();
+            let num_reassigned_buckets = (0..num_buckets as u64)
+                .filter(|&bucket| nodes.node(bucket) != reweighted_nodes.node(bucket))
+                .count();
+            prop_assert_eq!(num_reassigned_buckets, num_lost_buckets);
+            for &(node, _) in &new_weights {
+                prop_assert_eq!(
+                    reweighted_nodes.num_buckets(&node),
+                    expected_reweighted_nodes.num_buckets(&node)
+                );
+            }
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test_case(&[], 10, crate::FlipHashError::EmptyRange)]
+    #[test_case(&[1.0], 0, crate::FlipHashError::EmptyRange)]
+    #[test_case(&[1.0, 0.0], 10, crate::FlipHashError::InvalidWeight)]
+    #[test_case(&[1.0, -1.0], 10, crate::FlipHashError::InvalidWeight)]
+    #[test_case(&[1.0, f64::NAN], 10, crate::FlipHashError::InvalidWeight)]
+    #[test_case(&[f64::MAX, f64::MAX], 10, crate::FlipHashError::InvalidWeight)]
+    fn virtual_nodes_error(weights: &[f64], num_buckets: usize, error: super::FlipHashError) {
+        let nodes = super::virtual_nodes::VirtualNodes::new(
+            weights.iter().copied().enumerate(),
+            num_buckets,
+        );
+        assert_eq!(nodes.unwrap_err(), error);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn working_set_removal() {
+        use super::working_set::WorkingSet;
+
+        proptest!(|(
+            keys in prop::collection::vec(any::<u64>(), 100),
+            seed: u64,
+            range in mostly_small_ranges(),
+            removed_buckets: Vec<u64>,
+            bucket: u64,
+        )| {
+            let mut working_set = WorkingSet::new(range);
+            for removed_bucket in removed_buckets {
+                let _ = working_set.remove(removed_bucket % (range.end / 2 + 1));
+            }
+            let bucket = range.end.checked_add(1).map_or(bucket, |len| bucket % len);
+            let mut new_working_set = working_set.clone();
+            prop_assume!(new_working_set.remove(bucket) == Ok(true));
+            for key in keys {
+                let hash = working_set.hash_with_seed(key, seed);
+                let new_hash = new_working_set.hash_with_seed(key, seed);
+                prop_assert!(working_set.is_working(hash));
+                prop_assert!(new_working_set.is_working(new_hash));
+                prop_assert!(new_hash == hash || hash == bucket);
+            }
+            prop_assert!(new_working_set.restore(bucket));
+            prop_assert_eq!(&new_working_set, &working_set);
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn working_set_growth() {
+        use super::working_set::WorkingSet;
+
+        proptest!(|(
+            keys in prop::collection::vec(any::<u64>(), 100),
+            seed: u64,
+            range_end in 1..1000_u64,
+            removed_buckets: Vec<u64>,
+        )| {
+            let mut working_set = WorkingSet::new(..=range_end);
+            for removed_bucket in removed_buckets {
+                let _ = working_set.remove(removed_bucket % (range_end + 1));
+            }
+            let mut new_working_set = working_set.clone();
+            let new_bucket = new_working_set.grow().unwrap();
+            prop_assert_eq!(new_bucket, range_end + 1);
+            for key in keys {
+                let hash = working_set.hash_with_seed(key, seed);
+                let new_hash = new_working_set.hash_with_seed(key, seed);
+                prop_assert!(new_hash == hash || new_hash == new_bucket);
+            }
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn working_set_regularity() {
+        use super::working_set::WorkingSet;
+
+        #[derive(Debug)]
+        struct TestCase {
+            working_set: WorkingSet,
+            num_hashes: usize,
+            key_rng_seed: u64,
+        }
+
+        let mut rng = thread_rng();
+        assert_statistical_hypothesis(
+            iter::repeat_with(|| {
+                let range_end = rng.gen_range(2..200);
+                let mut working_set = WorkingSet::new(..=range_end);
+                for _ in 0..rng.gen_range(0..range_end) {
+                    working_set.remove(rng.gen_range(0..=range_end)).unwrap();
+                }
+                TestCase {
+                    working_set,
+                    num_hashes: rng.gen_range(100..10000),
+                    key_rng_seed: rng.next_u64(),
+                }
+            }),
+            |test_case| {
+                let range_end = test_case.working_set.range().end;
+                let mut num_occurrences = vec![0_u64; range_end as usize + 1];
+                StdRng::seed_from_u64(test_case.key_rng_seed)
+                    .sample_iter(Standard)
+                    .map(|key| test_case.working_set.hash(key))
+                    .take(test_case.num_hashes)
+                    .for_each(|hash| num_occurrences[hash as usize] += 1);
+                let num_occurrences = (0..=range_end)
+                    .filter(|&bucket| test_case.working_set.is_working(bucket))
+                    .map(|bucket| num_occurrences[bucket as usize])
+                    .collect::<Vec<_>>();
+                chi_squared_uniformity_test_p_value(&num_occurrences)
+            },
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn working_set_shrink() {
+        let mut working_set = super::working_set::WorkingSet::new(..=9);
+        for bucket in [9, 8, 6] {
+            assert_eq!(working_set.remove(bucket), Ok(true));
+        }
+        assert_eq!(working_set.shrink(), 2);
+        assert_eq!(working_set.range(), ..=7);
+        assert_eq!(working_set.removed_buckets().collect::<Vec<_>>(), [6]);
+        assert_eq!(working_set.num_working_buckets(), 7);
+        for bucket in 0..6 {
+            assert_eq!(working_set.remove(bucket), Ok(true));
+        }
+        assert_eq!(working_set.remove(7), Err(crate::FlipHashError::EmptyRange));
+        assert_eq!(working_set.remove(6), Ok(false));
+    }
+
+    #[test]
+    fn many_compatibility() {
+        proptest!(|(keys: Vec<u64>, seed: u64, range in mostly_small_ranges())| {
+            let mut hashes = vec![0; keys.len()];
+            super::flip_hash_64_many_with_seed(&keys, seed, range, &mut hashes);
+            for (&key, &hash) in iter::zip(&keys, &hashes) {
+                prop_assert_eq!(hash, super::flip_hash_64_with_seed(key, seed, range));
+            }
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_many_compatibility() {
+        proptest!(|(keys in prop::collection::vec(any::<u64>(), 0..5000), range in mostly_small_ranges())| {
+            let mut hashes = vec![0; keys.len()];
+            super::flip_hash_64_many(&keys, range, &mut hashes);
+            prop_assert_eq!(super::par_flip_hash_many(&keys, range), hashes);
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_histogram_compatibility() {
+        proptest!(|(keys in prop::collection::vec(any::<u64>(), 0..5000), range_end in ..1000_u64)| {
+            let mut histogram = vec![0; range_end as usize + 1];
+            for &key in &keys {
+                histogram[super::flip_hash_64(key, ..=range_end) as usize] += 1;
+            }
+            prop_assert_eq!(super::par_flip_hash_histogram(&keys, ..=range_end), histogram);
+        });
+    }
+
+    #[test]
+    fn fixed_range_compatibility() {
+        fn check<const END: u64>() {
+            proptest!(|(key: u64, seed: u64)| {
+                prop_assert_eq!(super::FixedFlipRange::<END>::hash(key), super::flip_hash_64(key, ..=END));
+                prop_assert_eq!(
+                    super::FixedFlipRange::<END>::hash_with_seed(key, seed),
+                    super::flip_hash_64_with_seed(key, seed, ..=END)
+                );
+            });
+        }
+        check::<0>();
+        check::<1>();
+        check::<17>();
+        check::<{ (1 << 20) - 1 }>();
+        check::<{ 1 << 20 }>();
+        check::<{ u64::MAX }>();
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(2)]
+    #[test_case(17)]
+    #[test_case(1 << 20)]
+    #[test_case((1 << 20) - 1)]
+    fn bucket_probability(range_end: u64) {
+        let probabilities = (0..=range_end)
+            .map(|bucket| super::bucket_probability(bucket, ..=range_end))
+            .collect::<Vec<_>>();
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let uniform_probability = 1.0 / (range_end as f64 + 1.0);
+        assert!(probabilities
+            .iter()
+            .all(|p| (p - uniform_probability).abs() <= uniform_probability * 1e-12));
+        assert_eq!(super::bucket_probability(range_end + 1, ..=range_end), 0.0);
+    }
+
+    #[test]
+    fn bucket_probability_large_range() {
+        let p = super::bucket_probability(u64::MAX, ..=u64::MAX);
+        assert!((p * 2_f64.powi(64) - 1.0).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "xxh3")]
+    #[test]
+    fn xxh3_compatibility() {
+        // Long enough for the SIMD code paths.
+        proptest!(|(key in prop::collection::vec(any::<u8>(), 0..2048), seed: u64)| {
+            prop_assert_eq!(super::xxh3::hash_64(&key, seed), xxhash_rust::xxh3::xxh3_64_with_seed(&key, seed));
+            prop_assert_eq!(super::xxh3::hash_128(&key, seed), xxhash_rust::xxh3::xxh3_128_with_seed(&key, seed));
+        });
+    }
+
+    #[cfg(feature = "xxh3")]
+    #[test]
+    fn str_compatibility() {
+        use std::string::String;
+
+        proptest!(|(key: String, seed: u64, range in mostly_small_ranges())| {
+            prop_assert_eq!(
+                super::flip_hash_str_with_seed(&key, seed, range),
+                super::flip_hash_xxh3_64_with_seed(key.as_bytes(), seed, range)
+            );
+            prop_assert_eq!(
+                super::flip_hash_str(&key, range),
+                super::flip_hash_str_with_seed(&key, 0, range)
+            );
+        });
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_compatibility() {
+        proptest!(|(key: u128, seed: u64, range in mostly_small_ranges())| {
+            prop_assert_eq!(
+                super::flip_hash_uuid_with_seed(uuid::Uuid::from_u128(key), seed, range),
+                super::flip_hash_xxh3_64_with_seed(&key.to_be_bytes(), seed, range)
+            );
+            prop_assert_eq!(
+                super::flip_hash_uuid(uuid::Uuid::from_u128(key), range),
+                super::flip_hash_uuid_with_seed(uuid::Uuid::from_u128(key), 0, range)
+            );
+        });
+    }
+
+    #[cfg(feature = "tower")]
+    #[test]
+    fn tower_picker() {
+        use ::tower::steer::Picker;
+
+        proptest!(|(key: u64, num_services in 1_usize..1000)| {
+            let mut picker = super::tower::FlipHashPicker::new(|&key: &u64| key);
+            let services = vec![(); num_services];
+            let index = picker.pick(&key, &services);
+            prop_assert_eq!(index as u64, super::flip_hash_64(key, ..=num_services as u64 - 1));
+        });
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn soft_aesenc() {
+        // FIPS-197, appendix B: start of round 1 and 2, and round key 1.
+        let state = 0x193de3bea0f4e22b9ac68d2ae9f84808_u128.to_be_bytes();
+        let round_key = 0xa0fafe1788542cb123a339392a6c7605_u128.to_be_bytes();
+        let next_state = 0xa49c7ff2689f352b6b5bea43026a5049_u128.to_be_bytes();
+        assert_eq!(super::aes::soft::aesenc(state, round_key), next_state);
+    }
+
+    #[cfg(all(feature = "aes", target_arch = "x86_64"))]
+    #[test]
+    fn aes_hardware_compatibility() {
+        if !std::is_x86_feature_detected!("aes") {
+            return;
+        }
+        proptest!(|(key: Vec<u8>, tweak: u64)| {
+            // SAFETY: AES-NI is available.
+            let hardware_hash = unsafe { super::aes::x86_64::hash(&key, tweak) };
+            prop_assert_eq!(hardware_hash, super::aes::soft::hash(&key, tweak));
+        });
+    }
+
+    // From Kafka's UtilsTest.
+    #[cfg(feature = "murmur2")]
+    #[test_case(b"21", -973932308)]
+    #[test_case(b"foobar", -790332482)]
+    #[test_case(b"a-little-bit-long-string", -985981536)]
+    #[test_case(b"a-little-bit-longer-string", -1486304829)]
+    #[test_case(b"lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8", -58897971)]
+    #[test_case(b"abc", 479470107)]
+    fn kafka_murmur2(key: &[u8], expected_hash: i32) {
+        assert_eq!(super::murmur2::murmur2(key) as i32, expected_hash);
+    }
+
+    // As computed by Kafka's default partitioner.
+    #[cfg(feature = "murmur2")]
+    #[test_case(b"21", 100, 40)]
+    #[test_case(b"foobar", 12, 6)]
+    #[test_case(b"a-little-bit-longer-string", 12, 11)]
+    #[test_case(b"lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8", 100, 77)]
+    #[test_case(b"abc", 1, 0)]
+    #[test_case(b"abc", i32::MAX as u32, 479470107)]
+    fn kafka_partition(key: &[u8], num_partitions: u32, expected_partition: u32) {
+        assert_eq!(
+            super::kafka_partition(key, num_partitions),
+            expected_partition
+        );
+    }
+
+    // From the reference implementation of SipHash, whose secret is 00..0F
+    // and whose inputs are 00..(len - 1).
+    #[cfg(feature = "keyed")]
+    #[test_case(0, 0x726FDB47DD0E0E31)]
+    #[test_case(1, 0x74F839C593DC67FD)]
+    #[test_case(2, 0x0D6C8009D9A94F5A)]
+    #[test_case(3, 0x85676696D7FB7E2D)]
+    #[test_case(15, 0xA129CA6149BE45E5)]
+    #[test_case(63, 0x958A324CEB064572)]
+    fn sip_hash_2_4(len: u8, expected_hash: u64) {
+        let secret = array::from_fn(|i| i as u8);
+        let data = (0..len).collect::<Vec<_>>();
+        assert_eq!(super::siphash::sip_hash_2_4(&data, &secret), expected_hash);
+    }
+
+    #[cfg(feature = "keyed")]
+    #[test]
+    #[allow(deprecated)]
+    fn sip_hash_2_4_compatibility() {
+        use core::hash::{Hasher, SipHasher};
+
+        proptest!(|(data: Vec<u8>, secret: [u8; 16], bit_len: u32, iteration_index: u32)| {
+            let mut hasher = SipHasher::new_with_keys(
+                u64::from_le_bytes(secret[..8].try_into().unwrap()),
+                u64::from_le_bytes(secret[8..].try_into().unwrap()),
+            );
+            hasher.write(&data);
+            prop_assert_eq!(super::siphash::sip_hash_2_4(&data, &secret), hasher.finish());
+
+            // The bit length and the iteration index are hashed as a prefix.
+            let prefixed_data = (bit_len as u64 + ((iteration_index as u64) << 32))
+                .to_le_bytes()
+                .into_iter()
+                .chain(data.iter().copied())
+                .collect::<Vec<_>>();
+            prop_assert_eq!(
+                super::siphash::hash(&data, &secret, bit_len, iteration_index),
+                super::siphash::sip_hash_2_4(&prefixed_data, &secret)
+            );
+        });
+    }
+
+    // Generated by testdata/GuavaConsistentHash.java.
+    #[cfg(feature = "compat")]
+    #[test]
+    fn guava_consistent_hash() {
+        let vectors = include_str!("../testdata/guava_consistent_hash.csv");
+        for line in vectors.lines().skip(1) {
+            let (input, buckets, hash) = line
+                .split(',')
+                .map(|field| field.parse::<u64>().unwrap())
+                .collect_tuple()
+                .unwrap();
+            assert_eq!(
+                super::compat::guava_consistent_hash(input, buckets as u32),
+                hash as u32,
+                "{line}"
+            );
+        }
+    }
+
+    #[cfg(feature = "compat")]
+    #[test_case(0; "no buckets")]
+    #[test_case(i32::MAX as u32 + 1; "more buckets than Java ints")]
+    #[should_panic(expected = "buckets must be in 1..=i32::MAX")]
+    fn guava_consistent_hash_of_invalid_buckets(buckets: u32) {
+        super::compat::guava_consistent_hash(0, buckets);
+    }
+
+    #[cfg(feature = "compat")]
+    #[test]
+    fn ketama_ring_without_servers() {
+        assert_eq!(
+            super::compat::KetamaRing::new(iter::empty::<&str>()).unwrap_err(),
+            super::FlipHashError::EmptyRange
+        );
+    }
+
+    // Generated by testdata/KetamaRing.java.
+    #[cfg(feature = "compat")]
+    #[test]
+    fn ketama_ring() {
+        let vectors = include_str!("../testdata/ketama_ring.csv");
+        for (num_servers, lines) in &vectors
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').collect_tuple().unwrap())
+            .group_by(|&(num_servers, _, _)| num_servers.parse::<u64>().unwrap())
+        {
+            let ring = super::compat::KetamaRing::new(
+                (1..=num_servers).map(|server| format!("10.0.0.{server}:11211")),
+            )
+            .unwrap();
+            assert_eq!(ring.range(), ..=num_servers - 1);
+            for (_, key, server_index) in lines {
+                assert_eq!(
+                    ring.hash(key.as_bytes()),
+                    server_index.parse::<u64>().unwrap(),
+                    "{num_servers} servers, {key}"
+                );
+            }
+        }
+    }
+
     // To avoid the use of unsafe Step.
     fn range_inclusive<H: PrimInt>(start: H, end: H) -> impl Iterator<Item = H> + Clone {
         iter::successors(Some(start), |&h| Some(h + one())).take_while(move |&h| h <= end)
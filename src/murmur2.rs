@@ -0,0 +1,71 @@
+//! MurmurHash2 hash functions of byte slices.
+//!
+//! [`murmur2`] is the 32-bit variant with the seed and the byte order that
+//! Apache Kafka's clients use to partition records, and [`hash`] is the
+//! 64-bit variant (MurmurHash64A) that Flip Hash uses as a backend.
+
+/// Returns the 32-bit MurmurHash2 of `data`, as computed by Kafka's
+/// `Utils.murmur2`.
+#[inline]
+pub(crate) fn murmur2(data: &[u8]) -> u32 {
+    const SEED: u32 = 0x9747B28C;
+    const M: u32 = 0x5BD1E995;
+    const R: u32 = 24;
+
+    let mut h = SEED ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &b) in tail.iter().enumerate() {
+            h ^= (b as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^ (h >> 15)
+}
+
+#[inline(always)]
+pub(crate) fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
+    murmur64a(
+        key,
+        seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+    )
+}
+
+/// Returns the 64-bit MurmurHash2 (MurmurHash64A) of `data`.
+#[inline(always)]
+fn murmur64a(data: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xC6A4A7935BD1E995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (data.len() as u64).wrapping_mul(M);
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &b) in tail.iter().enumerate() {
+            h ^= (b as u64) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^ (h >> R)
+}
@@ -0,0 +1,138 @@
+//! A straightforward implementation of Flip Hash, written after the
+//! description of the algorithm rather than for speed.
+//!
+//! It shares the underlying hash functions with the optimized implementations
+//! and is only compiled for tests and fuzzing, to check that they compute
+//! the same hashes.
+
+use core::ops::RangeToInclusive;
+
+/// Hashes the key to a value of `range`, given `hash(bit_len, iteration_index)`,
+/// a hash of the key that is parameterized by a bit length and an iteration
+/// index.
+///
+/// The computation is carried on 128-bit integers, so that the same function
+/// applies to 64-bit hash functions (whose outputs are zero-extended) and to
+/// 128-bit ones.
+pub fn flip_hash(
+    hash: impl Fn(u32, u32) -> u128,
+    range: RangeToInclusive<u128>,
+    max_num_iterations: u32,
+) -> u128 {
+    if range.end == 0 {
+        return 0;
+    }
+
+    // The smallest power of two that is larger than the range end is 2^r.
+    let r = bit_length(range.end);
+
+    // Hash to a value of ..2^r; that is the hash if it is in the range.
+    let hash_pow2 = flip_hash_pow2(&hash, r);
+    if hash_pow2 <= range.end {
+        return hash_pow2;
+    }
+
+    // Otherwise, draw values of ..2^r until one is in the range, or until one
+    // is in ..2^(r-1), in which case hash to a value of ..2^(r-1).
+    for iteration_index in 1..=max_num_iterations {
+        let draw = low_bits(hash(r - 1, iteration_index), r);
+        if draw < 1 << (r - 1) {
+            break;
+        } else if draw <= range.end {
+            return draw;
+        }
+    }
+    flip_hash_pow2(&hash, r - 1)
+}
+
+/// Hashes the key to a value of `..2^r`.
+fn flip_hash_pow2(hash: &impl Fn(u32, u32) -> u128, r: u32) -> u128 {
+    let masked_hash = low_bits(hash(0, 0), r);
+    if masked_hash == 0 {
+        return 0;
+    }
+
+    // The masked hash is in 2^b..2^(b+1). Flip its b lowest bits, with a hash
+    // that is specific to b, so that the result is uniformly distributed
+    // over 2^b..2^(b+1), independently of the hashes to smaller powers of two.
+    let b = bit_length(masked_hash) - 1;
+    let flipper = low_bits(hash(b, 0), b);
+    masked_hash ^ flipper
+}
+
+/// Returns the number of bits that are needed to represent `value`.
+fn bit_length(value: u128) -> u32 {
+    u128::BITS - value.leading_zeros()
+}
+
+/// Returns the `num_bits` lowest bits of `value`.
+fn low_bits(value: u128, num_bits: u32) -> u128 {
+    if num_bits >= u128::BITS {
+        value
+    } else {
+        value & ((1 << num_bits) - 1)
+    }
+}
+
+/// Reference implementation of [`crate::flip_hash_64_with_seed`].
+pub fn flip_hash_64_with_seed(key: u64, seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash(
+        |bit_len, iteration_index| crate::v1::hash_64(key, seed, bit_len, iteration_index).into(),
+        ..=range.end.into(),
+        crate::v1::MAX_NUM_ITERATIONS,
+    ) as u64
+}
+
+/// Reference implementation of [`crate::flip_hash_xxh3_64_with_seed`].
+#[cfg(feature = "xxh3")]
+pub fn flip_hash_xxh3_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash(
+        |bit_len, iteration_index| {
+            crate::v1::hash_xxh3_64(key, seed, bit_len, iteration_index).into()
+        },
+        ..=range.end.into(),
+        crate::v1::XXH3_MAX_NUM_ITERATIONS,
+    ) as u64
+}
+
+/// Reference implementation of [`crate::flip_hash_xxh3_128_with_seed`].
+#[cfg(feature = "xxh3")]
+pub fn flip_hash_xxh3_128_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
+    flip_hash(
+        |bit_len, iteration_index| crate::v1::hash_xxh3_128(key, seed, bit_len, iteration_index),
+        range,
+        crate::v1::XXH3_MAX_NUM_ITERATIONS,
+    )
+}
+
+/// Reference implementation of [`crate::flip_hash_aes_64_with_seed`].
+#[cfg(feature = "aes")]
+pub fn flip_hash_aes_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash(
+        |bit_len, iteration_index| crate::aes::hash(key, seed, bit_len, iteration_index).into(),
+        ..=range.end.into(),
+        crate::v1::AES_MAX_NUM_ITERATIONS,
+    ) as u64
+}
+
+/// Reference implementation of [`crate::flip_hash_murmur2_64_with_seed`].
+#[cfg(feature = "murmur2")]
+pub fn flip_hash_murmur2_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash(
+        |bit_len, iteration_index| crate::murmur2::hash(key, seed, bit_len, iteration_index).into(),
+        ..=range.end.into(),
+        crate::v1::MURMUR2_MAX_NUM_ITERATIONS,
+    ) as u64
+}
+
+/// Reference implementation of [`crate::flip_hash_keyed_64`].
+#[cfg(feature = "keyed")]
+pub fn flip_hash_keyed_64(key: &[u8], secret: &[u8; 16], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash(
+        |bit_len, iteration_index| {
+            crate::siphash::hash(key, secret, bit_len, iteration_index).into()
+        },
+        ..=range.end.into(),
+        crate::v1::KEYED_MAX_NUM_ITERATIONS,
+    ) as u64
+}
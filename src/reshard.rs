@@ -0,0 +1,167 @@
+//! Planning of the migration of keys when resizing the range, e.g., when
+//! adding or removing shards.
+//!
+//! Thanks to the monotonicity of Flip Hash, only the keys that are hashed to
+//! a different value move: when growing the range, to the new values, and
+//! when shrinking it, from the removed values. The plan splits those moves
+//! into batches of bounded size, so that the migration can be throttled,
+//! checkpointed, or rolled out gradually.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{num::NonZeroU64, ops::RangeToInclusive};
+
+use crate::flip_hash_64_with_seed;
+
+/// A key and the number of moves that moving it takes, e.g., the number of
+/// records that have this key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyCount {
+    pub key: u64,
+    pub count: u64,
+}
+
+impl From<u64> for KeyCount {
+    /// A key that takes a single move.
+    #[inline]
+    fn from(key: u64) -> Self {
+        KeyCount { key, count: 1 }
+    }
+}
+
+impl From<(u64, u64)> for KeyCount {
+    #[inline]
+    fn from((key, count): (u64, u64)) -> Self {
+        KeyCount { key, count }
+    }
+}
+
+/// The move of keys from one bucket to another.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    /// The bucket of the keys given the old range.
+    pub from: u64,
+    /// The bucket of the keys given the new range.
+    pub to: u64,
+    /// The keys to move, sorted, which are the only ones to move from
+    /// `from` to `to` in this batch.
+    pub keys: Vec<u64>,
+    /// The total count of `keys`.
+    pub count: u64,
+}
+
+impl Move {
+    /// Returns whether `key` is moved by this move, e.g., to filter the keys
+    /// of bucket `from` while scanning it.
+    #[inline]
+    pub fn contains(&self, key: u64) -> bool {
+        self.keys.binary_search(&key).is_ok()
+    }
+}
+
+/// A batch of moves, ordered by `from` then `to`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveBatch {
+    pub moves: Vec<Move>,
+}
+
+impl MoveBatch {
+    /// Returns the total count of the keys of the moves of the batch.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.moves.iter().map(|m| m.count).sum()
+    }
+}
+
+/// Plans the moves of `keys` when changing the range they are hashed to with
+/// [`crate::flip_hash_64`] from `old_range` to `new_range`, in batches whose
+/// counts do not exceed `max_moves_per_batch`.
+///
+/// `keys` are either keys, which take a move each, or a histogram of keys
+/// and counts. A key is never split across batches, so a batch only
+/// exceeds `max_moves_per_batch` if it has a single key whose count does.
+/// Duplicate keys are merged, and keys whose count is zero are skipped.
+///
+/// The batches are ordered by source bucket, then by destination bucket,
+/// then by key, so that plans are deterministic; applying them in order
+/// moves all the keys that need to move, and only them.
+///
+/// # Example
+///
+/// ```
+/// use std::num::NonZeroU64;
+///
+/// use flip_hash::{flip_hash_64, reshard::plan_reshard};
+///
+/// let keys = 0..1000_u64;
+/// let batches = plan_reshard(..=9, ..=10, keys.clone(), NonZeroU64::new(20).unwrap());
+///
+/// let moved_keys = batches
+///     .iter()
+///     .flat_map(|batch| &batch.moves)
+///     .flat_map(|m| &m.keys)
+///     .count();
+/// assert_eq!(moved_keys, keys.filter(|&key| flip_hash_64(key, ..=10) == 10).count());
+/// assert!(batches.iter().all(|batch| batch.count() <= 20));
+/// ```
+#[inline]
+pub fn plan_reshard<K: Into<KeyCount>>(
+    old_range: RangeToInclusive<u64>,
+    new_range: RangeToInclusive<u64>,
+    keys: impl IntoIterator<Item = K>,
+    max_moves_per_batch: NonZeroU64,
+) -> Vec<MoveBatch> {
+    plan_reshard_with_seed(old_range, new_range, keys, 0, max_moves_per_batch)
+}
+
+/// Plans the moves of `keys`, as [`plan_reshard`] does, given that they are
+/// hashed with [`crate::flip_hash_64_with_seed`] and `seed`.
+pub fn plan_reshard_with_seed<K: Into<KeyCount>>(
+    old_range: RangeToInclusive<u64>,
+    new_range: RangeToInclusive<u64>,
+    keys: impl IntoIterator<Item = K>,
+    seed: u64,
+    max_moves_per_batch: NonZeroU64,
+) -> Vec<MoveBatch> {
+    // (from, to) => key => count
+    let mut moves = BTreeMap::<(u64, u64), BTreeMap<u64, u64>>::new();
+    for KeyCount { key, count } in keys.into_iter().map(Into::into) {
+        if count == 0 {
+            continue;
+        }
+        let from = flip_hash_64_with_seed(key, seed, old_range);
+        let to = flip_hash_64_with_seed(key, seed, new_range);
+        if from != to {
+            let key_count = moves.entry((from, to)).or_default().entry(key).or_default();
+            *key_count = key_count.saturating_add(count);
+        }
+    }
+
+    let mut batches = Vec::new();
+    let mut batch = MoveBatch::default();
+    let mut batch_count = 0_u64;
+    for ((from, to), key_counts) in moves {
+        for (key, count) in key_counts {
+            if batch_count > 0 && batch_count.saturating_add(count) > max_moves_per_batch.get() {
+                batches.push(core::mem::take(&mut batch));
+                batch_count = 0;
+            }
+            match batch.moves.last_mut() {
+                Some(m) if (m.from, m.to) == (from, to) => {
+                    m.keys.push(key);
+                    m.count = m.count.saturating_add(count);
+                }
+                _ => batch.moves.push(Move {
+                    from,
+                    to,
+                    keys: alloc::vec![key],
+                    count,
+                }),
+            }
+            batch_count = batch_count.saturating_add(count);
+        }
+    }
+    if !batch.moves.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
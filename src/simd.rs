@@ -0,0 +1,93 @@
+//! Flip Hash of 64-bit keys over SIMD lanes, which hashes several keys at
+//! once.
+//!
+//! It computes the same hashes as the scalar implementation: lanes whose
+//! hash to the largest power of two is out of the range keep drawing values
+//! until all lanes are done, and the other ones are masked.
+
+use core::{
+    ops::RangeToInclusive,
+    simd::{
+        cmp::{SimdPartialEq, SimdPartialOrd},
+        num::SimdUint,
+        Mask, Select, Simd,
+    },
+};
+
+use crate::v1::MAX_NUM_ITERATIONS;
+
+const NUM_LANES: usize = 8;
+
+type Lanes = Simd<u64, NUM_LANES>;
+
+/// Hashes the keys of the chunks of `NUM_LANES` keys of `keys`, and returns
+/// the remaining keys and their hashes, which are left to the caller.
+#[inline]
+pub(crate) fn flip_hash_64_many_with_seed<'a, 'b>(
+    keys: &'a [u64],
+    seed: u64,
+    range: RangeToInclusive<u64>,
+    out: &'b mut [u64],
+) -> (&'a [u64], &'b mut [u64]) {
+    let (key_chunks, remaining_keys) = keys.as_chunks::<NUM_LANES>();
+    let (hash_chunks, remaining_hashes) = out.as_chunks_mut::<NUM_LANES>();
+    for (keys, hashes) in key_chunks.iter().zip(hash_chunks) {
+        flip_hash(Lanes::from_array(*keys), seed, range).copy_to_slice(hashes);
+    }
+    (remaining_keys, remaining_hashes)
+}
+
+#[inline(always)]
+fn flip_hash(keys: Lanes, seed: u64, range: RangeToInclusive<u64>) -> Lanes {
+    if range.end == 0 {
+        return Lanes::splat(0);
+    }
+    let pow2_mask = !0 >> range.end.leading_zeros(); // == 2^r - 1
+    let hash = hash_64(keys, seed, Lanes::splat(0), 0);
+    let mut flip_hash = flip_hash_pow2(keys, seed, hash, pow2_mask);
+
+    let mut drawing = flip_hash.simd_gt(Lanes::splat(range.end));
+    if drawing.any() {
+        let mut falling_back = Mask::splat(false);
+        let bit_len = Lanes::splat(range.end.ilog2().into());
+        let mut iteration_index = 1; // i
+        while drawing.any() && iteration_index <= MAX_NUM_ITERATIONS {
+            let draw = hash_64(keys, seed, bit_len, iteration_index) & Lanes::splat(pow2_mask);
+            let in_lower_half = draw.simd_le(Lanes::splat(pow2_mask >> 1));
+            let in_range = draw.simd_le(Lanes::splat(range.end));
+            falling_back |= drawing & in_lower_half;
+            flip_hash = (drawing & in_range & !in_lower_half).select(draw, flip_hash);
+            drawing &= !in_range;
+            iteration_index += 1;
+        }
+        falling_back |= drawing;
+        if falling_back.any() {
+            flip_hash =
+                falling_back.select(flip_hash_pow2(keys, seed, hash, pow2_mask >> 1), flip_hash);
+        }
+    }
+    flip_hash
+}
+
+#[inline(always)]
+fn flip_hash_pow2(keys: Lanes, seed: u64, hash: Lanes, pow2_mask: u64) -> Lanes {
+    let masked_hash = hash & Lanes::splat(pow2_mask);
+    let leading_zeros = masked_hash.leading_zeros();
+    // The lanes whose masked hash is zero are discarded.
+    let bit_len = Lanes::splat(u64::BITS as u64 - 1).saturating_sub(leading_zeros);
+    let flipper = hash_64(keys, seed, bit_len, 0) & (Lanes::splat(!0) >> leading_zeros >> 1);
+    masked_hash
+        .simd_eq(Lanes::splat(0))
+        .select(Lanes::splat(0), masked_hash ^ flipper)
+}
+
+/// [`crate::v1::hash_64`], lane-wise.
+#[inline(always)]
+fn hash_64(keys: Lanes, seed: u64, bit_len: Lanes, iteration_index: u32) -> Lanes {
+    let mut k = keys ^ Lanes::splat(seed);
+    k *= bit_len * Lanes::splat(2) + Lanes::splat(1);
+    k = (k ^ (k >> 27)) * Lanes::splat(0x3C79AC492BA7B653);
+    k *= Lanes::splat(iteration_index as u64 * 2 + 1);
+    k = (k ^ (k >> 33)) * Lanes::splat(0x1C69B3F74AC4AE35);
+    k ^ (k >> 27)
+}
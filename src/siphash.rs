@@ -0,0 +1,85 @@
+//! SipHash-2-4, a keyed hash function: without its 128-bit secret, one
+//! cannot predict its hashes, nor find inputs that collide.
+
+#[inline(always)]
+pub(crate) fn hash(key: &[u8], secret: &[u8; 16], bit_len: u32, iteration_index: u32) -> u64 {
+    // The hash of a key differs across bit lengths and iteration indices
+    // because they are hashed as a prefix of the key.
+    let mut state = State::new(secret);
+    state.compress(bit_len as u64 + ((iteration_index as u64) << 32));
+    state.hash(key, 8)
+}
+
+/// Returns the SipHash-2-4 of `data`.
+#[cfg(test)]
+pub(crate) fn sip_hash_2_4(data: &[u8], secret: &[u8; 16]) -> u64 {
+    State::new(secret).hash(data, 0)
+}
+
+struct State {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl State {
+    #[inline(always)]
+    fn new(secret: &[u8; 16]) -> Self {
+        let k0 = u64::from_le_bytes(secret[..8].try_into().unwrap());
+        let k1 = u64::from_le_bytes(secret[8..].try_into().unwrap());
+        State {
+            v0: k0 ^ 0x736F6D6570736575,
+            v1: k1 ^ 0x646F72616E646F6D,
+            v2: k0 ^ 0x6C7967656E657261,
+            v3: k1 ^ 0x7465646279746573,
+        }
+    }
+
+    /// Hashes `data`, given that `num_hashed_bytes` bytes have already been
+    /// hashed, and returns the hash.
+    #[inline(always)]
+    fn hash(mut self, data: &[u8], num_hashed_bytes: usize) -> u64 {
+        let mut words = data.chunks_exact(8);
+        for word in &mut words {
+            self.compress(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+        let mut last_word = ((num_hashed_bytes + data.len()) as u64) << 56;
+        for (i, &b) in words.remainder().iter().enumerate() {
+            last_word |= (b as u64) << (8 * i);
+        }
+        self.compress(last_word);
+
+        self.v2 ^= 0xFF;
+        for _ in 0..4 {
+            self.round();
+        }
+        self.v0 ^ self.v1 ^ self.v2 ^ self.v3
+    }
+
+    #[inline(always)]
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.round();
+        self.v0 ^= word;
+    }
+
+    #[inline(always)]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+}
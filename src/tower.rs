@@ -0,0 +1,68 @@
+//! Integration with [tower](https://docs.rs/tower): a [`Picker`] that routes
+//! requests to services with Flip Hash, for consistent backend affinity.
+//!
+//! It is meant to be used with [`Steer`](::tower::steer::Steer), which sends
+//! requests to the services that the picker selects. When services are added
+//! at the end of the list, or the last ones are removed, only the keys that
+//! Flip Hash remaps change of service. Removing another service shifts the
+//! indices of the following ones, which reassigns most keys.
+
+use ::tower::steer::Picker;
+
+use crate::v1::flip_hash_64;
+
+/// A [`Picker`] that hashes the key that `key` extracts from a request to
+/// the index of a service, with [`flip_hash_64`], of [`crate::v1`] so that
+/// the affinity does not change across releases.
+///
+/// The services are those that are passed to
+/// [`Steer::new`](::tower::steer::Steer::new).
+///
+/// # Panics
+///
+/// Picking panics if there are no services.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use flip_hash::tower::FlipHashPicker;
+/// use tower::{service_fn, steer::Steer};
+///
+/// struct Request {
+///     user_id: u64,
+/// }
+///
+/// let backends = (0..3)
+///     .map(|backend| service_fn(move |_: Request| async move { Ok::<_, Infallible>(backend) }))
+///     .collect::<Vec<_>>();
+/// let picker = FlipHashPicker::new(|request: &Request| request.user_id);
+/// let balancer: Steer<_, _, Request> = Steer::new(backends, picker);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FlipHashPicker<F> {
+    key: F,
+}
+
+impl<F> FlipHashPicker<F> {
+    /// Creates a picker that hashes the keys that `key` extracts from
+    /// requests.
+    pub fn new(key: F) -> Self {
+        FlipHashPicker { key }
+    }
+}
+
+impl<S, Req, F> Picker<S, Req> for FlipHashPicker<F>
+where
+    F: Fn(&Req) -> u64,
+{
+    #[inline]
+    fn pick(&mut self, request: &Req, services: &[S]) -> usize {
+        let range_end = services
+            .len()
+            .checked_sub(1)
+            .expect("there are no services to pick from");
+        flip_hash_64((self.key)(request), ..=range_end as u64) as usize
+    }
+}
@@ -0,0 +1,698 @@
+//! Version 1 of Flip Hash, whose hashes never change.
+//!
+//! Every function of this module is guaranteed to hash a given input to the
+//! same value in all future releases of this crate, and on all platforms, so
+//! that hashes can be persisted, e.g., assignments of keys to shards. This
+//! covers the hash functions that the variants are built on: the hashes of
+//! [`flip_hash_aes_64`] do not depend on AES-NI being available, and those
+//! of the XXH3-based functions do not depend on the SIMD instructions that
+//! are used. Golden vectors, in `tests/v1.rs`, enforce that guarantee.
+//!
+//! Any change to the hashes will be made in a new module, e.g., `v2`. The
+//! functions at the root of the crate are re-exports of those of the latest
+//! version; use the ones of this module to opt out of future changes.
+//!
+//! # Example
+//!
+//! ```
+//! use flip_hash::v1;
+//!
+//! assert_eq!(v1::flip_hash_64(15960427081186311679, ..=17), 17);
+//! ```
+
+use core::ops::RangeToInclusive;
+
+#[cfg(feature = "aes")]
+use crate::aes;
+#[cfg(feature = "murmur2")]
+use crate::murmur2;
+#[cfg(feature = "simd")]
+use crate::simd;
+#[cfg(feature = "keyed")]
+use crate::siphash;
+#[cfg(feature = "xxh3")]
+use crate::xxh3;
+use crate::FlipHashError;
+
+macro_rules! flip_hash {
+    ($hash_fn: path, $key: expr, $seed: expr, $range: expr, $max_num_iterations: expr) => {
+        match $range.end {
+            0 => 0,
+            _ => flip_hash_nonzero!(
+                $hash_fn,
+                $key,
+                $seed,
+                $range,
+                !0 >> $range.end.leading_zeros(),
+                $range.end.ilog2(),
+                $max_num_iterations
+            ),
+        }
+    };
+}
+
+/// Flip Hash of a range whose end is not 0, given the mask of the enclosing
+/// power of two and its logarithm, which only depend on the range.
+macro_rules! flip_hash_nonzero {
+    (
+        $hash_fn: path,
+        $key: expr,
+        $seed: expr,
+        $range: expr,
+        $pow2_mask: expr,
+        $range_ilog2: expr,
+        $max_num_iterations: expr
+    ) => {{
+        let pow2_mask = $pow2_mask; // == 2^r - 1
+        let hash = $hash_fn($key, $seed, 0, 0);
+        match flip_hash_pow2!($hash_fn, $key, $seed, hash, pow2_mask) {
+            flip_hash_pow2 if flip_hash_pow2 <= $range.end => flip_hash_pow2,
+            _ => {
+                let mut iteration_index = 1; // i
+                if let Some(draw) = loop {
+                    if iteration_index > $max_num_iterations {
+                        break None;
+                    }
+                    let draw = $hash_fn($key, $seed, $range_ilog2, iteration_index) & pow2_mask;
+                    if draw <= pow2_mask >> 1 {
+                        break None;
+                    } else if draw <= $range.end {
+                        break Some(draw);
+                    }
+                    iteration_index += 1;
+                } {
+                    draw
+                } else {
+                    flip_hash_pow2!($hash_fn, $key, $seed, hash, pow2_mask >> 1)
+                }
+            }
+        }
+    }};
+}
+
+macro_rules! flip_hash_pow2 {
+    ($hash_fn: path, $key: expr, $seed: expr, $hash: expr, $pow2_mask: expr) => {
+        match $hash & $pow2_mask {
+            0 => 0,
+            masked_hash => {
+                let flipper = $hash_fn($key, $seed, masked_hash.ilog2(), 0)
+                    & !0 >> masked_hash.leading_zeros() >> 1;
+                masked_hash ^ flipper
+            }
+        }
+    };
+}
+
+/// Hashes `key` to a value of `range`, uniformly and with stability.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::flip_hash_64;
+///
+/// let key = 15960427081186311679;
+/// let hash_17 = flip_hash_64(key, ..=17);
+/// let hash_18 = flip_hash_64(key, ..=18);
+///
+/// assert!(hash_17 <= 17);
+/// assert!(hash_18 == hash_17 || hash_18 == 18);
+/// ```
+#[inline]
+pub const fn flip_hash_64(key: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_64_with_seed(key, 0, range)
+}
+
+#[inline]
+pub const fn flip_hash_64_with_seed(key: u64, seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash!(hash_64, key, seed, range, MAX_NUM_ITERATIONS)
+}
+
+/// Hashes `key` to a value of `range`, as [`flip_hash_64`] does, and
+/// returns it as a `T`.
+///
+/// # Errors
+///
+/// Returns [`FlipHashError::RangeTooLarge`] if the end of `range` does not
+/// fit in a `T`. Otherwise, the hash fits in a `T` too.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, flip_hash_64_as};
+///
+/// let key = 15960427081186311679;
+/// let hash: u16 = flip_hash_64_as(key, ..=17).unwrap();
+///
+/// assert_eq!(u64::from(hash), flip_hash_64(key, ..=17));
+/// assert!(flip_hash_64_as::<u8>(key, ..=256).is_err());
+/// ```
+#[inline]
+pub fn flip_hash_64_as<T: TryFrom<u64>>(
+    key: u64,
+    range: RangeToInclusive<u64>,
+) -> Result<T, FlipHashError> {
+    flip_hash_64_with_seed_as(key, 0, range)
+}
+
+#[inline]
+pub fn flip_hash_64_with_seed_as<T: TryFrom<u64>>(
+    key: u64,
+    seed: u64,
+    range: RangeToInclusive<u64>,
+) -> Result<T, FlipHashError> {
+    T::try_from(range.end).map_err(|_| FlipHashError::RangeTooLarge)?;
+    T::try_from(flip_hash_64_with_seed(key, seed, range)).map_err(|_| FlipHashError::RangeTooLarge)
+}
+
+/// Hashes `key` to a value of `range` once per seed of `seeds`, writing the
+/// hashes to `out`, e.g., to place the replicas of a key.
+///
+/// This is equivalent to calling [`flip_hash_64_with_seed`] for each seed,
+/// but the mask of the power of two that encloses the range, and its
+/// logarithm, are computed once for all the seeds.
+///
+/// # Panics
+///
+/// Panics if `seeds` and `out` do not have the same length.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64_multi_seed, flip_hash_64_with_seed};
+///
+/// let key = 15960427081186311679;
+/// let seeds = [1, 2, 3];
+/// let mut hashes = [0; 3];
+/// flip_hash_64_multi_seed(key, &seeds, ..=17, &mut hashes);
+///
+/// assert_eq!(hashes[1], flip_hash_64_with_seed(key, 2, ..=17));
+/// ```
+#[inline]
+pub fn flip_hash_64_multi_seed(
+    key: u64,
+    seeds: &[u64],
+    range: RangeToInclusive<u64>,
+    out: &mut [u64],
+) {
+    assert_eq!(seeds.len(), out.len());
+    if range.end == 0 {
+        out.fill(0);
+        return;
+    }
+    let pow2_mask = !0 >> range.end.leading_zeros();
+    let range_ilog2 = range.end.ilog2();
+    for (&seed, hash) in seeds.iter().zip(out) {
+        *hash = flip_hash_nonzero!(
+            hash_64,
+            key,
+            seed,
+            range,
+            pow2_mask,
+            range_ilog2,
+            MAX_NUM_ITERATIONS
+        );
+    }
+}
+
+/// Hashes `key` to the location of its replica of index `replica_index`, a
+/// value of `range`.
+///
+/// The locations of the replicas of a key are independent of one another,
+/// and the replica of index 0 is located at [`flip_hash_64`]. Replicas may be
+/// located at the same value; callers that need distinct locations can skip
+/// the duplicates, e.g., by hashing replicas of increasing indices until
+/// enough distinct locations are found.
+///
+/// This is [`flip_hash_64_with_seed`] with the seed [`replica_seed`]. Using
+/// the replica index as the seed directly would not do, as the seed is
+/// xor-ed with the key: replica 1 of a key would be replica 0 of the key
+/// that only differs in its lowest bit.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, flip_hash_64_with_replica};
+///
+/// let key = 15960427081186311679;
+/// let locations = [0, 1, 2].map(|replica_index| {
+///     flip_hash_64_with_replica(key, replica_index, ..=17)
+/// });
+///
+/// assert_eq!(locations[0], flip_hash_64(key, ..=17));
+/// assert!(locations.iter().all(|&location| location <= 17));
+/// ```
+#[inline]
+pub const fn flip_hash_64_with_replica(
+    key: u64,
+    replica_index: u32,
+    range: RangeToInclusive<u64>,
+) -> u64 {
+    flip_hash_64_with_seed(key, replica_seed(replica_index), range)
+}
+
+/// Returns the seed of the replica of index `replica_index`, as used by
+/// [`flip_hash_64_with_replica`], e.g., to locate several replicas at once
+/// with [`flip_hash_64_multi_seed`].
+///
+/// It is the finalizer of MurmurHash3 applied to the index, a bijection that
+/// maps 0 to 0 and consecutive indices to seeds that look random.
+#[inline]
+pub const fn replica_seed(replica_index: u32) -> u64 {
+    let mut k = replica_index as u64;
+    k = (k ^ (k >> 33)).wrapping_mul(0xFF51AFD7ED558CCD);
+    k = (k ^ (k >> 33)).wrapping_mul(0xC4CEB9FE1A85EC53);
+    k ^ (k >> 33)
+}
+
+/// Hashes each key of `keys` to a value of `range`, writing the hashes to
+/// `out`, e.g., to reassign many keys at once.
+///
+/// This is equivalent to calling [`flip_hash_64`] for each key. With the
+/// `simd` feature, which requires a nightly compiler, 8 keys are hashed at
+/// once with SIMD instructions. That is only faster if the target has SIMD
+/// multiplications of 64-bit integers, e.g., x86-64 with AVX-512DQ enabled
+/// (`-C target-cpu=native` on recent CPUs); otherwise, they are emulated and
+/// it is several times slower.
+///
+/// # Panics
+///
+/// Panics if `keys` and `out` do not have the same length.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, flip_hash_64_many};
+///
+/// let keys = [15960427081186311679, 10427592028180905159, 42];
+/// let mut hashes = [0; 3];
+/// flip_hash_64_many(&keys, ..=17, &mut hashes);
+///
+/// assert_eq!(hashes[1], flip_hash_64(keys[1], ..=17));
+/// ```
+#[inline]
+pub fn flip_hash_64_many(keys: &[u64], range: RangeToInclusive<u64>, out: &mut [u64]) {
+    flip_hash_64_many_with_seed(keys, 0, range, out)
+}
+
+#[inline]
+pub fn flip_hash_64_many_with_seed(
+    keys: &[u64],
+    seed: u64,
+    range: RangeToInclusive<u64>,
+    out: &mut [u64],
+) {
+    assert_eq!(keys.len(), out.len());
+    #[cfg(feature = "simd")]
+    let (keys, out) = simd::flip_hash_64_many_with_seed(keys, seed, range, out);
+    for (&key, hash) in keys.iter().zip(out) {
+        *hash = flip_hash!(hash_64, key, seed, range, MAX_NUM_ITERATIONS);
+    }
+}
+
+/// The number of keys that the parallel functions hash at once.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_LEN: usize = 1024;
+
+/// Hashes each key of `keys` to a value of `range` in parallel, using
+/// rayon's global thread pool, and returns the hashes.
+///
+/// This is equivalent to [`flip_hash_64_many`], and is meant for jobs that
+/// reassign millions of keys, e.g., to plan a resharding.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, par_flip_hash_many};
+///
+/// let keys = (0..100_000).collect::<Vec<u64>>();
+/// let hashes = par_flip_hash_many(&keys, ..=17);
+///
+/// assert_eq!(hashes[42], flip_hash_64(42, ..=17));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_flip_hash_many(keys: &[u64], range: RangeToInclusive<u64>) -> alloc::vec::Vec<u64> {
+    use rayon::prelude::*;
+
+    let mut hashes = alloc::vec![0; keys.len()];
+    keys.par_chunks(PAR_CHUNK_LEN)
+        .zip(hashes.par_chunks_mut(PAR_CHUNK_LEN))
+        .for_each(|(keys, hashes)| flip_hash_64_many(keys, range, hashes));
+    hashes
+}
+
+/// Hashes each key of `keys` to a value of `range` in parallel, using
+/// rayon's global thread pool, and returns the number of keys that are hashed
+/// to each value of `range`.
+///
+/// Every thread counts hashes in its own histogram, which takes as many
+/// integers as there are values in `range`.
+///
+/// # Panics
+///
+/// Panics if `range` has more values than can be counted in memory.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::par_flip_hash_histogram;
+///
+/// let keys = (0..100_000).collect::<Vec<u64>>();
+/// let histogram = par_flip_hash_histogram(&keys, ..=17);
+///
+/// assert_eq!(histogram.len(), 18);
+/// assert_eq!(histogram.iter().sum::<u64>(), 100_000);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_flip_hash_histogram(keys: &[u64], range: RangeToInclusive<u64>) -> alloc::vec::Vec<u64> {
+    use rayon::prelude::*;
+
+    let num_buckets = usize::try_from(range.end)
+        .ok()
+        .and_then(|range_end| range_end.checked_add(1))
+        .expect("the range is too large for a histogram");
+    keys.par_chunks(PAR_CHUNK_LEN)
+        .fold(
+            || alloc::vec![0; num_buckets],
+            |mut histogram, keys| {
+                let mut hashes = [0; PAR_CHUNK_LEN];
+                let hashes = &mut hashes[..keys.len()];
+                flip_hash_64_many(keys, range, hashes);
+                for &hash in &*hashes {
+                    histogram[hash as usize] += 1;
+                }
+                histogram
+            },
+        )
+        .reduce_with(|mut histogram, other_histogram| {
+            for (count, other_count) in histogram.iter_mut().zip(other_histogram) {
+                *count += other_count;
+            }
+            histogram
+        })
+        .unwrap_or_else(|| alloc::vec![0; num_buckets])
+}
+
+/// Flip Hash to a range whose end is known at compile time, e.g., a number
+/// of shards that is fixed.
+///
+/// It computes the same hashes as [`flip_hash_64_with_seed`], but the
+/// computations that only depend on the range are done at compile time, and
+/// the branches that the range makes unreachable are eliminated, e.g., there
+/// are no draws if the range end is a power of two minus one.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, FixedFlipRange};
+///
+/// type Shards = FixedFlipRange<17>;
+///
+/// let key = 15960427081186311679;
+///
+/// assert_eq!(Shards::hash(key), flip_hash_64(key, ..=17));
+/// assert!(Shards::RANGE.contains(&Shards::hash(key)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedFlipRange<const END: u64>;
+
+impl<const END: u64> FixedFlipRange<END> {
+    pub const RANGE: RangeToInclusive<u64> = ..=END;
+
+    /// Hashes `key` to a value of `..=END`, as [`flip_hash_64`] does.
+    #[inline(always)]
+    pub const fn hash(key: u64) -> u64 {
+        Self::hash_with_seed(key, 0)
+    }
+
+    /// Hashes `key` to a value of `..=END`, as [`flip_hash_64_with_seed`]
+    /// does.
+    #[inline(always)]
+    pub const fn hash_with_seed(key: u64, seed: u64) -> u64 {
+        flip_hash!(hash_64, key, seed, Self::RANGE, MAX_NUM_ITERATIONS)
+    }
+}
+
+pub(crate) const MAX_NUM_ITERATIONS: u32 = 64;
+
+#[inline(always)]
+pub(crate) const fn hash_64(key: u64, seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
+    // Inspired by https://mostlymangling.blogspot.com/2019/12/stronger-better-morer-moremur-better.html
+    let mut k = key ^ seed;
+    k = k.wrapping_mul(bit_len as u64 * 2 + 1);
+    k = (k ^ (k >> 27)).wrapping_mul(0x3C79AC492BA7B653);
+    k = k.wrapping_mul(iteration_index as u64 * 2 + 1);
+    k = (k ^ (k >> 33)).wrapping_mul(0x1C69B3F74AC4AE35);
+    k ^ (k >> 27)
+}
+
+#[cfg(feature = "xxh3")]
+pub(crate) const XXH3_MAX_NUM_ITERATIONS: u32 = 64;
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_xxh3_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_xxh3_64_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_xxh3_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash!(hash_xxh3_64, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
+}
+
+/// Hashes the string `key` to a value of `range`, as
+/// [`flip_hash_xxh3_64`] hashes its UTF-8 bytes.
+///
+/// The string is hashed as is, without any normalization, so strings that
+/// users see as equal may be hashed to different values, e.g., `"Key"` and
+/// `"key"`, `"key "` and `"key"`, or `"é"` as one code point (NFC) and as
+/// two (NFD). Services that shard by the same strings should normalize them
+/// the same way before hashing them, e.g., by trimming them, by lowercasing
+/// them, or by converting them to Unicode Normalization Form C, and keys that
+/// are not strings, e.g., UTF-16 strings of other languages, should be
+/// converted to UTF-8 first.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_str, flip_hash_xxh3_64};
+///
+/// let hash = flip_hash_str("café", ..=17);
+///
+/// assert_eq!(hash, flip_hash_xxh3_64(b"caf\xC3\xA9", ..=17));
+/// ```
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_str(key: &str, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_xxh3_64(key.as_bytes(), range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_str_with_seed(key: &str, seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_xxh3_64_with_seed(key.as_bytes(), seed, range)
+}
+
+/// Hashes the UUID `key` to a value of `range`, as [`flip_hash_xxh3_64`]
+/// hashes its 16 bytes in network order, i.e., [`uuid::Uuid::as_bytes`], which
+/// are also the big-endian bytes of [`uuid::Uuid::as_u128`].
+///
+/// Services that store UUIDs in other forms should convert them back to
+/// UUIDs first, e.g., with [`uuid::Uuid::from_bytes_le`] for the mixed-endian
+/// bytes of Microsoft's GUIDs, or with [`uuid::Uuid::from_u128`] for 128-bit
+/// integers, rather than hash these bytes, which differ.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_uuid, flip_hash_xxh3_64};
+/// use uuid::Uuid;
+///
+/// let key = Uuid::from_u128(0x67E55044_10B1_426F_9247_BB680E5FE0C8);
+/// let hash = flip_hash_uuid(key, ..=17);
+///
+/// assert_eq!(hash, flip_hash_xxh3_64(&key.as_u128().to_be_bytes(), ..=17));
+/// ```
+#[cfg(feature = "uuid")]
+#[inline]
+pub fn flip_hash_uuid(key: uuid::Uuid, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_uuid_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "uuid")]
+#[inline]
+pub fn flip_hash_uuid_with_seed(key: uuid::Uuid, seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_xxh3_64_with_seed(key.as_bytes(), seed, range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline(always)]
+pub(crate) fn hash_xxh3_64(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
+    xxh3::hash_64(
+        key,
+        seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+    )
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub const fn flip_hash_const_xxh3_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_const_xxh3_64_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub const fn flip_hash_const_xxh3_64_with_seed(
+    key: &[u8],
+    seed: u64,
+    range: RangeToInclusive<u64>,
+) -> u64 {
+    #[inline(always)]
+    const fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u64 {
+        xxhash_rust::const_xxh3::xxh3_64_with_seed(
+            key,
+            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+        )
+    }
+    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_xxh3_128(key: &[u8], range: RangeToInclusive<u128>) -> u128 {
+    flip_hash_xxh3_128_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub fn flip_hash_xxh3_128_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
+    flip_hash!(hash_xxh3_128, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline(always)]
+pub(crate) fn hash_xxh3_128(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u128 {
+    xxh3::hash_128(
+        key,
+        seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+    )
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub const fn flip_hash_const_xxh3_128(key: &[u8], range: RangeToInclusive<u128>) -> u128 {
+    flip_hash_const_xxh3_128_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "xxh3")]
+#[inline]
+pub const fn flip_hash_const_xxh3_128_with_seed(
+    key: &[u8],
+    seed: u64,
+    range: RangeToInclusive<u128>,
+) -> u128 {
+    #[inline(always)]
+    const fn hash(key: &[u8], seed: u64, bit_len: u32, iteration_index: u32) -> u128 {
+        xxhash_rust::const_xxh3::xxh3_128_with_seed(
+            key,
+            seed ^ (bit_len as u64 + ((iteration_index as u64) << 32)),
+        )
+    }
+    flip_hash!(hash, key, seed, range, XXH3_MAX_NUM_ITERATIONS)
+}
+
+#[cfg(feature = "aes")]
+pub(crate) const AES_MAX_NUM_ITERATIONS: u32 = 64;
+
+/// Hashes `key` to a value of `range`, uniformly and with stability, using a
+/// hash function that is built on AES rounds.
+///
+/// It is hardware-accelerated on x86-64 CPUs with AES-NI, which makes it
+/// faster than the XXH3-based functions for short keys. The hashes do not
+/// depend on the CPU: on other CPUs, the same AES rounds are computed in
+/// software, which is much slower. With the `std` feature, AES-NI is detected
+/// at runtime; otherwise, it is only used if enabled at compile time, e.g.,
+/// with `-C target-feature=+aes`.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::flip_hash_aes_64;
+///
+/// let hash = flip_hash_aes_64(b"key", ..=17);
+///
+/// assert!(hash <= 17);
+/// ```
+#[cfg(feature = "aes")]
+#[inline]
+pub fn flip_hash_aes_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_aes_64_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "aes")]
+#[inline]
+pub fn flip_hash_aes_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash!(aes::hash, key, seed, range, AES_MAX_NUM_ITERATIONS)
+}
+
+#[cfg(feature = "keyed")]
+pub(crate) const KEYED_MAX_NUM_ITERATIONS: u32 = 64;
+
+/// Hashes `key` to a value of `range`, uniformly and with stability, using
+/// SipHash-2-4 keyed with a 128-bit `secret`.
+///
+/// Unlike with the seeded functions, whose hashes can be predicted by
+/// brute-forcing the 64-bit seed, hashes cannot be predicted without the
+/// secret. This makes it suitable for keys that can be chosen by adversaries,
+/// who otherwise could pick keys that are hashed to the same value, so as to
+/// overload it. The secret should be drawn from a cryptographically secure
+/// source of randomness, and kept secret.
+///
+/// SipHash is slower than the non-cryptographic hash functions of the other
+/// variants.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::flip_hash_keyed_64;
+///
+/// let secret = [
+///     0x0F, 0x1E, 0x2D, 0x3C, 0x4B, 0x5A, 0x69, 0x78, 0x87, 0x96, 0xA5, 0xB4, 0xC3, 0xD2, 0xE1,
+///     0xF0,
+/// ];
+/// let hash = flip_hash_keyed_64(b"key", &secret, ..=17);
+///
+/// assert!(hash <= 17);
+/// ```
+#[cfg(feature = "keyed")]
+#[inline]
+pub fn flip_hash_keyed_64(key: &[u8], secret: &[u8; 16], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash!(siphash::hash, key, secret, range, KEYED_MAX_NUM_ITERATIONS)
+}
+
+#[cfg(feature = "murmur2")]
+pub(crate) const MURMUR2_MAX_NUM_ITERATIONS: u32 = 64;
+
+/// Hashes `key` to a value of `range`, uniformly and with stability, using
+/// MurmurHash64A, the 64-bit variant of MurmurHash2.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::flip_hash_murmur2_64;
+///
+/// let hash = flip_hash_murmur2_64(b"key", ..=17);
+///
+/// assert!(hash <= 17);
+/// ```
+#[cfg(feature = "murmur2")]
+#[inline]
+pub fn flip_hash_murmur2_64(key: &[u8], range: RangeToInclusive<u64>) -> u64 {
+    flip_hash_murmur2_64_with_seed(key, 0, range)
+}
+
+#[cfg(feature = "murmur2")]
+#[inline]
+pub fn flip_hash_murmur2_64_with_seed(key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+    flip_hash!(murmur2::hash, key, seed, range, MURMUR2_MAX_NUM_ITERATIONS)
+}
@@ -0,0 +1,24 @@
+//! [Kani](https://model-checking.github.io/kani/) proof harnesses.
+//!
+//! They are only compiled by `cargo kani` and check properties of Flip Hash
+//! over all possible keys, seeds and ranges, rather than over sampled inputs
+//! as the property tests do.
+
+// The draw loop runs at most `MAX_NUM_ITERATIONS` (64) times; the extra
+// unwindings let Kani prove that it terminates.
+#[kani::proof]
+#[kani::unwind(66)]
+fn flip_hash_64_in_range() {
+    let (key, seed, range_end): (u64, u64, u64) = kani::any();
+    assert!(crate::flip_hash_64_with_seed(key, seed, ..=range_end) <= range_end);
+}
+
+#[kani::proof]
+#[kani::unwind(66)]
+fn flip_hash_64_default_seed() {
+    let (key, range_end): (u64, u64) = kani::any();
+    assert_eq!(
+        crate::flip_hash_64(key, ..=range_end),
+        crate::flip_hash_64_with_seed(key, 0, ..=range_end)
+    );
+}
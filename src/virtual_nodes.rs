@@ -0,0 +1,233 @@
+//! Weighted routing on top of the unweighted Flip Hash, by expanding nodes
+//! into virtual buckets.
+//!
+//! Every node gets a number of buckets of the range that is proportional to
+//! its weight, and keys are hashed to buckets, then mapped to the nodes that
+//! own them.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::ops::RangeToInclusive;
+
+use crate::{flip_hash_64_with_seed, FlipHashError};
+
+/// The expansion of weighted nodes into a range of virtual buckets, and the
+/// reverse map from buckets to nodes.
+///
+/// To keep the expansion stable when weights change, derive the new one from
+/// the current one with [`VirtualNodes::reweight`], which only reassigns as
+/// many buckets as the change of weights requires, so that only the keys of
+/// those buckets move to other nodes.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::virtual_nodes::VirtualNodes;
+///
+/// let nodes = VirtualNodes::new([("a", 1.0), ("b", 1.0), ("c", 2.0)], 1000)?;
+/// assert_eq!(nodes.num_buckets(&"c"), 500);
+///
+/// let node = nodes.hash(15960427081186311679);
+/// assert!(["a", "b", "c"].contains(node));
+///
+/// // Only 25 buckets of "c" are given to "a".
+/// let reweighted_nodes = nodes.reweight([("a", 1.1), ("b", 1.0), ("c", 1.9)])?;
+/// assert_eq!(reweighted_nodes.num_buckets(&"c"), 475);
+/// assert_eq!(
+///     (0..1000)
+///         .filter(|&bucket| nodes.node(bucket) != reweighted_nodes.node(bucket))
+///         .count(),
+///     25
+/// );
+/// # Ok::<(), flip_hash::FlipHashError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct VirtualNodes<N> {
+    nodes: Vec<N>,
+    /// The index in `nodes` of the node of every bucket.
+    buckets: Vec<usize>,
+}
+
+impl<N> VirtualNodes<N> {
+    /// Expands `weighted_nodes` into `num_buckets` buckets.
+    ///
+    /// The numbers of buckets of the nodes are proportional to their weights,
+    /// rounded with the largest remainder method. A node whose weight is too
+    /// small for `num_buckets` may get no bucket, so `num_buckets` should be
+    /// large enough for the precision that the weights require.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlipHashError::EmptyRange`] if there are no nodes or no
+    /// buckets, and [`FlipHashError::InvalidWeight`] if a weight is not a
+    /// positive finite number.
+    pub fn new(
+        weighted_nodes: impl IntoIterator<Item = (N, f64)>,
+        num_buckets: usize,
+    ) -> Result<Self, FlipHashError> {
+        let (nodes, weights) = weighted_nodes.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        let mut buckets = Vec::with_capacity(num_buckets);
+        for (node_index, node_num_buckets) in num_buckets_per_node(&weights, num_buckets)?
+            .into_iter()
+            .enumerate()
+        {
+            buckets.extend(core::iter::repeat_n(node_index, node_num_buckets));
+        }
+        Ok(VirtualNodes { nodes, buckets })
+    }
+
+    /// Returns the range of the buckets.
+    #[inline]
+    pub fn range(&self) -> RangeToInclusive<u64> {
+        ..=self.buckets.len() as u64 - 1
+    }
+
+    /// Returns the node of `bucket`, or `None` if it is not in
+    /// [`VirtualNodes::range`].
+    #[inline]
+    pub fn node(&self, bucket: u64) -> Option<&N> {
+        let node_index = *self.buckets.get(usize::try_from(bucket).ok()?)?;
+        Some(&self.nodes[node_index])
+    }
+
+    /// Returns the nodes, including those that have no buckets, in the order
+    /// in which they were given.
+    #[inline]
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Hashes `key` to a node, through a bucket that [`crate::flip_hash_64`]
+    /// hashes it to.
+    #[inline]
+    pub fn hash(&self, key: u64) -> &N {
+        self.hash_with_seed(key, 0)
+    }
+
+    /// Hashes `key` to a node, through a bucket that
+    /// [`crate::flip_hash_64_with_seed`] hashes it to given `seed`.
+    #[inline]
+    pub fn hash_with_seed(&self, key: u64, seed: u64) -> &N {
+        let bucket = flip_hash_64_with_seed(key, seed, self.range());
+        &self.nodes[self.buckets[bucket as usize]]
+    }
+}
+
+impl<N: Ord> VirtualNodes<N> {
+    /// Returns the number of buckets of `node`.
+    #[inline]
+    pub fn num_buckets(&self, node: &N) -> usize {
+        self.buckets
+            .iter()
+            .filter(|&&node_index| self.nodes[node_index] == *node)
+            .count()
+    }
+
+    /// Expands `weighted_nodes` into as many buckets as this expansion has,
+    /// as [`VirtualNodes::new`] does, but keeps the nodes of as many buckets
+    /// as possible.
+    ///
+    /// A node keeps its buckets of lowest indices, up to its new number of
+    /// buckets, and the other buckets are given to the nodes that have fewer
+    /// buckets than their new numbers of buckets, in the order of
+    /// `weighted_nodes`. Nodes are matched by equality; nodes that are not in
+    /// `weighted_nodes` lose all their buckets.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlipHashError::EmptyRange`] if there are no nodes, and
+    /// [`FlipHashError::InvalidWeight`] if a weight is not a positive finite
+    /// number.
+    pub fn reweight(
+        &self,
+        weighted_nodes: impl IntoIterator<Item = (N, f64)>,
+    ) -> Result<Self, FlipHashError> {
+        let (nodes, weights) = weighted_nodes.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        let mut num_missing_buckets = num_buckets_per_node(&weights, self.buckets.len())?;
+        let node_indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(node_index, node)| (node, node_index))
+            .collect::<BTreeMap<_, _>>();
+
+        // usize::MAX for the buckets to reassign.
+        let mut buckets = self
+            .buckets
+            .iter()
+            .map(
+                |&previous_node_index| match node_indices.get(&self.nodes[previous_node_index]) {
+                    Some(&node_index) if num_missing_buckets[node_index] > 0 => {
+                        num_missing_buckets[node_index] -= 1;
+                        node_index
+                    }
+                    _ => usize::MAX,
+                },
+            )
+            .collect::<Vec<_>>();
+        let mut node_index = 0;
+        for bucket_node_index in buckets
+            .iter_mut()
+            .filter(|node_index| **node_index == usize::MAX)
+        {
+            while num_missing_buckets[node_index] == 0 {
+                node_index += 1;
+            }
+            num_missing_buckets[node_index] -= 1;
+            *bucket_node_index = node_index;
+        }
+        Ok(VirtualNodes { nodes, buckets })
+    }
+}
+
+/// Splits `num_buckets` among nodes proportionally to their `weights`, with
+/// the largest remainder method.
+fn num_buckets_per_node(weights: &[f64], num_buckets: usize) -> Result<Vec<usize>, FlipHashError> {
+    if weights.is_empty() || num_buckets == 0 {
+        return Err(FlipHashError::EmptyRange);
+    }
+    if !weights
+        .iter()
+        .all(|&weight| weight.is_finite() && weight > 0.0)
+    {
+        return Err(FlipHashError::InvalidWeight);
+    }
+    let total_weight = weights.iter().sum::<f64>();
+    if !total_weight.is_finite() {
+        return Err(FlipHashError::InvalidWeight);
+    }
+    let quotas = weights
+        .iter()
+        .map(|&weight| weight / total_weight * num_buckets as f64)
+        .collect::<Vec<_>>();
+    let mut counts = quotas
+        .iter()
+        .map(|&quota| (quota as usize).min(num_buckets))
+        .collect::<Vec<_>>();
+
+    // The nodes by decreasing remainder, the first ones getting the remaining
+    // buckets. Rounding errors may make the counts add up to more than
+    // `num_buckets`, in which case the last ones give buckets back.
+    let mut node_indices = (0..weights.len()).collect::<Vec<_>>();
+    node_indices.sort_by(|&i, &j| {
+        let remainder = |node_index: usize| quotas[node_index] - counts[node_index] as f64;
+        remainder(j).total_cmp(&remainder(i))
+    });
+    let total_count = counts.iter().sum::<usize>();
+    for &node_index in node_indices
+        .iter()
+        .cycle()
+        .take(num_buckets.saturating_sub(total_count))
+    {
+        counts[node_index] += 1;
+    }
+    let mut num_excess_buckets = total_count.saturating_sub(num_buckets);
+    for &node_index in node_indices.iter().rev().cycle() {
+        if num_excess_buckets == 0 {
+            break;
+        }
+        if counts[node_index] > 0 {
+            counts[node_index] -= 1;
+            num_excess_buckets -= 1;
+        }
+    }
+    Ok(counts)
+}
@@ -0,0 +1,159 @@
+//! Removal of arbitrary buckets of the range, not only of the last ones, as
+//! in [AnchorHash](https://arxiv.org/abs/1812.09674)'s working set.
+//!
+//! Removed buckets are kept in the range as tombstones: keys that are hashed
+//! to a removed bucket are hashed again, with other seeds, until they are
+//! hashed to a working bucket.
+
+use alloc::collections::BTreeSet;
+use core::ops::RangeToInclusive;
+
+use crate::{flip_hash_64_with_seed, replica_seed, FlipHashError};
+
+/// A range of buckets, some of which may be removed, that keys are hashed to
+/// the working buckets of.
+///
+/// Removing a bucket only moves its keys, which are spread uniformly over
+/// the working buckets, and restoring it moves them back. The hashes only
+/// depend on the range and on the set of removed buckets, not on the order
+/// of the removals. Growing the range is monotone, as with
+/// [`crate::flip_hash_64`]: keys only move to the new bucket.
+///
+/// Hashing a key takes `n / w` Flip Hash computations on average, where `n`
+/// is the number of buckets of the range and `w` is the number of working
+/// buckets, so the range should be shrunk, by removing its last buckets
+/// with [`WorkingSet::shrink`], when most buckets are removed.
+///
+/// # Example
+///
+/// ```
+/// use flip_hash::{flip_hash_64, working_set::WorkingSet};
+///
+/// let mut working_set = WorkingSet::new(..=9);
+/// let keys = 0..1000_u64;
+/// let hashes = keys.clone().map(|key| working_set.hash(key)).collect::<Vec<_>>();
+/// assert!(keys.clone().all(|key| working_set.hash(key) == flip_hash_64(key, ..=9)));
+///
+/// working_set.remove(3)?;
+/// for (key, hash) in keys.zip(hashes) {
+///     let new_hash = working_set.hash(key);
+///     assert!(new_hash == hash || (hash == 3 && new_hash != 3));
+/// }
+/// # Ok::<(), flip_hash::FlipHashError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkingSet {
+    range: RangeToInclusive<u64>,
+    removed_buckets: BTreeSet<u64>,
+}
+
+impl WorkingSet {
+    /// Returns the working set of all the buckets of `range`.
+    #[inline]
+    pub fn new(range: RangeToInclusive<u64>) -> Self {
+        WorkingSet {
+            range,
+            removed_buckets: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the range of the buckets, including the removed ones.
+    #[inline]
+    pub fn range(&self) -> RangeToInclusive<u64> {
+        self.range
+    }
+
+    /// Returns whether `bucket` is in the range and not removed.
+    #[inline]
+    pub fn is_working(&self, bucket: u64) -> bool {
+        self.range.contains(&bucket) && !self.removed_buckets.contains(&bucket)
+    }
+
+    /// Returns the number of working buckets.
+    #[inline]
+    pub fn num_working_buckets(&self) -> u128 {
+        self.range.end as u128 + 1 - self.removed_buckets.len() as u128
+    }
+
+    /// Returns the removed buckets, in increasing order.
+    #[inline]
+    pub fn removed_buckets(&self) -> impl Iterator<Item = u64> + '_ {
+        self.removed_buckets.iter().copied()
+    }
+
+    /// Removes `bucket`, and returns whether it was working.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlipHashError::EmptyRange`] if `bucket` is the last working
+    /// bucket.
+    pub fn remove(&mut self, bucket: u64) -> Result<bool, FlipHashError> {
+        if !self.is_working(bucket) {
+            return Ok(false);
+        }
+        if self.num_working_buckets() == 1 {
+            return Err(FlipHashError::EmptyRange);
+        }
+        Ok(self.removed_buckets.insert(bucket))
+    }
+
+    /// Restores `bucket`, and returns whether it was removed.
+    #[inline]
+    pub fn restore(&mut self, bucket: u64) -> bool {
+        self.removed_buckets.remove(&bucket)
+    }
+
+    /// Adds a working bucket at the end of the range, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FlipHashError::RangeTooLarge`] if the range already ends
+    /// with `u64::MAX`.
+    pub fn grow(&mut self) -> Result<u64, FlipHashError> {
+        let bucket = self
+            .range
+            .end
+            .checked_add(1)
+            .ok_or(FlipHashError::RangeTooLarge)?;
+        self.range = ..=bucket;
+        Ok(bucket)
+    }
+
+    /// Removes the last buckets of the range as long as they are removed
+    /// buckets, and returns the number of buckets that are dropped from the
+    /// range.
+    ///
+    /// Only the keys that were hashed to the dropped buckets before being
+    /// hashed again may move, from a working bucket to another one.
+    pub fn shrink(&mut self) -> u64 {
+        let end = self.range.end;
+        while self.removed_buckets.last() == Some(&self.range.end) {
+            self.removed_buckets.pop_last();
+            // There is at least one working bucket.
+            self.range = ..=self.range.end - 1;
+        }
+        end - self.range.end
+    }
+
+    /// Hashes `key` to a working bucket, which is the bucket that
+    /// [`crate::flip_hash_64`] hashes it to if it is working.
+    #[inline]
+    pub fn hash(&self, key: u64) -> u64 {
+        self.hash_with_seed(key, 0)
+    }
+
+    /// Hashes `key` to a working bucket, which is the bucket that
+    /// [`crate::flip_hash_64_with_seed`] hashes it to given `seed` if it is
+    /// working.
+    pub fn hash_with_seed(&self, key: u64, seed: u64) -> u64 {
+        let mut bucket = flip_hash_64_with_seed(key, seed, self.range);
+        let mut attempt_index = 0_u32;
+        while self.removed_buckets.contains(&bucket) {
+            // The attempts use the seeds of the replicas of the key, so that
+            // they are independent of one another.
+            attempt_index = attempt_index.wrapping_add(1);
+            bucket = flip_hash_64_with_seed(key, seed ^ replica_seed(attempt_index), self.range);
+        }
+        bucket
+    }
+}
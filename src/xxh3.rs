@@ -0,0 +1,32 @@
+//! XXH3 hash functions of byte slices.
+//!
+//! Only the inputs that are longer than 240 bytes are hashed with SIMD
+//! instructions. By default, which instructions are used is decided at
+//! compile time, e.g., SSE2 on x86-64 unless AVX2 is enabled with
+//! `-C target-feature=+avx2`. With the `std` feature, the long inputs are
+//! hashed with the best instructions that the CPU supports among AVX2 and SSE2
+//! on x86-64, and NEON on AArch64, which are detected at runtime. Either way,
+//! the hashes are the same.
+
+/// The length of the longest inputs that XXH3 hashes without SIMD
+/// instructions.
+#[cfg(feature = "std")]
+const MAX_SCALAR_INPUT_LEN: usize = 240;
+
+#[inline(always)]
+pub(crate) fn hash_64(key: &[u8], seed: u64) -> u64 {
+    #[cfg(feature = "std")]
+    if key.len() > MAX_SCALAR_INPUT_LEN {
+        return twox_hash::XxHash3_64::oneshot_with_seed(seed, key);
+    }
+    xxhash_rust::xxh3::xxh3_64_with_seed(key, seed)
+}
+
+#[inline(always)]
+pub(crate) fn hash_128(key: &[u8], seed: u64) -> u128 {
+    #[cfg(feature = "std")]
+    if key.len() > MAX_SCALAR_INPUT_LEN {
+        return twox_hash::XxHash3_128::oneshot_with_seed(seed, key);
+    }
+    xxhash_rust::xxh3::xxh3_128_with_seed(key, seed)
+}
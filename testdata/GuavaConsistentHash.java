@@ -0,0 +1,82 @@
+// Generates testdata/guava_consistent_hash.csv, the test vectors of
+// flip_hash::compat::guava_consistent_hash.
+//
+// consistentHash is a copy of Guava's Hashing.consistentHash, without the
+// dependency on Guava's preconditions, so that the vectors can be generated
+// without Guava. main checks that it reproduces the vectors of Guava's
+// HashingTest.testConsistentHash_linearCongruentialGeneratorCompatibility
+// before printing.
+//
+// Usage: java testdata/GuavaConsistentHash.java > testdata/guava_consistent_hash.csv
+
+import java.util.SplittableRandom;
+
+public class GuavaConsistentHash {
+  public static int consistentHash(long input, int buckets) {
+    if (buckets <= 0) {
+      throw new IllegalArgumentException("buckets must be positive: " + buckets);
+    }
+    LinearCongruentialGenerator generator = new LinearCongruentialGenerator(input);
+    int candidate = 0;
+    int next;
+
+    // Jump from bucket to bucket until we go out of range
+    while (true) {
+      next = (int) ((candidate + 1) / generator.nextDouble());
+      if (next >= 0 && next < buckets) {
+        candidate = next;
+      } else {
+        return candidate;
+      }
+    }
+  }
+
+  private static final class LinearCongruentialGenerator {
+    private long state;
+
+    public LinearCongruentialGenerator(long seed) {
+      this.state = seed;
+    }
+
+    public double nextDouble() {
+      state = 2862933555777941757L * state + 1;
+      return ((double) ((int) (state >>> 33) + 1)) / 0x1.0p31;
+    }
+  }
+
+  private static void check(int expected, long input, int buckets) {
+    int actual = consistentHash(input, buckets);
+    if (actual != expected) {
+      throw new AssertionError(input + ", " + buckets + ": " + actual + " != " + expected);
+    }
+  }
+
+  public static void main(String[] args) {
+    int[] golden100 = {0, 55, 62, 8, 45, 59, 86, 97, 82, 59, 73, 37, 17, 56, 86, 21, 90, 37, 38, 83};
+    for (int i = 0; i < golden100.length; i++) {
+      check(golden100[i], i, 100);
+    }
+    check(6, 10863919174838991L, 11);
+    check(3, 2016238256797177309L, 11);
+    check(5, 1673758223894951030L, 11);
+    check(80343, 2, 100001);
+    check(22152, 2201, 100001);
+    check(15018, 2202, 100001);
+
+    // The input is printed as an unsigned integer, as it is passed in Rust.
+    System.out.println("input,buckets,hash");
+    SplittableRandom random = new SplittableRandom(0);
+    int[] bucketCounts = {1, 2, 3, 10, 100, 1000, 65536, 1000000, Integer.MAX_VALUE};
+    for (int buckets : bucketCounts) {
+      for (int i = 0; i < 100; i++) {
+        long input = random.nextLong();
+        System.out.println(Long.toUnsignedString(input) + "," + buckets + "," + consistentHash(input, buckets));
+      }
+    }
+    for (int i = 0; i < 1000; i++) {
+      long input = random.nextLong();
+      int buckets = 1 + random.nextInt(Integer.MAX_VALUE);
+      System.out.println(Long.toUnsignedString(input) + "," + buckets + "," + consistentHash(input, buckets));
+    }
+  }
+}
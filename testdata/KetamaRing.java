@@ -0,0 +1,70 @@
+// Generates testdata/ketama_ring.csv, the test vectors of
+// flip_hash::compat::KetamaRing.
+//
+// The ring is built and queried as spymemcached's KetamaNodeLocator does with
+// its default configuration (KETAMA_HASH, 160 points per server), without the
+// dependency on spymemcached.
+//
+// Usage: java testdata/KetamaRing.java > testdata/ketama_ring.csv
+
+import java.nio.charset.StandardCharsets;
+import java.security.MessageDigest;
+import java.security.NoSuchAlgorithmException;
+import java.util.SortedMap;
+import java.util.TreeMap;
+
+public class KetamaRing {
+  private static final int NUM_REPS = 160;
+
+  private final TreeMap<Long, Integer> ketamaNodes = new TreeMap<>();
+
+  public KetamaRing(String[] servers) {
+    for (int server = 0; server < servers.length; server++) {
+      for (int i = 0; i < NUM_REPS / 4; i++) {
+        byte[] digest = md5(servers[server] + "-" + i);
+        for (int h = 0; h < 4; h++) {
+          ketamaNodes.put(point(digest, h), server);
+        }
+      }
+    }
+  }
+
+  public int getPrimary(String key) {
+    long hash = point(md5(key), 0);
+    SortedMap<Long, Integer> tailMap = ketamaNodes.tailMap(hash);
+    return tailMap.isEmpty() ? ketamaNodes.firstEntry().getValue() : tailMap.get(tailMap.firstKey());
+  }
+
+  private static long point(byte[] digest, int h) {
+    return ((long) (digest[3 + h * 4] & 0xFF) << 24)
+        | ((long) (digest[2 + h * 4] & 0xFF) << 16)
+        | ((long) (digest[1 + h * 4] & 0xFF) << 8)
+        | (digest[h * 4] & 0xFF);
+  }
+
+  private static byte[] md5(String s) {
+    try {
+      return MessageDigest.getInstance("MD5").digest(s.getBytes(StandardCharsets.UTF_8));
+    } catch (NoSuchAlgorithmException e) {
+      throw new RuntimeException(e);
+    }
+  }
+
+  public static void main(String[] args) {
+    // The servers of a ring of n servers are 10.0.0.1:11211, ...,
+    // 10.0.0.n:11211.
+    System.out.println("num_servers,key,server_index");
+    int[] serverCounts = {1, 2, 3, 10, 100, 250};
+    for (int numServers : serverCounts) {
+      String[] servers = new String[numServers];
+      for (int server = 0; server < numServers; server++) {
+        servers[server] = "10.0.0." + (server + 1) + ":11211";
+      }
+      KetamaRing ring = new KetamaRing(servers);
+      for (int i = 0; i < 200; i++) {
+        String key = "key-" + i;
+        System.out.println(numServers + "," + key + "," + ring.getPrimary(key));
+      }
+    }
+  }
+}
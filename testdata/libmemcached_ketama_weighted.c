@@ -0,0 +1,48 @@
+// Generates testdata/libmemcached_ketama_weighted.csv, the test vectors of
+// flip_hash::compat::KetamaRing against libmemcached itself, with
+// MEMCACHED_BEHAVIOR_KETAMA_WEIGHTED and equally weighted servers.
+//
+// The servers are on the default port, which libmemcached leaves out of the
+// names of the points, so the ring of n servers matches
+// KetamaRing::new(["10.0.0.1", ..., "10.0.0.n"]).
+//
+// Usage:
+//   cc testdata/libmemcached_ketama_weighted.c -lmemcached -o ketama_weighted
+//   ./ketama_weighted > testdata/libmemcached_ketama_weighted.csv
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include <libmemcached/memcached.h>
+
+static void check(memcached_return_t rc, const char *what) {
+  if (rc != MEMCACHED_SUCCESS) {
+    fprintf(stderr, "%s: %s\n", what, memcached_strerror(NULL, rc));
+    exit(1);
+  }
+}
+
+int main(void) {
+  const int server_counts[] = {1, 2, 3, 10, 100, 250};
+  printf("num_servers,key,server_index\n");
+  for (size_t c = 0; c < sizeof(server_counts) / sizeof(server_counts[0]); c++) {
+    int num_servers = server_counts[c];
+    memcached_st *memc = memcached_create(NULL);
+    check(memcached_behavior_set(memc, MEMCACHED_BEHAVIOR_KETAMA_WEIGHTED, 1),
+          "memcached_behavior_set");
+    for (int server = 0; server < num_servers; server++) {
+      char host[32];
+      snprintf(host, sizeof(host), "10.0.0.%d", server + 1);
+      check(memcached_server_add(memc, host, 11211), "memcached_server_add");
+    }
+    for (int i = 0; i < 200; i++) {
+      char key[32];
+      snprintf(key, sizeof(key), "key-%d", i);
+      printf("%d,%s,%u\n", num_servers, key,
+             memcached_generate_hash(memc, key, strlen(key)));
+    }
+    memcached_free(memc);
+  }
+  return 0;
+}
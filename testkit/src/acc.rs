@@ -0,0 +1,918 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    mem,
+    ops::RangeInclusive,
+    time::Duration,
+};
+
+use crate::{
+    checkpoint::{invalid_state, read_u64, read_u64s, write_u64, write_u64s},
+    keys::mix,
+};
+
+pub trait Accumulator {
+    type Value;
+    fn record(&mut self, value: Self::Value);
+    fn merge(&mut self, other: &Self);
+    fn num_iterations(&self) -> u64;
+
+    /// Returns the approximate number of bytes of the counts of the
+    /// accumulator, e.g., to see that those of a large cartesian product are
+    /// about to exhaust the memory.
+    fn memory_bytes(&self) -> usize;
+
+    /// Returns the approximate number of bytes of the counts of the
+    /// accumulator once it records `num_iterations` more iterations, e.g., to
+    /// plan a run, which is [`Accumulator::memory_bytes`] unless the counts
+    /// grow with the iterations.
+    fn memory_bytes_after(&self, _num_iterations: u64) -> usize {
+        self.memory_bytes()
+    }
+
+    /// Writes the counts of the accumulator, e.g., to a checkpoint.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()>;
+
+    /// Reads counts that [`Accumulator::write_state`] wrote, replacing those
+    /// of this accumulator, which must have the support of the one that
+    /// wrote them.
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()>;
+
+    /// Returns the nonzero counts of the values that the accumulator counts,
+    /// by index of value, e.g., to compare accumulators, or `None` if it does
+    /// not count values.
+    fn nonzero_counts_by_value(&self) -> Option<Vec<(usize, u64)>> {
+        None
+    }
+}
+
+/// The number of occurrences of each of the values `0..len`.
+///
+/// The counts are dense, unless `len` is at least
+/// [`NumOccurrences::SPARSE_MIN_LEN`], in which case only the nonzero counts
+/// are stored, so that ranges of billions of values fit in memory as long as
+/// the number of keys does.
+pub struct NumOccurrences<V> {
+    len: usize,
+    counts: Counts,
+    value_type: PhantomData<V>,
+}
+
+enum Counts {
+    Dense(Vec<u64>),
+    Sparse(HashMap<usize, u64>),
+}
+
+impl<V> NumOccurrences<V> {
+    pub const SPARSE_MIN_LEN: usize = 1 << 24;
+
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            counts: if len < Self::SPARSE_MIN_LEN {
+                Counts::Dense(vec![0; len])
+            } else {
+                Counts::Sparse(HashMap::new())
+            },
+            value_type: PhantomData,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of occurrences of the value of index `index`.
+    pub fn count(&self, index: usize) -> u64 {
+        assert!(index < self.len);
+        match &self.counts {
+            Counts::Dense(counts) => counts[index],
+            Counts::Sparse(counts) => counts.get(&index).copied().unwrap_or(0),
+        }
+    }
+
+    /// Returns the nonzero numbers of occurrences, in no particular order.
+    pub fn nonzero_counts(&self) -> impl Iterator<Item = u64> + Clone + '_ {
+        let (dense, sparse) = match &self.counts {
+            Counts::Dense(counts) => (Some(counts.iter().copied().filter(|&c| c > 0)), None),
+            Counts::Sparse(counts) => (None, Some(counts.values().copied())),
+        };
+        dense
+            .into_iter()
+            .flatten()
+            .chain(sparse.into_iter().flatten())
+    }
+
+    /// Returns the number of values that did not occur.
+    pub fn num_zero_counts(&self) -> usize {
+        self.len - self.nonzero_counts().count()
+    }
+}
+impl<V> Accumulator for NumOccurrences<V>
+where
+    usize: TryFrom<V>,
+    <usize as TryFrom<V>>::Error: Debug,
+{
+    type Value = V;
+
+    #[inline]
+    fn record(&mut self, value: Self::Value) {
+        let index = usize::try_from(value).unwrap();
+        match &mut self.counts {
+            Counts::Dense(counts) => counts[index] += 1,
+            Counts::Sparse(counts) => {
+                assert!(index < self.len);
+                *counts.entry(index).or_default() += 1;
+            }
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        match (&mut self.counts, &other.counts) {
+            (Counts::Dense(counts), Counts::Dense(other_counts)) => counts
+                .iter_mut()
+                .zip(other_counts.iter())
+                .for_each(|(s, o)| *s += o),
+            (Counts::Sparse(counts), Counts::Sparse(other_counts)) => other_counts
+                .iter()
+                .for_each(|(&index, o)| *counts.entry(index).or_default() += o),
+            _ => panic!("Cannot merge counts of different lengths"),
+        }
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.nonzero_counts().sum::<u64>()
+    }
+
+    /// Returns the bytes of the dense counts, or of the buckets of the sparse
+    /// ones, apart from the control bytes of the hash map.
+    fn memory_bytes(&self) -> usize {
+        match &self.counts {
+            Counts::Dense(counts) => mem::size_of_val(counts.as_slice()),
+            Counts::Sparse(counts) => counts.capacity() * mem::size_of::<(usize, u64)>(),
+        }
+    }
+
+    /// Returns the bytes of the dense counts, or of a bucket per value that
+    /// may occur in the sparse ones.
+    fn memory_bytes_after(&self, num_iterations: u64) -> usize {
+        match &self.counts {
+            Counts::Dense(_) => self.memory_bytes(),
+            Counts::Sparse(counts) => {
+                let num_values = counts
+                    .len()
+                    .saturating_add(num_iterations.try_into().unwrap_or(usize::MAX))
+                    .min(self.len);
+                num_values.saturating_mul(mem::size_of::<(usize, u64)>())
+            }
+        }
+    }
+
+    /// Writes the counts if they are dense, and otherwise the number of
+    /// nonzero counts, then the index and count of each of them.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        match &self.counts {
+            Counts::Dense(counts) => write_u64s(output, counts),
+            Counts::Sparse(counts) => {
+                write_u64(output, counts.len() as u64)?;
+                for (&index, &count) in counts {
+                    write_u64(output, index as u64)?;
+                    write_u64(output, count)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        match &mut self.counts {
+            Counts::Dense(counts) => read_u64s(input, counts),
+            Counts::Sparse(counts) => {
+                counts.clear();
+                for _ in 0..read_u64(input)? {
+                    let index = read_u64(input)? as usize;
+                    if index >= self.len {
+                        return Err(invalid_state("invalid value"));
+                    }
+                    counts.insert(index, read_u64(input)?);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn nonzero_counts_by_value(&self) -> Option<Vec<(usize, u64)>> {
+        Some(match &self.counts {
+            Counts::Dense(counts) => counts
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|&(_, count)| count > 0)
+                .collect(),
+            Counts::Sparse(counts) => counts
+                .iter()
+                .map(|(&index, &count)| (index, count))
+                .collect(),
+        })
+    }
+}
+
+/// The number of co-occurrences of each tuple of values of the cartesian
+/// product of intervals, which are the dimensions.
+///
+/// Tuples are packed into single indices, in mixed radix, so that recording
+/// one does not allocate, and the counts are dense.
+pub struct NumCooccurrences {
+    dimensions: Vec<RangeInclusive<u64>>,
+    counts: Vec<u64>,
+}
+impl NumCooccurrences {
+    /// # Panics
+    ///
+    /// Panics if the number of tuples overflows `usize`.
+    pub fn new(dimensions: Vec<RangeInclusive<u64>>) -> Self {
+        let len = dimensions
+            .iter()
+            .map(|dimension| usize::try_from(dimension.end() - dimension.start() + 1).unwrap())
+            .try_fold(1_usize, usize::checked_mul)
+            .expect("Too many tuples");
+        Self {
+            dimensions,
+            counts: vec![0; len],
+        }
+    }
+
+    /// Returns the number of tuples of `dimensions`, as a float, as it may
+    /// overflow `usize`, e.g., to see that their counts cannot be allocated.
+    pub fn num_tuples(dimensions: &[RangeInclusive<u64>]) -> f64 {
+        dimensions
+            .iter()
+            .map(|dimension| (dimension.end() - dimension.start()) as f64 + 1.0)
+            .product()
+    }
+
+    pub fn dimensions(&self) -> &[RangeInclusive<u64>] {
+        &self.dimensions
+    }
+
+    /// Returns the counts, by index of tuple.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Returns the index of `tuple`, or `None` if one of its values is not
+    /// in its dimension, in which case the values after it are not consumed.
+    #[inline]
+    pub fn index(&self, tuple: impl IntoIterator<Item = u64>) -> Option<usize> {
+        let mut index = 0;
+        let mut stride = 1;
+        let mut dimensions = self.dimensions.iter();
+        for value in tuple {
+            let dimension = dimensions.next()?;
+            if !dimension.contains(&value) {
+                return None;
+            }
+            index += (value - dimension.start()) as usize * stride;
+            stride *= (dimension.end() - dimension.start() + 1) as usize;
+        }
+        dimensions.next().is_none().then_some(index)
+    }
+
+    /// Returns the offsets from the starts of the dimensions of the values of
+    /// the tuple of index `index`.
+    pub fn offsets(&self, mut index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.dimensions.iter().map(move |dimension| {
+            let len = (dimension.end() - dimension.start() + 1) as usize;
+            let offset = index % len;
+            index /= len;
+            offset
+        })
+    }
+}
+
+impl Accumulator for NumCooccurrences {
+    /// The index of a tuple, see [`NumCooccurrences::index`].
+    type Value = usize;
+
+    #[inline]
+    fn record(&mut self, index: Self::Value) {
+        self.counts[index] += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.counts
+            .iter_mut()
+            .zip(&other.counts)
+            .for_each(|(s, o)| *s += o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.counts.iter().sum::<u64>()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of_val(self.counts.as_slice())
+    }
+
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64s(output, &self.counts)
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        read_u64s(input, &mut self.counts)
+    }
+
+    /// Returns the nonzero counts by index of tuple.
+    fn nonzero_counts_by_value(&self) -> Option<Vec<(usize, u64)>> {
+        Some(
+            self.counts
+                .iter()
+                .copied()
+                .enumerate()
+                .filter(|&(_, count)| count > 0)
+                .collect(),
+        )
+    }
+}
+
+/// A sketch of the numbers of occurrences of values, from which the sum of
+/// their squares, the second frequency moment, is estimated in constant
+/// memory, as by Alon, Matias and Szegedy.
+///
+/// Each row adds every value, with a random sign, to one of its `width`
+/// counters, so that the sum of the squares of the counters of a row is an
+/// unbiased estimate of the second moment, with a variance of at most
+/// `2 * F2^2 / width`, and the rows are independent estimates. The hash
+/// functions of the rows are fixed, so that sketches of different threads
+/// and checkpoints can be merged.
+pub struct SecondMomentSketch {
+    width: usize,
+    /// The salt of the hash function of each row.
+    salts: Vec<u64>,
+    /// The counters of the rows, one row after the other.
+    counters: Vec<i64>,
+    num_records: u64,
+}
+impl SecondMomentSketch {
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0);
+        Self {
+            width,
+            salts: (1..=depth as u64).map(mix).collect(),
+            counters: vec![0; width * depth],
+            num_records: 0,
+        }
+    }
+
+    /// Returns the estimate of the second moment of each row.
+    pub fn row_estimates(&self) -> impl Iterator<Item = f64> + '_ {
+        self.counters
+            .chunks(self.width)
+            .map(|row| row.iter().map(|&c| (c as f64).powi(2)).sum())
+    }
+}
+impl Accumulator for SecondMomentSketch {
+    /// A value of up to 128 bits, e.g., a hash to a range beyond 64 bits.
+    type Value = u128;
+
+    #[inline]
+    fn record(&mut self, value: Self::Value) {
+        self.num_records += 1;
+        // The high bits are folded into the low ones, which values of 64
+        // bits keep, as `mix(0)` is 0.
+        let value = value as u64 ^ mix((value >> 64) as u64);
+        for (row, salt) in self.counters.chunks_mut(self.width).zip(&self.salts) {
+            let hash = mix(value ^ salt);
+            // The high bits pick the counter and the low bit the sign.
+            let index = ((hash as u128 * self.width as u128) >> 64) as usize;
+            row[index] += if hash & 1 == 0 { 1 } else { -1 };
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        assert_eq!(self.counters.len(), other.counters.len());
+        self.num_records += other.num_records;
+        self.counters
+            .iter_mut()
+            .zip(&other.counters)
+            .for_each(|(s, o)| *s += o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.num_records
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of_val(self.salts.as_slice()) + mem::size_of_val(self.counters.as_slice())
+    }
+
+    /// Writes the number of records, then the counters, as two's complement.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64(output, self.num_records)?;
+        write_u64s(
+            output,
+            &self
+                .counters
+                .iter()
+                .map(|&counter| counter as u64)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        self.num_records = read_u64(input)?;
+        let mut counters = vec![0; self.counters.len()];
+        read_u64s(input, &mut counters)?;
+        self.counters = counters.into_iter().map(|counter| counter as i64).collect();
+        Ok(())
+    }
+}
+
+/// The numbers of keys hashed and the time spent hashing them, for each of a
+/// number of configurations, and likewise for the builds of the states of
+/// algorithms, e.g., their lookup tables.
+pub struct Durations {
+    num_keys: Vec<u64>,
+    elapsed: Vec<Duration>,
+    num_builds: Vec<u64>,
+    build_elapsed: Vec<Duration>,
+}
+impl Durations {
+    pub fn new(len: usize) -> Self {
+        Self {
+            num_keys: vec![0; len],
+            elapsed: vec![Duration::ZERO; len],
+            num_builds: vec![0; len],
+            build_elapsed: vec![Duration::ZERO; len],
+        }
+    }
+
+    pub fn num_keys(&self) -> &[u64] {
+        &self.num_keys
+    }
+
+    pub fn elapsed(&self) -> &[Duration] {
+        &self.elapsed
+    }
+
+    pub fn num_builds(&self) -> &[u64] {
+        &self.num_builds
+    }
+
+    pub fn build_elapsed(&self) -> &[Duration] {
+        &self.build_elapsed
+    }
+
+    /// Records a build of the state of the algorithm for a configuration,
+    /// which took `elapsed`.
+    pub fn record_build(&mut self, index: usize, elapsed: Duration) {
+        self.num_builds[index] += 1;
+        self.build_elapsed[index] += elapsed;
+    }
+}
+impl Accumulator for Durations {
+    /// The index of the configuration, the number of keys and the time spent
+    /// hashing them.
+    type Value = (usize, u64, Duration);
+
+    #[inline]
+    fn record(&mut self, (index, num_keys, elapsed): Self::Value) {
+        self.num_keys[index] += num_keys;
+        self.elapsed[index] += elapsed;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.num_keys
+            .iter_mut()
+            .zip(&other.num_keys)
+            .for_each(|(s, o)| *s += o);
+        self.elapsed
+            .iter_mut()
+            .zip(&other.elapsed)
+            .for_each(|(s, o)| *s += *o);
+        self.num_builds
+            .iter_mut()
+            .zip(&other.num_builds)
+            .for_each(|(s, o)| *s += o);
+        self.build_elapsed
+            .iter_mut()
+            .zip(&other.build_elapsed)
+            .for_each(|(s, o)| *s += *o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.num_keys.iter().sum::<u64>()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of_val(self.num_keys.as_slice())
+            + mem::size_of_val(self.elapsed.as_slice())
+            + mem::size_of_val(self.num_builds.as_slice())
+            + mem::size_of_val(self.build_elapsed.as_slice())
+    }
+
+    /// Writes the numbers of keys, then the elapsed times in nanoseconds, and
+    /// likewise for the builds.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64s(output, &self.num_keys)?;
+        write_u64s(output, &nanos(&self.elapsed))?;
+        write_u64s(output, &self.num_builds)?;
+        write_u64s(output, &nanos(&self.build_elapsed))
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        read_u64s(input, &mut self.num_keys)?;
+        read_durations(input, &mut self.elapsed)?;
+        read_u64s(input, &mut self.num_builds)?;
+        read_durations(input, &mut self.build_elapsed)
+    }
+}
+
+/// The [`Durations`] of a number of configurations, with the histograms of
+/// the times of the batches of keys, whose buckets are logarithmic, so that
+/// their quantiles are estimated within 1/16 of their values, e.g., to report
+/// percentiles of latencies.
+pub struct Latencies {
+    durations: Durations,
+    /// The counts of the buckets of the histograms, configuration after
+    /// configuration.
+    counts: Vec<u64>,
+}
+impl Latencies {
+    /// The number of buckets of a histogram: the times below 8 nanoseconds
+    /// have their own buckets, and each power of two above has 8.
+    const NUM_BUCKETS: usize = 8 * 62;
+
+    pub fn new(len: usize) -> Self {
+        Self {
+            durations: Durations::new(len),
+            counts: vec![0; len * Self::NUM_BUCKETS],
+        }
+    }
+
+    pub fn durations(&self) -> &Durations {
+        &self.durations
+    }
+
+    /// Records a build of the state of the algorithm for a configuration,
+    /// which took `elapsed`.
+    pub fn record_build(&mut self, index: usize, elapsed: Duration) {
+        self.durations.record_build(index, elapsed);
+    }
+
+    /// Returns the estimate of the `quantile` of the times of the batches of
+    /// a configuration, in nanoseconds, or `None` if none was recorded.
+    pub fn quantile_nanos(&self, index: usize, quantile: f64) -> Option<f64> {
+        let counts = &self.counts[index * Self::NUM_BUCKETS..][..Self::NUM_BUCKETS];
+        let num_batches = counts.iter().sum::<u64>();
+        if num_batches == 0 {
+            return None;
+        }
+        // The rank of the quantile, from 1 to the number of batches.
+        let rank = ((quantile * num_batches as f64).ceil() as u64).clamp(1, num_batches);
+        let mut num_below = 0;
+        let bucket = counts
+            .iter()
+            .position(|&count| {
+                num_below += count;
+                num_below >= rank
+            })
+            .unwrap();
+        // The middle of the bucket.
+        Some((Self::bucket_min(bucket) + Self::bucket_min(bucket + 1)) / 2.0)
+    }
+
+    fn bucket(nanos: u64) -> usize {
+        if nanos < 8 {
+            return nanos as usize;
+        }
+        let exponent = nanos.ilog2();
+        let mantissa = (nanos >> (exponent - 3)) & 7;
+        8 * (exponent as usize - 2) + mantissa as usize
+    }
+
+    /// Returns the smallest time of a bucket, as a float, as that of the
+    /// bucket after the last one is 2^64.
+    fn bucket_min(bucket: usize) -> f64 {
+        if bucket < 8 {
+            return bucket as f64;
+        }
+        (8 + bucket % 8) as f64 * 2_f64.powi(bucket as i32 / 8 - 1)
+    }
+}
+impl Accumulator for Latencies {
+    /// The index of the configuration, the number of keys of the batch and
+    /// the time spent hashing them.
+    type Value = (usize, u64, Duration);
+
+    #[inline]
+    fn record(&mut self, (index, num_keys, elapsed): Self::Value) {
+        self.durations.record((index, num_keys, elapsed));
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[index * Self::NUM_BUCKETS + Self::bucket(nanos)] += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.durations.merge(&other.durations);
+        self.counts
+            .iter_mut()
+            .zip(&other.counts)
+            .for_each(|(s, o)| *s += o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.durations.num_iterations()
+    }
+
+    fn memory_bytes(&self) -> usize {
+        self.durations.memory_bytes() + mem::size_of_val(self.counts.as_slice())
+    }
+
+    /// Writes the durations, then the counts of the histograms.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        self.durations.write_state(output)?;
+        write_u64s(output, &self.counts)
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        self.durations.read_state(input)?;
+        read_u64s(input, &mut self.counts)
+    }
+}
+
+fn nanos(durations: &[Duration]) -> Vec<u64> {
+    durations
+        .iter()
+        .map(|duration| duration.as_nanos() as u64)
+        .collect()
+}
+
+fn read_durations(input: &mut impl Read, durations: &mut [Duration]) -> io::Result<()> {
+    let mut nanos = vec![0; durations.len()];
+    read_u64s(input, &mut nanos)?;
+    for (duration, nanos) in durations.iter_mut().zip(nanos) {
+        *duration = Duration::from_nanos(nanos);
+    }
+    Ok(())
+}
+
+/// The numbers of successes of a fixed number of Bernoulli trials, which are
+/// recorded together, e.g., whether the hash changes when flipping each bit
+/// of a key.
+pub struct NumSuccesses {
+    num_records: u64,
+    counts: Vec<u64>,
+}
+impl NumSuccesses {
+    pub fn new(len: usize) -> Self {
+        Self {
+            num_records: 0,
+            counts: vec![0; len],
+        }
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Records the outcomes of the trials, as [`Accumulator::record`] does,
+    /// without collecting them first.
+    #[inline]
+    pub fn record_successes(&mut self, successes: impl IntoIterator<Item = bool>) {
+        self.num_records += 1;
+        self.counts
+            .iter_mut()
+            .zip(successes)
+            .for_each(|(c, success)| *c += u64::from(success));
+    }
+}
+impl Accumulator for NumSuccesses {
+    type Value = Vec<bool>;
+
+    #[inline]
+    fn record(&mut self, value: Self::Value) {
+        self.record_successes(value);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.num_records += other.num_records;
+        self.counts
+            .iter_mut()
+            .zip(&other.counts)
+            .for_each(|(s, o)| *s += o);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.num_records
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of_val(self.counts.as_slice())
+    }
+
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64(output, self.num_records)?;
+        write_u64s(output, &self.counts)
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        self.num_records = read_u64(input)?;
+        read_u64s(input, &mut self.counts)
+    }
+}
+
+/// The mean, variance, minimum and maximum of a stream of observations, which
+/// are updated with Welford's algorithm, and merged with that of Chan et al.,
+/// so that the observations are not stored.
+///
+/// The loads of the values of a range, e.g., of `max-load` and range sweeps,
+/// are only known once all the keys are counted, so that they still need the
+/// counts of the values, which these moments summarize without copying or
+/// sorting them.
+#[derive(Clone, Copy, Debug)]
+pub struct Moments {
+    num_observations: u64,
+    mean: f64,
+    /// The sum of the squares of the differences from the mean.
+    sum_squared_deviations: f64,
+    min: f64,
+    max: f64,
+}
+impl Moments {
+    pub fn new() -> Self {
+        Self {
+            num_observations: 0,
+            mean: 0.0,
+            sum_squared_deviations: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Records `count` observations of `value` at once, e.g., the loads of
+    /// the values that did not occur, which sparse counts do not store.
+    pub fn record_repeated(&mut self, value: f64, count: u64) {
+        if count > 0 {
+            self.merge(&Self {
+                num_observations: count,
+                mean: value,
+                sum_squared_deviations: 0.0,
+                min: value,
+                max: value,
+            });
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the variance of the observations, as a population, e.g., of
+    /// all the values of a range, rather than as a sample of one.
+    pub fn variance(&self) -> f64 {
+        self.sum_squared_deviations / self.num_observations as f64
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Returns the smallest observation, which is infinite if there are none.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Returns the largest observation, which is minus infinity if there are
+    /// none.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+}
+impl Default for Moments {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Accumulator for Moments {
+    type Value = f64;
+
+    #[inline]
+    fn record(&mut self, value: Self::Value) {
+        self.num_observations += 1;
+        let deviation = value - self.mean;
+        self.mean += deviation / self.num_observations as f64;
+        self.sum_squared_deviations += deviation * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        let num_observations = self.num_observations + other.num_observations;
+        if num_observations == 0 {
+            return;
+        }
+        let deviation = other.mean - self.mean;
+        let other_weight = other.num_observations as f64 / num_observations as f64;
+        self.mean += deviation * other_weight;
+        self.sum_squared_deviations += other.sum_squared_deviations
+            + deviation.powi(2) * self.num_observations as f64 * other_weight;
+        self.num_observations = num_observations;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    fn num_iterations(&self) -> u64 {
+        self.num_observations
+    }
+
+    fn memory_bytes(&self) -> usize {
+        mem::size_of::<Self>()
+    }
+
+    /// Writes the number of observations, then the moments, as the bits of
+    /// their floats.
+    fn write_state(&self, output: &mut impl Write) -> io::Result<()> {
+        write_u64(output, self.num_observations)?;
+        write_u64s(
+            output,
+            &[self.mean, self.sum_squared_deviations, self.min, self.max].map(f64::to_bits),
+        )
+    }
+
+    fn read_state(&mut self, input: &mut impl Read) -> io::Result<()> {
+        self.num_observations = read_u64(input)?;
+        let mut bits = [0; 4];
+        read_u64s(input, &mut bits)?;
+        [self.mean, self.sum_squared_deviations, self.min, self.max] = bits.map(f64::from_bits);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [f64; 8] = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+    fn moments(values: &[f64]) -> Moments {
+        let mut moments = Moments::new();
+        values.iter().for_each(|&value| moments.record(value));
+        moments
+    }
+
+    fn assert_same_moments(actual: &Moments, expected: &Moments) {
+        assert_eq!(actual.num_iterations(), expected.num_iterations());
+        assert!((actual.mean() - expected.mean()).abs() < 1e-12);
+        assert!((actual.variance() - expected.variance()).abs() < 1e-12);
+        assert_eq!(actual.min(), expected.min());
+        assert_eq!(actual.max(), expected.max());
+    }
+
+    #[test]
+    fn moments_of_known_values() {
+        let moments = moments(&VALUES);
+        assert_eq!(moments.num_iterations(), 8);
+        assert_eq!(moments.mean(), 5.0);
+        assert_eq!(moments.variance(), 4.0);
+        assert_eq!(moments.std_dev(), 2.0);
+        assert_eq!(moments.min(), 2.0);
+        assert_eq!(moments.max(), 9.0);
+    }
+
+    #[test]
+    fn merged_moments_are_those_of_all_the_values() {
+        let mut merged = moments(&VALUES[..3]);
+        merged.merge(&moments(&VALUES[3..]));
+        assert_same_moments(&merged, &moments(&VALUES));
+
+        let mut merged = Moments::new();
+        merged.merge(&Moments::new());
+        assert_eq!(merged.num_iterations(), 0);
+    }
+
+    #[test]
+    fn repeated_observations_are_recorded_at_once() {
+        let mut repeated = moments(&[2.0, 5.0, 5.0, 7.0, 9.0]);
+        repeated.record_repeated(4.0, 3);
+        repeated.record_repeated(1.0, 0);
+        assert_same_moments(&repeated, &moments(&VALUES));
+    }
+
+    #[test]
+    fn moments_state_round_trip() {
+        let moments = moments(&VALUES);
+        let mut state = Vec::new();
+        moments.write_state(&mut state).unwrap();
+        let mut read = Moments::new();
+        read.read_state(&mut state.as_slice()).unwrap();
+        assert_same_moments(&read, &moments);
+    }
+}
@@ -0,0 +1,31 @@
+use std::{fmt, ops::RangeToInclusive};
+
+/// A ranged hash function, which maps keys to the values of ranges, and whose
+/// name in results is its [`Display`](fmt::Display) output.
+pub trait Algorithm: fmt::Display {
+    /// Returns the value of `range` of `key`, given `seed`, which algorithms
+    /// that do not take seeds may mix into the key.
+    fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64;
+
+    /// Returns the value of `range` of `key`, given `seed`, as
+    /// [`Algorithm::hash`] does, for ranges of up to 128 bits, e.g., to test
+    /// ranges beyond those of 64 bits without truncating them.
+    ///
+    /// # Panics
+    ///
+    /// Unless the algorithm overrides it, panics if the end of `range` does
+    /// not fit in 64 bits.
+    fn hash_128(&self, key: &[u8], seed: u64, range: RangeToInclusive<u128>) -> u128 {
+        let end = u64::try_from(range.end)
+            .unwrap_or_else(|_| panic!("{self} only hashes to ranges of up to 64 bits"));
+        self.hash(key, seed, ..=end).into()
+    }
+
+    /// Builds the state of the algorithm for `seed` and `range`, e.g., its
+    /// ring or its lookup table, which is otherwise built on first use and
+    /// kept, and returns whether it did, i.e., whether the algorithm has such
+    /// a state and it was not built yet.
+    fn build(&self, _seed: u64, _range: RangeToInclusive<u64>) -> bool {
+        false
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::acc::Accumulator;
+
+const MAGIC: &[u8; 8] = b"FHCKPT03";
+
+/// The accumulators of the algorithms of a checkpoint, and the numbers of
+/// steps that were accumulated for each of them, which resumed runs continue
+/// from, so that they do not hash the keys of the same steps again.
+pub struct Checkpoint<A> {
+    pub accumulators: HashMap<String, A>,
+    pub num_steps: HashMap<String, u64>,
+}
+
+/// Writes the accumulators of the algorithms of `experiment`, which
+/// identifies the experiment, e.g., with its arguments, and their numbers of
+/// steps, which are 0 when missing, to `path`, through a temporary file, so
+/// that the previous checkpoint is kept if writing fails midway.
+pub fn write_checkpoint<A: Accumulator>(
+    path: &Path,
+    experiment: &str,
+    accumulators: &HashMap<String, A>,
+    num_steps: &HashMap<String, u64>,
+) -> io::Result<()> {
+    let temporary_path = path.with_extension("tmp");
+    let mut output = BufWriter::new(File::create(&temporary_path)?);
+    output.write_all(MAGIC)?;
+    write_u64(&mut output, experiment.len() as u64)?;
+    output.write_all(experiment.as_bytes())?;
+    write_u64(&mut output, accumulators.len() as u64)?;
+    for (algo, accumulator) in accumulators {
+        write_u64(&mut output, algo.len() as u64)?;
+        output.write_all(algo.as_bytes())?;
+        write_u64(&mut output, num_steps.get(algo).copied().unwrap_or(0))?;
+        accumulator.write_state(&mut output)?;
+    }
+    output.into_inner()?.sync_all()?;
+    fs::rename(temporary_path, path)
+}
+
+/// Reads the checkpoint that [`write_checkpoint`] wrote to `path`, given a
+/// function that returns empty accumulators of the same experiment, which
+/// must be `experiment`, so that the counts of other experiments, e.g., of
+/// other ranges, are not read as those of this one.
+pub fn read_checkpoint<A: Accumulator>(
+    path: &Path,
+    experiment: &str,
+    new_accumulator: impl Fn() -> A,
+) -> io::Result<Checkpoint<A>> {
+    let mut input = BufReader::new(File::open(path)?);
+    let mut magic = [0; MAGIC.len()];
+    input.read_exact(&mut magic)?;
+    if magic != *MAGIC {
+        return Err(invalid_state("not a checkpoint"));
+    }
+    let mut checkpoint_experiment = vec![0; read_u64(&mut input)? as usize];
+    input.read_exact(&mut checkpoint_experiment)?;
+    if checkpoint_experiment != experiment.as_bytes() {
+        return Err(invalid_state(&format!(
+            "of another experiment, {}",
+            String::from_utf8_lossy(&checkpoint_experiment)
+        )));
+    }
+    let mut accumulators = HashMap::new();
+    let mut num_steps = HashMap::new();
+    for _ in 0..read_u64(&mut input)? {
+        let mut algo = vec![0; read_u64(&mut input)? as usize];
+        input.read_exact(&mut algo)?;
+        let algo = String::from_utf8(algo).map_err(|_| invalid_state("invalid algorithm"))?;
+        num_steps.insert(algo.clone(), read_u64(&mut input)?);
+        let mut accumulator = new_accumulator();
+        accumulator.read_state(&mut input)?;
+        accumulators.insert(algo, accumulator);
+    }
+    if input.read(&mut [0])? > 0 {
+        return Err(invalid_state("trailing bytes"));
+    }
+    Ok(Checkpoint {
+        accumulators,
+        num_steps,
+    })
+}
+
+pub fn write_u64(output: &mut impl Write, value: u64) -> io::Result<()> {
+    output.write_all(&value.to_le_bytes())
+}
+
+pub fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Writes the length of `values`, then `values`.
+pub fn write_u64s(output: &mut impl Write, values: &[u64]) -> io::Result<()> {
+    write_u64(output, values.len() as u64)?;
+    values
+        .iter()
+        .try_for_each(|&value| write_u64(output, value))
+}
+
+/// Reads values that [`write_u64s`] wrote, which must be as many as
+/// `values`.
+pub fn read_u64s(input: &mut impl Read, values: &mut [u64]) -> io::Result<()> {
+    if read_u64(input)? != values.len() as u64 {
+        return Err(invalid_state("the number of counts differs"));
+    }
+    for value in values {
+        *value = read_u64(input)?;
+    }
+    Ok(())
+}
+
+pub fn invalid_state(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid checkpoint: {reason}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::acc::NumOccurrences;
+
+    const EXPERIMENT: &str = "Regularity { range_end: 3 }";
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let path = env::temp_dir().join(format!(
+            "flip_hash_testkit_checkpoint_{}.bin",
+            std::process::id()
+        ));
+        let mut accumulators = HashMap::new();
+        for (algo, values) in [("flip", vec![0, 1, 1, 3]), ("jump", vec![2])] {
+            let mut accumulator = NumOccurrences::<u64>::new(4);
+            values
+                .into_iter()
+                .for_each(|value| accumulator.record(value));
+            accumulators.insert(algo.to_string(), accumulator);
+        }
+        let num_steps = HashMap::from([("flip".to_string(), 3)]);
+        write_checkpoint(&path, EXPERIMENT, &accumulators, &num_steps).unwrap();
+
+        let checkpoint = read_checkpoint(&path, EXPERIMENT, || NumOccurrences::<u64>::new(4));
+        let other_checkpoint = read_checkpoint(&path, "Regularity { range_end: 4 }", || {
+            NumOccurrences::<u64>::new(5)
+        });
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            other_checkpoint.err().unwrap().to_string(),
+            format!("Invalid checkpoint: of another experiment, {EXPERIMENT}")
+        );
+        let checkpoint = checkpoint.unwrap();
+        assert_eq!(checkpoint.accumulators.len(), 2);
+        for (algo, counts) in [("flip", [1, 2, 0, 1]), ("jump", [0, 0, 1, 0])] {
+            let accumulator = &checkpoint.accumulators[algo];
+            assert_eq!(
+                (0..4)
+                    .map(|index| accumulator.count(index))
+                    .collect::<Vec<_>>(),
+                counts
+            );
+        }
+        // The missing numbers of steps are 0.
+        assert_eq!(
+            checkpoint.num_steps,
+            HashMap::from([("flip".to_string(), 3), ("jump".to_string(), 0)])
+        );
+    }
+
+    #[test]
+    fn other_files_are_not_checkpoints() {
+        let path = env::temp_dir().join(format!(
+            "flip_hash_testkit_not_a_checkpoint_{}.bin",
+            std::process::id()
+        ));
+        fs::write(&path, b"FHCKPT01").unwrap();
+        let checkpoint = read_checkpoint(&path, EXPERIMENT, || NumOccurrences::<u64>::new(4));
+        fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,2533 @@
+use std::{
+    hint::black_box,
+    iter, mem,
+    ops::{RangeInclusive, RangeToInclusive},
+    time::Instant,
+};
+
+use itertools::Itertools;
+use rand::{distributions::Standard, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use rand_distr::{Binomial, Distribution};
+use serde::Serialize;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+use crate::{
+    acc::{
+        Accumulator, Durations, Latencies, Moments, NumCooccurrences, NumOccurrences, NumSuccesses,
+        SecondMomentSketch,
+    },
+    algo::Algorithm,
+    keys::Keys,
+    rng::thread_rng,
+};
+
+pub trait Experiment {
+    type Accumulator: Accumulator;
+    /// The summary of an accumulator, whose fields are written to a line of
+    /// JSON.
+    type Summary: Serialize;
+    /// The buffers that the runs reuse, e.g., of the bytes of keys, so that
+    /// iterations do not allocate.
+    type Scratch;
+
+    fn new_accumulator(&self) -> Self::Accumulator;
+
+    fn new_scratch(&self) -> Self::Scratch;
+
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        scratch: &mut Self::Scratch,
+    );
+
+    fn accumulate(&self, algorithm: &impl Algorithm, num_iterations: u64) -> Self::Accumulator {
+        let mut accumulator = self.new_accumulator();
+        let mut scratch = self.new_scratch();
+        for _ in 0..num_iterations {
+            self.run(&mut accumulator, algorithm, &mut scratch);
+        }
+        accumulator
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary;
+
+    /// Returns the approximate number of bytes of an accumulator of
+    /// `num_iterations` iterations, as a float, without allocating it if it
+    /// may not fit in memory, e.g., to plan a run.
+    fn accumulator_memory_bytes(&self, num_iterations: u64) -> f64 {
+        self.new_accumulator().memory_bytes_after(num_iterations) as f64
+    }
+
+    /// Returns the cells of the chi-squared test of the summaries, e.g., to
+    /// plan the number of keys that it needs, or `None` if they have none.
+    fn test_cells(&self) -> Option<TestCells> {
+        None
+    }
+}
+
+/// The cells of the counts of a chi-squared test, e.g., the tuples of the
+/// cartesian product of the ranges of an independence test.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct TestCells {
+    #[serde(rename = "num cells")]
+    pub num_cells: f64,
+    #[serde(rename = "degrees of freedom")]
+    pub degrees_of_freedom: f64,
+}
+
+impl TestCells {
+    /// Returns the cells of the test of mutual independence of counts of
+    /// co-occurrences of `dimensions`.
+    fn mutual_independence(dimensions: &[RangeInclusive<u64>]) -> Self {
+        let num_cells = NumCooccurrences::num_tuples(dimensions);
+        let marginal_degrees_of_freedom = dimensions
+            .iter()
+            .map(|dimension| (dimension.end() - dimension.start()) as f64)
+            .sum::<f64>();
+        Self {
+            num_cells,
+            degrees_of_freedom: num_cells - 1.0 - marginal_degrees_of_freedom,
+        }
+    }
+}
+
+/// The smallest expected count of a cell for the chi-squared distribution to
+/// approximate the statistics of the tests well. Consecutive values whose
+/// expected counts are below it are merged into cells, e.g., early in a run.
+pub const MIN_EXPECTED_COUNT: f64 = 5.0;
+
+/// The tests of goodness of fit of the uniformity and independence
+/// experiments.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GoodnessOfFitTest {
+    /// Pearson's chi-squared test, whose p-value is `p-value`.
+    #[default]
+    Chi2,
+    /// The G-test, i.e., the likelihood-ratio test, whose p-value is `g
+    /// p-value`, and which is more accurate when expected counts are small.
+    G,
+    /// Both tests, to cross-check their conclusions.
+    Both,
+}
+
+impl GoodnessOfFitTest {
+    /// Returns the p-values of the chi-squared test and of the G-test, if
+    /// they are run, given a function that returns the p-value of a test
+    /// from the term of its statistic.
+    fn p_values(self, p_value: impl Fn(fn(f64, f64) -> f64) -> f64) -> (Option<f64>, Option<f64>) {
+        (
+            (self != GoodnessOfFitTest::G).then(|| p_value(chi_squared_term)),
+            (self != GoodnessOfFitTest::Chi2).then(|| p_value(g_term)),
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Regularity {
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+    test: GoodnessOfFitTest,
+    exact_test_num_samples: Option<usize>,
+    bootstrap_num_samples: Option<usize>,
+}
+
+impl Regularity {
+    /// The largest range end of the exact test, whose cost grows with the
+    /// number of values.
+    pub const EXACT_TEST_MAX_RANGE_END: u64 = (1 << 16) - 1;
+
+    /// If `exact_test_num_samples` is set, the summaries also report the
+    /// p-value of the exact multinomial test of uniformity, estimated from
+    /// that number of samples.
+    ///
+    /// If `bootstrap_num_samples` is set, the summaries also report the 95%
+    /// confidence intervals of the distances, from that number of bootstrap
+    /// samples of the counts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the exact test is set and the range end is larger than
+    /// [`Regularity::EXACT_TEST_MAX_RANGE_END`].
+    pub fn new(
+        range: RangeToInclusive<u64>,
+        input_size_bytes: usize,
+        keys: Keys,
+        test: GoodnessOfFitTest,
+        exact_test_num_samples: Option<usize>,
+        bootstrap_num_samples: Option<usize>,
+    ) -> Self {
+        assert!(
+            exact_test_num_samples.is_none() || range.end <= Self::EXACT_TEST_MAX_RANGE_END,
+            "The exact test needs a range end of at most {}",
+            Self::EXACT_TEST_MAX_RANGE_END
+        );
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+            test,
+            exact_test_num_samples,
+            bootstrap_num_samples,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RegularitySummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "l1 distance")]
+    l1_distance: f64,
+    #[serde(rename = "l2 distance")]
+    l2_distance: f64,
+    /// The bounds of the 95% confidence intervals of the distances, from
+    /// bootstrap samples of the counts.
+    #[serde(rename = "l1 distance ci low", skip_serializing_if = "Option::is_none")]
+    l1_distance_ci_low: Option<f64>,
+    #[serde(
+        rename = "l1 distance ci high",
+        skip_serializing_if = "Option::is_none"
+    )]
+    l1_distance_ci_high: Option<f64>,
+    #[serde(rename = "l2 distance ci low", skip_serializing_if = "Option::is_none")]
+    l2_distance_ci_low: Option<f64>,
+    #[serde(
+        rename = "l2 distance ci high",
+        skip_serializing_if = "Option::is_none"
+    )]
+    l2_distance_ci_high: Option<f64>,
+    #[serde(rename = "p-value", skip_serializing_if = "Option::is_none")]
+    p_value: Option<f64>,
+    #[serde(rename = "g p-value", skip_serializing_if = "Option::is_none")]
+    g_p_value: Option<f64>,
+    /// The number of cells of the tests, if the values were merged into
+    /// fewer, as their expected counts are below [`MIN_EXPECTED_COUNT`], in
+    /// which case the p-values are only reported if at least two are left.
+    #[serde(rename = "merged cells", skip_serializing_if = "Option::is_none")]
+    merged_cells: Option<usize>,
+    /// The p-value of the exact multinomial test, which is sound for small
+    /// ranges, unlike the chi-squared approximation.
+    #[serde(rename = "exact p-value", skip_serializing_if = "Option::is_none")]
+    exact_p_value: Option<f64>,
+    /// The Kolmogorov-Smirnov statistic of the distribution of the values,
+    /// in order, which is sensitive to deviations that span many neighboring
+    /// values, e.g., a trend across the range, to which the chi-squared test
+    /// is not.
+    #[serde(rename = "ks statistic")]
+    ks_statistic: f64,
+    #[serde(rename = "ks p-value", skip_serializing_if = "Option::is_none")]
+    ks_p_value: Option<f64>,
+}
+
+impl Experiment for Regularity {
+    type Accumulator = NumOccurrences<u64>;
+    type Summary = RegularitySummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumOccurrences::new(
+            usize::try_from(self.range.end)
+                .unwrap()
+                .checked_add(1)
+                .unwrap(),
+        )
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash(bytes, 0, self.range);
+        accumulator.record(hash);
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let num_zero_counts = accumulator.num_zero_counts();
+        let (l1_distance, l2_distance) = uniformity_distances(
+            accumulator.nonzero_counts(),
+            num_zero_counts,
+            accumulator.len(),
+        );
+        let [l1_distance_ci, l2_distance_ci] = match self.bootstrap_num_samples {
+            Some(num_samples) => {
+                let nonzero_counts = accumulator.nonzero_counts().collect::<Vec<_>>();
+                bootstrap_uniformity_distances_cis(
+                    &nonzero_counts,
+                    num_zero_counts,
+                    accumulator.len(),
+                    num_samples,
+                )
+                .map(Some)
+            }
+            None => [None; 2],
+        };
+        let nonzero_counts_by_value = accumulator.nonzero_counts_by_value().unwrap();
+        let num_cells = uniformity_test_num_cells(num_keys, accumulator.len());
+        let (p_value, g_p_value) = if num_cells > 1 {
+            self.test.p_values(|term| {
+                uniformity_test_p_value(
+                    &nonzero_counts_by_value,
+                    accumulator.len(),
+                    num_cells,
+                    term,
+                )
+            })
+        } else {
+            (None, None)
+        };
+        let exact_p_value = self.exact_test_num_samples.map(|num_samples| {
+            let counts = (0..accumulator.len())
+                .map(|index| accumulator.count(index))
+                .collect::<Vec<_>>();
+            monte_carlo_multinomial_uniformity_test_p_value(&counts, num_samples)
+        });
+        let ks_statistic =
+            kolmogorov_smirnov_uniformity_statistic(nonzero_counts_by_value, accumulator.len());
+        RegularitySummary {
+            num_keys,
+            l1_distance,
+            l2_distance,
+            l1_distance_ci_low: l1_distance_ci.map(|(low, _)| low),
+            l1_distance_ci_high: l1_distance_ci.map(|(_, high)| high),
+            l2_distance_ci_low: l2_distance_ci.map(|(low, _)| low),
+            l2_distance_ci_high: l2_distance_ci.map(|(_, high)| high),
+            p_value,
+            g_p_value,
+            merged_cells: (num_cells < accumulator.len()).then_some(num_cells),
+            exact_p_value,
+            ks_statistic,
+            ks_p_value: kolmogorov_smirnov_p_value(ks_statistic, num_keys),
+        }
+    }
+
+    fn test_cells(&self) -> Option<TestCells> {
+        let num_cells = self.range.end as f64 + 1.0;
+        Some(TestCells {
+            num_cells,
+            degrees_of_freedom: num_cells - 1.0,
+        })
+    }
+}
+
+/// Measures the largest number of keys of a value, and its ratio to the
+/// mean, as the number of keys grows, as in balls-into-bins problems.
+///
+/// For uniformly random hashes, the peak-to-mean ratio is about `1 +
+/// sqrt(2 * ln(n) / m)` for `n` values and a mean of `m` keys per value when
+/// `m` is much larger than `ln(n)`.
+///
+/// The accumulator counts the keys of every value, as the load of a value is
+/// only known once all the keys are hashed, and the summary streams the loads
+/// into [`Moments`].
+#[derive(Clone, Debug)]
+pub struct MaxLoad {
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl MaxLoad {
+    pub fn new(range: RangeToInclusive<u64>, input_size_bytes: usize, keys: Keys) -> Self {
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MaxLoadSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "max load")]
+    max_load: u64,
+    #[serde(rename = "min load")]
+    min_load: u64,
+    #[serde(rename = "mean load")]
+    mean_load: f64,
+    /// The standard deviation of the loads of the values, and that of
+    /// uniformly random hashes, whose loads are binomial.
+    #[serde(rename = "load std dev")]
+    load_std_dev: f64,
+    #[serde(rename = "expected load std dev")]
+    expected_load_std_dev: f64,
+    #[serde(rename = "peak to mean")]
+    peak_to_mean: f64,
+    #[serde(rename = "expected peak to mean")]
+    expected_peak_to_mean: f64,
+}
+
+impl Experiment for MaxLoad {
+    type Accumulator = NumOccurrences<u64>;
+    type Summary = MaxLoadSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumOccurrences::new(
+            usize::try_from(self.range.end)
+                .unwrap()
+                .checked_add(1)
+                .unwrap(),
+        )
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash(bytes, 0, self.range);
+        accumulator.record(hash);
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let range_len = accumulator.len() as f64;
+        let mut loads = Moments::new();
+        accumulator
+            .nonzero_counts()
+            .for_each(|load| loads.record(load as f64));
+        loads.record_repeated(0.0, accumulator.num_zero_counts() as u64);
+        let max_load = loads.max() as u64;
+        let mean_load = num_keys as f64 / range_len;
+        let peak_to_mean = max_load as f64 / mean_load;
+        let expected_peak_to_mean = 1.0 + (2.0 * range_len.ln() / mean_load).sqrt();
+        MaxLoadSummary {
+            num_keys,
+            max_load,
+            min_load: loads.min() as u64,
+            mean_load,
+            load_std_dev: loads.std_dev(),
+            expected_load_std_dev: (mean_load * (1.0 - 1.0 / range_len)).sqrt(),
+            peak_to_mean,
+            expected_peak_to_mean,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Collisions {
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Collisions {
+    pub fn new(range: RangeToInclusive<u64>, input_size_bytes: usize, keys: Keys) -> Self {
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CollisionsSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "num collisions")]
+    num_collisions: f64,
+    #[serde(rename = "c hat")]
+    c_hat: f64,
+    #[serde(rename = "normalized c hat")]
+    normalized_c_hat: f64,
+}
+
+impl Experiment for Collisions {
+    type Accumulator = NumOccurrences<u64>;
+    type Summary = CollisionsSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumOccurrences::new(
+            usize::try_from(self.range.end)
+                .unwrap()
+                .checked_add(1)
+                .unwrap(),
+        )
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash(bytes, 0, self.range);
+        accumulator.record(hash);
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let num_collisions = accumulator
+            .nonzero_counts()
+            .filter(|&c| c > 1)
+            .map(|c| c as f64)
+            .map(|c| c * (c - 1.0) / 2.0)
+            .sum::<f64>();
+        let c_hat = num_collisions / (num_keys as f64 * (num_keys as f64 - 1.0) / 2.0);
+        let normalized_c_hat = c_hat * accumulator.len() as f64;
+        CollisionsSummary {
+            num_keys,
+            num_collisions,
+            c_hat,
+            normalized_c_hat,
+        }
+    }
+}
+
+/// Estimates the number of collisions as [`Collisions`] does, but from a
+/// sketch of the counts, whose memory does not depend on the range, so that
+/// ranges too large to count exactly can be tested.
+///
+/// The number of collisions is `(F2 - n) / 2` for `n` keys, where `F2` is the
+/// sum of the squares of the counts. Each row of the sketch estimates it
+/// independently, and the summary reports their mean and its standard error.
+#[derive(Clone, Debug)]
+pub struct CollisionsSketch {
+    range: RangeToInclusive<u128>,
+    input_size_bytes: usize,
+    sketch_width: usize,
+    sketch_depth: usize,
+    keys: Keys,
+}
+
+impl CollisionsSketch {
+    pub fn new(
+        range: RangeToInclusive<u128>,
+        input_size_bytes: usize,
+        sketch_width: usize,
+        sketch_depth: usize,
+        keys: Keys,
+    ) -> Self {
+        assert!(sketch_depth > 1, "The standard error needs two rows");
+        Self {
+            range,
+            input_size_bytes,
+            sketch_width,
+            sketch_depth,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CollisionsSketchSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "num collisions")]
+    num_collisions: f64,
+    #[serde(rename = "num collisions std error")]
+    num_collisions_std_error: f64,
+    #[serde(rename = "c hat")]
+    c_hat: f64,
+    #[serde(rename = "normalized c hat")]
+    normalized_c_hat: f64,
+    #[serde(rename = "normalized c hat std error")]
+    normalized_c_hat_std_error: f64,
+}
+
+impl Experiment for CollisionsSketch {
+    type Accumulator = SecondMomentSketch;
+    type Summary = CollisionsSketchSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        SecondMomentSketch::new(self.sketch_width, self.sketch_depth)
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash_128(bytes, 0, self.range);
+        accumulator.record(hash);
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let row_num_collisions = accumulator
+            .row_estimates()
+            .map(|f2| (f2 - num_keys as f64) / 2.0)
+            .collect::<Vec<_>>();
+        let depth = row_num_collisions.len() as f64;
+        let num_collisions = row_num_collisions.iter().sum::<f64>() / depth;
+        let num_collisions_std_error = (row_num_collisions
+            .iter()
+            .map(|c| (c - num_collisions).powi(2))
+            .sum::<f64>()
+            / (depth - 1.0)
+            / depth)
+            .sqrt();
+        let num_pairs = num_keys as f64 * (num_keys as f64 - 1.0) / 2.0;
+        let c_hat = num_collisions / num_pairs;
+        let range_len = self.range.end as f64 + 1.0;
+        let normalized_c_hat = c_hat * range_len;
+        let normalized_c_hat_std_error = num_collisions_std_error / num_pairs * range_len;
+        CollisionsSketchSummary {
+            num_keys,
+            num_collisions,
+            num_collisions_std_error,
+            c_hat,
+            normalized_c_hat,
+            normalized_c_hat_std_error,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndependenceAcrossRanges {
+    ranges: Vec<RangeToInclusive<u64>>,
+    input_size_bytes: usize,
+    keys: Keys,
+    test: GoodnessOfFitTest,
+}
+
+impl IndependenceAcrossRanges {
+    pub fn new(
+        ranges: Vec<RangeToInclusive<u64>>,
+        input_size_bytes: usize,
+        keys: Keys,
+        test: GoodnessOfFitTest,
+    ) -> Self {
+        assert!(
+            ranges.iter().all_unique(),
+            "The ranges must be distinct, as the hashes of equal ranges are always equal"
+        );
+        let experiment = Self {
+            ranges,
+            input_size_bytes,
+            keys,
+            test,
+        };
+        assert!(
+            experiment
+                .dimensions()
+                .iter()
+                .filter(|dimension| dimension.end() > dimension.start())
+                .count()
+                > 1,
+            "At least two ranges must have more than one new value for the hashes to be dependent"
+        );
+        experiment
+    }
+
+    /// The new values of each range, in the given order, which the hashes are
+    /// counted in, i.e., those that are not in the next smaller range, if any.
+    fn dimensions(&self) -> Vec<RangeInclusive<u64>> {
+        self.ranges
+            .iter()
+            .map(|range| {
+                let start = self
+                    .ranges
+                    .iter()
+                    .map(|smaller_range| smaller_range.end)
+                    .filter(|&end| end < range.end)
+                    .max()
+                    .map_or(0, |end| end + 1);
+                start..=range.end
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct IndependenceSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "p-value", skip_serializing_if = "Option::is_none")]
+    p_value: Option<f64>,
+    #[serde(rename = "g p-value", skip_serializing_if = "Option::is_none")]
+    g_p_value: Option<f64>,
+    /// The number of cells of the tests, if the values of the dimensions were
+    /// merged into fewer, as their expected counts are below
+    /// [`MIN_EXPECTED_COUNT`]. The p-values are only reported if the expected
+    /// counts of the cells are at least that.
+    #[serde(rename = "merged cells", skip_serializing_if = "Option::is_none")]
+    merged_cells: Option<usize>,
+    /// The effect size of the dependence of the most dependent pair of
+    /// dimensions, which is between 0 and 1, and does not grow with the
+    /// number of keys as the statistics do.
+    #[serde(rename = "cramers v")]
+    cramers_v: f64,
+}
+
+impl IndependenceSummary {
+    fn new(test: GoodnessOfFitTest, accumulator: &NumCooccurrences) -> Self {
+        let (num_cells, min_expected_count) = mutual_independence_test_num_cells(accumulator);
+        let (p_value, g_p_value) = if min_expected_count >= MIN_EXPECTED_COUNT {
+            test.p_values(|term| mutual_independence_test_p_value(accumulator, &num_cells, term))
+        } else {
+            (None, None)
+        };
+        let num_merged_cells = num_cells.iter().product::<usize>();
+        IndependenceSummary {
+            num_keys: accumulator.num_iterations(),
+            p_value,
+            g_p_value,
+            merged_cells: (num_merged_cells < accumulator.counts().len())
+                .then_some(num_merged_cells),
+            cramers_v: cramers_v(accumulator),
+        }
+    }
+}
+
+impl Experiment for IndependenceAcrossRanges {
+    type Accumulator = NumCooccurrences;
+    type Summary = IndependenceSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumCooccurrences::new(self.dimensions())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        loop {
+            self.keys.fill(bytes);
+            // As the hashes are consistent, they are pairwise distinct if and
+            // only if each of them is one of the new values of its range.
+            if let Some(index) = accumulator.index(
+                self.ranges
+                    .iter()
+                    .map(|&range| algorithm.hash(bytes, 0, range)),
+            ) {
+                accumulator.record(index);
+                break;
+            }
+        }
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        IndependenceSummary::new(self.test, accumulator)
+    }
+
+    fn accumulator_memory_bytes(&self, _num_iterations: u64) -> f64 {
+        NumCooccurrences::num_tuples(&self.dimensions()) * mem::size_of::<u64>() as f64
+    }
+
+    fn test_cells(&self) -> Option<TestCells> {
+        Some(TestCells::mutual_independence(&self.dimensions()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndependenceAcrossSeeds {
+    range: RangeToInclusive<u64>,
+    seeds: Vec<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+    test: GoodnessOfFitTest,
+}
+
+impl IndependenceAcrossSeeds {
+    /// Returns the experiment across `num_seeds` distinct random seeds.
+    pub fn new(
+        range: RangeToInclusive<u64>,
+        num_seeds: usize,
+        input_size_bytes: usize,
+        keys: Keys,
+        test: GoodnessOfFitTest,
+    ) -> Self {
+        let seeds = iter::repeat_with(|| {
+            thread_rng()
+                .sample_iter(Standard)
+                .take(num_seeds)
+                .collect::<Vec<_>>()
+        })
+        .find(|seeds| seeds.iter().all_unique())
+        .unwrap();
+        Self::with_seeds(range, seeds, input_size_bytes, keys, test)
+    }
+
+    /// Returns the experiment across the given seeds, e.g., sequential ones,
+    /// or ones that differ in a bit, as deployments often pick them.
+    pub fn with_seeds(
+        range: RangeToInclusive<u64>,
+        seeds: Vec<u64>,
+        input_size_bytes: usize,
+        keys: Keys,
+        test: GoodnessOfFitTest,
+    ) -> Self {
+        assert!(seeds.len() > 1, "The test needs at least two seeds");
+        assert!(
+            range.end > 0,
+            "The range must have more than one value for the hashes to be dependent"
+        );
+        assert!(
+            seeds.iter().all_unique(),
+            "The seeds must be distinct, as the hashes of equal seeds are always equal"
+        );
+        Self {
+            range,
+            seeds,
+            input_size_bytes,
+            keys,
+            test,
+        }
+    }
+
+    fn dimensions(&self) -> Vec<RangeInclusive<u64>> {
+        vec![0..=self.range.end; self.seeds.len()]
+    }
+}
+
+impl Experiment for IndependenceAcrossSeeds {
+    type Accumulator = NumCooccurrences;
+    type Summary = IndependenceSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumCooccurrences::new(self.dimensions())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let index = accumulator.index(
+            self.seeds
+                .iter()
+                .map(|&seed| algorithm.hash(bytes, seed, self.range)),
+        );
+        accumulator.record(index.unwrap())
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        IndependenceSummary::new(self.test, accumulator)
+    }
+
+    fn accumulator_memory_bytes(&self, _num_iterations: u64) -> f64 {
+        NumCooccurrences::num_tuples(&self.dimensions()) * mem::size_of::<u64>() as f64
+    }
+
+    fn test_cells(&self) -> Option<TestCells> {
+        Some(TestCells::mutual_independence(&self.dimensions()))
+    }
+}
+
+/// How the second key of a pair of `serial-correlation` relates to the first
+/// one.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyRelation {
+    /// The integer of the first 8 bytes of the key, in native byte order as
+    /// the 64-bit algorithms read it, plus one.
+    Consecutive,
+    /// The key with another last byte, so that the keys share all the other
+    /// bytes.
+    SharedPrefix,
+}
+
+/// Tests the independence of the hashes of pairs of distinct but related
+/// keys, e.g., consecutive integers, using a test of mutual independence,
+/// which detects structure that the distribution of the hashes of single
+/// keys does not reveal.
+#[derive(Clone, Debug)]
+pub struct SerialCorrelation {
+    range: RangeToInclusive<u64>,
+    relation: KeyRelation,
+    input_size_bytes: usize,
+    keys: Keys,
+    test: GoodnessOfFitTest,
+}
+
+impl SerialCorrelation {
+    pub fn new(
+        range: RangeToInclusive<u64>,
+        relation: KeyRelation,
+        input_size_bytes: usize,
+        keys: Keys,
+        test: GoodnessOfFitTest,
+    ) -> Self {
+        assert!(range.end > 0);
+        assert!(
+            match relation {
+                KeyRelation::Consecutive => input_size_bytes >= 8,
+                KeyRelation::SharedPrefix => input_size_bytes > 0,
+            },
+            "The keys are too short for {relation:?}"
+        );
+        Self {
+            range,
+            relation,
+            input_size_bytes,
+            keys,
+            test,
+        }
+    }
+
+    fn dimensions(&self) -> Vec<RangeInclusive<u64>> {
+        vec![0..=self.range.end; 2]
+    }
+}
+
+impl Experiment for SerialCorrelation {
+    type Accumulator = NumCooccurrences;
+    type Summary = IndependenceSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumCooccurrences::new(self.dimensions())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash(bytes, 0, self.range);
+        match self.relation {
+            KeyRelation::Consecutive => {
+                let integer = u64::from_ne_bytes(bytes[..8].try_into().unwrap());
+                bytes[..8].copy_from_slice(&integer.wrapping_add(1).to_ne_bytes());
+            }
+            KeyRelation::SharedPrefix => {
+                // The byte is XORed with a nonzero one, so that the keys are
+                // distinct.
+                *bytes.last_mut().unwrap() ^= thread_rng().gen_range(1..=u8::MAX);
+            }
+        }
+        let related_hash = algorithm.hash(bytes, 0, self.range);
+        let index = accumulator.index([hash, related_hash]);
+        accumulator.record(index.unwrap());
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        IndependenceSummary::new(self.test, accumulator)
+    }
+
+    fn accumulator_memory_bytes(&self, _num_iterations: u64) -> f64 {
+        NumCooccurrences::num_tuples(&self.dimensions()) * mem::size_of::<u64>() as f64
+    }
+
+    fn test_cells(&self) -> Option<TestCells> {
+        Some(TestCells::mutual_independence(&self.dimensions()))
+    }
+}
+
+/// Measures how often the hash changes when flipping each bit of the key,
+/// under a random seed for each key, which should be `1 - 1 / (range_end +
+/// 1)` if flipping a bit is the same as drawing another key.
+///
+/// The per-bit probabilities are tested against that value with a
+/// chi-squared test, whose degrees of freedom are the bits of the key.
+#[derive(Clone, Debug)]
+pub struct Avalanche {
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Avalanche {
+    pub fn new(range: RangeToInclusive<u64>, input_size_bytes: usize, keys: Keys) -> Self {
+        assert!(range.end > 0 && input_size_bytes > 0);
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AvalancheSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "expected change probability")]
+    expected_change_probability: f64,
+    #[serde(rename = "min change probability")]
+    min_change_probability: f64,
+    #[serde(rename = "min change probability bit")]
+    min_change_probability_bit: usize,
+    #[serde(rename = "max change probability")]
+    max_change_probability: f64,
+    #[serde(rename = "max change probability bit")]
+    max_change_probability_bit: usize,
+    #[serde(rename = "max bias")]
+    max_bias: f64,
+    #[serde(rename = "p-value")]
+    p_value: f64,
+}
+
+impl Experiment for Avalanche {
+    type Accumulator = NumSuccesses;
+    type Summary = AvalancheSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumSuccesses::new(self.input_size_bytes * 8)
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let seed = thread_rng().next_u64();
+        let hash = algorithm.hash(bytes, seed, self.range);
+        accumulator.record_successes((0..self.input_size_bytes * 8).map(|bit| {
+            bytes[bit / 8] ^= 1 << (bit % 8);
+            let flipped_hash = algorithm.hash(bytes, seed, self.range);
+            bytes[bit / 8] ^= 1 << (bit % 8);
+            flipped_hash != hash
+        }));
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        avalanche_summary(self.range, accumulator)
+    }
+}
+
+/// Measures how often the hash of a key changes when flipping each bit of
+/// the seed, as [`Avalanche`] does for the bits of the key, since weakly
+/// mixed seeds would make the hashes of different seeds correlated.
+#[derive(Clone, Debug)]
+pub struct SeedAvalanche {
+    range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl SeedAvalanche {
+    pub fn new(range: RangeToInclusive<u64>, input_size_bytes: usize, keys: Keys) -> Self {
+        assert!(range.end > 0);
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+impl Experiment for SeedAvalanche {
+    type Accumulator = NumSuccesses;
+    type Summary = AvalancheSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumSuccesses::new(u64::BITS as usize)
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let seed = thread_rng().next_u64();
+        let hash = algorithm.hash(bytes, seed, self.range);
+        accumulator.record_successes(
+            (0..u64::BITS).map(|bit| algorithm.hash(bytes, seed ^ (1 << bit), self.range) != hash),
+        );
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        avalanche_summary(self.range, accumulator)
+    }
+}
+
+/// Returns the summary of the numbers of changes of the hash when flipping
+/// each bit, which should be `1 - 1 / (range_end + 1)` if flipping a bit is
+/// the same as drawing another input.
+fn avalanche_summary(range: RangeToInclusive<u64>, accumulator: &NumSuccesses) -> AvalancheSummary {
+    let num_keys = accumulator.num_iterations();
+    let expected_probability = 1.0 - 1.0 / (range.end as f64 + 1.0);
+    let probabilities = accumulator
+        .counts()
+        .iter()
+        .map(|&c| c as f64 / num_keys as f64)
+        .collect::<Vec<_>>();
+    let (min_bit, min_probability) = probabilities
+        .iter()
+        .copied()
+        .enumerate()
+        .min_by(|(_, p0), (_, p1)| p0.total_cmp(p1))
+        .unwrap();
+    let (max_bit, max_probability) = probabilities
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|(_, p0), (_, p1)| p0.total_cmp(p1))
+        .unwrap();
+    let max_bias = probabilities
+        .iter()
+        .map(|p| (p - expected_probability).abs())
+        .fold(0.0, f64::max);
+    let statistic = accumulator
+        .counts()
+        .iter()
+        .map(|&c| {
+            let e = expected_probability * num_keys as f64;
+            (c as f64 - e).powi(2) / (e * (1.0 - expected_probability))
+        })
+        .sum::<f64>();
+    let p_value = 1.0
+        - ChiSquared::new(probabilities.len() as f64)
+            .unwrap()
+            .cdf(statistic);
+    AvalancheSummary {
+        num_keys,
+        expected_change_probability: expected_probability,
+        min_change_probability: min_probability,
+        min_change_probability_bit: min_bit,
+        max_change_probability: max_probability,
+        max_change_probability_bit: max_bit,
+        max_bias,
+        p_value,
+    }
+}
+
+/// Checks that when the range grows from `..=n` to `..=n+1`, keys either
+/// keep their hash or move to `n+1`, and compares the fraction of keys that
+/// move with `1 / (n + 2)`, the fraction of the new range that `n+1` is.
+#[derive(Clone, Debug)]
+pub struct Consistency {
+    range: RangeToInclusive<u128>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Consistency {
+    const KEPT: u64 = 0;
+    const MOVED_TO_NEW_BUCKET: u64 = 1;
+    const MOVED_ELSEWHERE: u64 = 2;
+
+    pub fn new(range: RangeToInclusive<u128>, input_size_bytes: usize, keys: Keys) -> Self {
+        assert!(range.end < u128::MAX);
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConsistencySummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "num moved")]
+    num_moved: u64,
+    #[serde(rename = "num moved elsewhere")]
+    num_moved_elsewhere: u64,
+    #[serde(rename = "moved fraction")]
+    moved_fraction: f64,
+    #[serde(rename = "expected moved fraction")]
+    expected_moved_fraction: f64,
+    #[serde(rename = "p-value")]
+    p_value: f64,
+}
+
+impl Experiment for Consistency {
+    type Accumulator = NumOccurrences<u64>;
+    type Summary = ConsistencySummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumOccurrences::new(3)
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash_128(bytes, 0, self.range);
+        let new_bucket = self.range.end + 1;
+        let new_hash = algorithm.hash_128(bytes, 0, ..=new_bucket);
+        accumulator.record(if new_hash == hash {
+            Self::KEPT
+        } else if new_hash == new_bucket {
+            Self::MOVED_TO_NEW_BUCKET
+        } else {
+            Self::MOVED_ELSEWHERE
+        });
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let num_moved_to_new_bucket = accumulator.count(Self::MOVED_TO_NEW_BUCKET as usize);
+        let num_moved_elsewhere = accumulator.count(Self::MOVED_ELSEWHERE as usize);
+        let num_moved = num_moved_to_new_bucket + num_moved_elsewhere;
+        let moved_fraction = num_moved as f64 / num_keys as f64;
+        let expected_moved_fraction = 1.0 / (self.range.end as f64 + 2.0);
+        let p_value = binomial_test_p_value(num_moved, num_keys, expected_moved_fraction);
+        ConsistencySummary {
+            num_keys,
+            num_moved,
+            num_moved_elsewhere,
+            moved_fraction,
+            expected_moved_fraction,
+            p_value,
+        }
+    }
+}
+
+/// Measures the fractions of keys that move when the range grows from `..=n`
+/// to `..=n+1` and to `..=2n`, with their confidence intervals, which are
+/// those of the new values for consistent hashing, i.e., `1 / (n + 2)` and
+/// `n / (2n + 1)`, and the fractions of keys that move to values that were
+/// already in the range, which should not.
+#[derive(Clone, Debug)]
+pub struct Remap {
+    range: RangeToInclusive<u128>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Remap {
+    /// The indices of the trials of a key, for each of the grown ranges.
+    const MOVED_BY_ONE: usize = 0;
+    const MOVED_ELSEWHERE_BY_ONE: usize = 1;
+    const MOVED_TO_TWICE: usize = 2;
+    const MOVED_ELSEWHERE_TO_TWICE: usize = 3;
+    const CONFIDENCE_LEVEL: f64 = 0.95;
+
+    pub fn new(range: RangeToInclusive<u128>, input_size_bytes: usize, keys: Keys) -> Self {
+        assert!(
+            range.end > 1 && range.end <= u128::MAX / 2,
+            "The range end must be at least 2, for `..=2n` to be larger than `..=n+1`, and at \
+             most half the largest one"
+        );
+        Self {
+            range,
+            input_size_bytes,
+            keys,
+        }
+    }
+
+    /// Returns the moves of the keys to the grown range of `new_range_end`.
+    fn moves(
+        &self,
+        accumulator: &NumSuccesses,
+        new_range_end: u128,
+        moved_index: usize,
+        moved_elsewhere_index: usize,
+    ) -> Moves {
+        let num_keys = accumulator.num_iterations();
+        let num_moved = accumulator.counts()[moved_index];
+        let expected_moved_fraction =
+            (new_range_end - self.range.end) as f64 / (new_range_end as f64 + 1.0);
+        Moves {
+            moved_fraction: num_moved as f64 / num_keys as f64,
+            moved_fraction_ci: wilson_interval(num_moved, num_keys, Self::CONFIDENCE_LEVEL),
+            expected_moved_fraction,
+            p_value: binomial_test_p_value(num_moved, num_keys, expected_moved_fraction),
+            num_moved_elsewhere: accumulator.counts()[moved_elsewhere_index],
+        }
+    }
+}
+
+/// The moves of the keys to a grown range.
+struct Moves {
+    moved_fraction: f64,
+    moved_fraction_ci: (f64, f64),
+    expected_moved_fraction: f64,
+    p_value: f64,
+    num_moved_elsewhere: u64,
+}
+
+/// The moves of the keys when the range grows by one, and to twice its end,
+/// with the 95% Wilson score intervals of their fractions.
+#[derive(Serialize)]
+pub struct RemapSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "by one moved fraction")]
+    by_one_moved_fraction: f64,
+    #[serde(rename = "by one moved fraction ci low")]
+    by_one_moved_fraction_ci_low: f64,
+    #[serde(rename = "by one moved fraction ci high")]
+    by_one_moved_fraction_ci_high: f64,
+    #[serde(rename = "by one expected moved fraction")]
+    by_one_expected_moved_fraction: f64,
+    #[serde(rename = "by one p-value")]
+    by_one_p_value: f64,
+    #[serde(rename = "by one num moved elsewhere")]
+    by_one_num_moved_elsewhere: u64,
+    #[serde(rename = "to twice moved fraction")]
+    to_twice_moved_fraction: f64,
+    #[serde(rename = "to twice moved fraction ci low")]
+    to_twice_moved_fraction_ci_low: f64,
+    #[serde(rename = "to twice moved fraction ci high")]
+    to_twice_moved_fraction_ci_high: f64,
+    #[serde(rename = "to twice expected moved fraction")]
+    to_twice_expected_moved_fraction: f64,
+    #[serde(rename = "to twice p-value")]
+    to_twice_p_value: f64,
+    #[serde(rename = "to twice num moved elsewhere")]
+    to_twice_num_moved_elsewhere: u64,
+}
+
+impl Experiment for Remap {
+    type Accumulator = NumSuccesses;
+    type Summary = RemapSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumSuccesses::new(4)
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash_128(bytes, 0, self.range);
+        let hash_by_one = algorithm.hash_128(bytes, 0, ..=self.range.end + 1);
+        let hash_to_twice = algorithm.hash_128(bytes, 0, ..=2 * self.range.end);
+        let mut successes = [false; 4];
+        successes[Self::MOVED_BY_ONE] = hash_by_one != hash;
+        successes[Self::MOVED_ELSEWHERE_BY_ONE] =
+            hash_by_one != hash && hash_by_one <= self.range.end;
+        successes[Self::MOVED_TO_TWICE] = hash_to_twice != hash;
+        successes[Self::MOVED_ELSEWHERE_TO_TWICE] =
+            hash_to_twice != hash && hash_to_twice <= self.range.end;
+        accumulator.record_successes(successes);
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let by_one = self.moves(
+            accumulator,
+            self.range.end + 1,
+            Self::MOVED_BY_ONE,
+            Self::MOVED_ELSEWHERE_BY_ONE,
+        );
+        let to_twice = self.moves(
+            accumulator,
+            2 * self.range.end,
+            Self::MOVED_TO_TWICE,
+            Self::MOVED_ELSEWHERE_TO_TWICE,
+        );
+        RemapSummary {
+            num_keys: accumulator.num_iterations(),
+            by_one_moved_fraction: by_one.moved_fraction,
+            by_one_moved_fraction_ci_low: by_one.moved_fraction_ci.0,
+            by_one_moved_fraction_ci_high: by_one.moved_fraction_ci.1,
+            by_one_expected_moved_fraction: by_one.expected_moved_fraction,
+            by_one_p_value: by_one.p_value,
+            by_one_num_moved_elsewhere: by_one.num_moved_elsewhere,
+            to_twice_moved_fraction: to_twice.moved_fraction,
+            to_twice_moved_fraction_ci_low: to_twice.moved_fraction_ci.0,
+            to_twice_moved_fraction_ci_high: to_twice.moved_fraction_ci.1,
+            to_twice_expected_moved_fraction: to_twice.expected_moved_fraction,
+            to_twice_p_value: to_twice.p_value,
+            to_twice_num_moved_elsewhere: to_twice.num_moved_elsewhere,
+        }
+    }
+}
+
+/// Measures where keys land when the range shrinks from `..=n` to `..=m`:
+/// the keys of the removed values should be the only ones to move, and
+/// should be spread uniformly over the remaining values.
+#[derive(Clone, Debug)]
+pub struct Shrink {
+    range: RangeToInclusive<u64>,
+    new_range: RangeToInclusive<u64>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Shrink {
+    pub fn new(
+        range: RangeToInclusive<u64>,
+        new_range: RangeToInclusive<u64>,
+        input_size_bytes: usize,
+        keys: Keys,
+    ) -> Self {
+        assert!(new_range.end < range.end);
+        Self {
+            range,
+            new_range,
+            input_size_bytes,
+            keys,
+        }
+    }
+
+    /// The index of the number of keys that keep their hash, after the
+    /// destinations of the keys that move.
+    fn kept_index(&self) -> u64 {
+        self.new_range.end + 1
+    }
+
+    /// The index of the number of keys that move although their hash is in
+    /// the new range.
+    fn moved_from_remaining_value_index(&self) -> u64 {
+        self.new_range.end + 2
+    }
+}
+
+#[derive(Serialize)]
+pub struct ShrinkSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "num moved from removed values")]
+    num_moved_from_removed_values: u64,
+    #[serde(rename = "num moved from remaining values")]
+    num_moved_from_remaining_values: u64,
+    #[serde(rename = "moved fraction")]
+    moved_fraction: f64,
+    #[serde(rename = "expected moved fraction")]
+    expected_moved_fraction: f64,
+    #[serde(rename = "destination p-value")]
+    destination_p_value: f64,
+    /// The number of cells of the test of the destinations, if they were
+    /// merged as in [`RegularitySummary`].
+    #[serde(
+        rename = "destination merged cells",
+        skip_serializing_if = "Option::is_none"
+    )]
+    destination_merged_cells: Option<usize>,
+}
+
+impl Experiment for Shrink {
+    type Accumulator = NumOccurrences<u64>;
+    type Summary = ShrinkSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        NumOccurrences::new(
+            usize::try_from(self.moved_from_remaining_value_index())
+                .unwrap()
+                .checked_add(1)
+                .unwrap(),
+        )
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; self.input_size_bytes]
+    }
+
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        bytes: &mut Self::Scratch,
+    ) {
+        self.keys.fill(bytes);
+        let hash = algorithm.hash(bytes, 0, self.range);
+        let new_hash = algorithm.hash(bytes, 0, self.new_range);
+        accumulator.record(if hash > self.new_range.end {
+            new_hash
+        } else if new_hash == hash {
+            self.kept_index()
+        } else {
+            self.moved_from_remaining_value_index()
+        });
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let destination_counts = (0..=self.new_range.end as usize)
+            .map(|value| accumulator.count(value))
+            .collect::<Vec<_>>();
+        let num_moved_from_removed_values = destination_counts.iter().sum::<u64>();
+        let num_moved_from_remaining_values =
+            accumulator.count(self.moved_from_remaining_value_index() as usize);
+        let moved_fraction = (num_moved_from_removed_values + num_moved_from_remaining_values)
+            as f64
+            / num_keys as f64;
+        let expected_moved_fraction =
+            (self.range.end - self.new_range.end) as f64 / (self.range.end as f64 + 1.0);
+        let (destination_p_value, num_destination_cells) =
+            chi_squared_uniformity_test(&destination_counts);
+        ShrinkSummary {
+            num_keys,
+            num_moved_from_removed_values,
+            num_moved_from_remaining_values,
+            moved_fraction,
+            expected_moved_fraction,
+            destination_p_value,
+            destination_merged_cells: (num_destination_cells < destination_counts.len())
+                .then_some(num_destination_cells),
+        }
+    }
+}
+
+/// Measures the number of keys hashed per second, for each range and input
+/// size.
+///
+/// The keys are generated in batches, before timing the hashing of the
+/// batch, so that the throughput does not include the generation of keys.
+#[derive(Clone, Debug)]
+pub struct Speed {
+    ranges: Vec<RangeToInclusive<u64>>,
+    input_sizes_bytes: Vec<usize>,
+    keys: Keys,
+}
+
+impl Speed {
+    const BATCH_LEN: usize = 1024;
+
+    pub fn new(
+        ranges: Vec<RangeToInclusive<u64>>,
+        input_sizes_bytes: Vec<usize>,
+        keys: Keys,
+    ) -> Self {
+        assert!(!ranges.is_empty() && !input_sizes_bytes.is_empty());
+        Self {
+            ranges,
+            input_sizes_bytes,
+            keys,
+        }
+    }
+
+    fn configurations(&self) -> impl Iterator<Item = (RangeToInclusive<u64>, usize)> + '_ {
+        self.ranges
+            .iter()
+            .cartesian_product(&self.input_sizes_bytes)
+            .map(|(&range, &input_size_bytes)| (range, input_size_bytes))
+    }
+}
+
+#[derive(Serialize)]
+pub struct SpeedSummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    throughputs: Vec<Throughput>,
+}
+
+/// The throughput of a configuration of [`Speed`].
+#[derive(Serialize)]
+pub struct Throughput {
+    #[serde(rename = "range end")]
+    range_end: u64,
+    #[serde(rename = "input size bytes")]
+    input_size_bytes: usize,
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "keys per second")]
+    keys_per_second: f64,
+    /// The mean time to build the state of the algorithm for the range, e.g.,
+    /// its lookup table, if it has one, which is built once per worker and
+    /// not included in the throughput.
+    #[serde(rename = "build seconds", skip_serializing_if = "Option::is_none")]
+    build_seconds: Option<f64>,
+}
+
+impl Experiment for Speed {
+    type Accumulator = Durations;
+    type Summary = SpeedSummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        Durations::new(self.configurations().count())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        let max_input_size_bytes = self.input_sizes_bytes.iter().max().copied().unwrap_or(0);
+        vec![0; Self::BATCH_LEN * max_input_size_bytes]
+    }
+
+    /// Hashes a batch of keys for each configuration.
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        batch: &mut Self::Scratch,
+    ) {
+        for (index, (range, input_size_bytes)) in self.configurations().enumerate() {
+            let keys = &mut batch[..Self::BATCH_LEN * input_size_bytes];
+            for key in keys.chunks_exact_mut(input_size_bytes) {
+                self.keys.fill(key);
+            }
+            let start = Instant::now();
+            for key in keys.chunks_exact(input_size_bytes) {
+                black_box(algorithm.hash(black_box(key), 0, range));
+            }
+            accumulator.record((index, Self::BATCH_LEN as u64, start.elapsed()));
+        }
+    }
+
+    /// Builds the state of the algorithm for each configuration, if it has
+    /// one that is not built yet, then hashes `num_iterations` keys for each
+    /// configuration.
+    fn accumulate(&self, algorithm: &impl Algorithm, num_iterations: u64) -> Self::Accumulator {
+        let mut accumulator = self.new_accumulator();
+        for (index, (range, _)) in self.configurations().enumerate() {
+            let start = Instant::now();
+            if algorithm.build(0, range) {
+                accumulator.record_build(index, start.elapsed());
+            }
+        }
+        let mut scratch = self.new_scratch();
+        for _ in 0..num_iterations.div_ceil(Self::BATCH_LEN as u64) {
+            self.run(&mut accumulator, algorithm, &mut scratch);
+        }
+        accumulator
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let num_keys = accumulator.num_iterations();
+        let throughputs = self
+            .configurations()
+            .zip(accumulator.num_keys().iter().zip(accumulator.elapsed()))
+            .zip(
+                accumulator
+                    .num_builds()
+                    .iter()
+                    .zip(accumulator.build_elapsed()),
+            )
+            .map(
+                |(
+                    ((range, input_size_bytes), (&num_keys, elapsed)),
+                    (&num_builds, build_elapsed),
+                )| {
+                    Throughput {
+                        range_end: range.end,
+                        input_size_bytes,
+                        num_keys,
+                        keys_per_second: num_keys as f64 / elapsed.as_secs_f64(),
+                        build_seconds: (num_builds > 0)
+                            .then(|| build_elapsed.as_secs_f64() / num_builds as f64),
+                    }
+                },
+            )
+            .collect();
+        SpeedSummary {
+            num_keys,
+            throughputs,
+        }
+    }
+}
+
+/// Measures the latency of hashing a key for each of the given range ends,
+/// as its mean and the percentiles of the times of batches of keys, e.g., to
+/// show how the cost of algorithms scales with the range end.
+///
+/// As reading the clock takes longer than hashing a key, keys are timed in
+/// batches of [`Latency::BATCH_LEN`], so that the percentiles are those of
+/// the mean latencies of the keys of the batches.
+#[derive(Clone, Debug)]
+pub struct Latency {
+    ranges: Vec<RangeToInclusive<u64>>,
+    input_size_bytes: usize,
+    keys: Keys,
+}
+
+impl Latency {
+    pub const BATCH_LEN: usize = 32;
+
+    pub fn new(ranges: Vec<RangeToInclusive<u64>>, input_size_bytes: usize, keys: Keys) -> Self {
+        assert!(!ranges.is_empty());
+        Self {
+            ranges,
+            input_size_bytes,
+            keys,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LatencySummary {
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    latencies: Vec<RangeLatency>,
+}
+
+/// The latency of hashing a key to a range of [`Latency`].
+#[derive(Serialize)]
+pub struct RangeLatency {
+    #[serde(rename = "range end")]
+    range_end: u64,
+    #[serde(rename = "num keys")]
+    num_keys: u64,
+    #[serde(rename = "mean nanoseconds")]
+    mean_nanoseconds: f64,
+    #[serde(rename = "median nanoseconds")]
+    median_nanoseconds: Option<f64>,
+    #[serde(rename = "p99 nanoseconds")]
+    p99_nanoseconds: Option<f64>,
+}
+
+impl Experiment for Latency {
+    type Accumulator = Latencies;
+    type Summary = LatencySummary;
+    type Scratch = Vec<u8>;
+
+    fn new_accumulator(&self) -> Self::Accumulator {
+        Latencies::new(self.ranges.len())
+    }
+
+    fn new_scratch(&self) -> Self::Scratch {
+        vec![0; Self::BATCH_LEN * self.input_size_bytes]
+    }
+
+    /// Hashes a batch of keys for each range.
+    #[inline]
+    fn run(
+        &self,
+        accumulator: &mut Self::Accumulator,
+        algorithm: &impl Algorithm,
+        keys: &mut Self::Scratch,
+    ) {
+        for (index, &range) in self.ranges.iter().enumerate() {
+            for key in keys.chunks_exact_mut(self.input_size_bytes) {
+                self.keys.fill(key);
+            }
+            let start = Instant::now();
+            for key in keys.chunks_exact(self.input_size_bytes) {
+                black_box(algorithm.hash(black_box(key), 0, range));
+            }
+            accumulator.record((index, Self::BATCH_LEN as u64, start.elapsed()));
+        }
+    }
+
+    /// Builds the state of the algorithm for each range, as [`Speed`] does,
+    /// then hashes `num_iterations` keys for each range.
+    fn accumulate(&self, algorithm: &impl Algorithm, num_iterations: u64) -> Self::Accumulator {
+        let mut accumulator = self.new_accumulator();
+        for (index, &range) in self.ranges.iter().enumerate() {
+            let start = Instant::now();
+            if algorithm.build(0, range) {
+                accumulator.record_build(index, start.elapsed());
+            }
+        }
+        let mut scratch = self.new_scratch();
+        for _ in 0..num_iterations.div_ceil(Self::BATCH_LEN as u64) {
+            self.run(&mut accumulator, algorithm, &mut scratch);
+        }
+        accumulator
+    }
+
+    fn summary(&self, accumulator: &Self::Accumulator) -> Self::Summary {
+        let durations = accumulator.durations();
+        let latencies = self
+            .ranges
+            .iter()
+            .zip(durations.num_keys().iter().zip(durations.elapsed()))
+            .enumerate()
+            .map(|(index, (range, (&num_keys, elapsed)))| {
+                let quantile = |q| {
+                    accumulator
+                        .quantile_nanos(index, q)
+                        .map(|nanos| nanos / Self::BATCH_LEN as f64)
+                };
+                RangeLatency {
+                    range_end: range.end,
+                    num_keys,
+                    mean_nanoseconds: elapsed.as_nanos() as f64 / num_keys as f64,
+                    median_nanoseconds: quantile(0.5),
+                    p99_nanoseconds: quantile(0.99),
+                }
+            })
+            .collect();
+        LatencySummary {
+            num_keys: accumulator.num_iterations(),
+            latencies,
+        }
+    }
+}
+
+/// Returns the Wilson score interval of the probability of success given
+/// `num_successes` out of `num_trials`, at `confidence_level`, which, unlike
+/// the normal approximation, stays within `[0, 1]` and is sound for
+/// probabilities close to 0, e.g., of keys moving when a large range grows.
+fn wilson_interval(num_successes: u64, num_trials: u64, confidence_level: f64) -> (f64, f64) {
+    let z = Normal::new(0.0, 1.0)
+        .unwrap()
+        .inverse_cdf((1.0 + confidence_level) / 2.0);
+    let n = num_trials as f64;
+    let p = num_successes as f64 / n;
+    let center = (p + z * z / (2.0 * n)) / (1.0 + z * z / n);
+    let half_width = z / (1.0 + z * z / n) * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+    (
+        (center - half_width).max(0.0),
+        (center + half_width).min(1.0),
+    )
+}
+
+/// Returns the two-sided p-value of `num_successes` out of `num_trials` given
+/// that the probability of success is `probability`, with the normal
+/// approximation of the binomial distribution.
+fn binomial_test_p_value(num_successes: u64, num_trials: u64, probability: f64) -> f64 {
+    let mean = num_trials as f64 * probability;
+    let std_dev = (mean * (1.0 - probability)).sqrt();
+    let z = (num_successes as f64 - mean).abs() / std_dev;
+    2.0 * (1.0 - Normal::new(0.0, 1.0).unwrap().cdf(z))
+}
+
+/// Returns the p-value of the chi-squared test of uniformity of
+/// `num_occurrences`, whose values are merged into cells as in
+/// [`uniformity_test_num_cells`], or 1 if fewer than two cells are left, as
+/// there is nothing to test.
+pub fn chi_squared_uniformity_test_p_value(num_occurrences: &[u64]) -> f64 {
+    chi_squared_uniformity_test(num_occurrences).0
+}
+
+/// Returns the p-value of [`chi_squared_uniformity_test_p_value`] and the
+/// number of cells of the test.
+fn chi_squared_uniformity_test(num_occurrences: &[u64]) -> (f64, usize) {
+    let len = num_occurrences.len();
+    let num_cells = uniformity_test_num_cells(num_occurrences.iter().sum(), len);
+    let nonzero_num_occurrences_by_value = num_occurrences
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, o)| o > 0)
+        .collect::<Vec<_>>();
+    let p_value = if num_cells > 1 {
+        uniformity_test_p_value(
+            &nonzero_num_occurrences_by_value,
+            len,
+            num_cells,
+            chi_squared_term,
+        )
+    } else {
+        1.0
+    };
+    (p_value, num_cells)
+}
+
+/// Returns the number of cells of the uniformity test of `num_samples`
+/// samples of `len` values, which are the values unless their expected counts
+/// are below [`MIN_EXPECTED_COUNT`], in which case they are the most runs of
+/// consecutive values whose expected counts are at least that.
+fn uniformity_test_num_cells(num_samples: u64, len: usize) -> usize {
+    if num_samples as f64 >= MIN_EXPECTED_COUNT * len as f64 {
+        return len;
+    }
+    let min_cell_len = (MIN_EXPECTED_COUNT * len as f64 / num_samples as f64).ceil();
+    // Without samples, the length is infinite, and saturates.
+    len / min_cell_len as usize
+}
+
+/// Returns the cell of the value at `offset` of `len` values that are merged
+/// into `num_cells` runs of consecutive values, whose lengths differ by at
+/// most one.
+fn merged_cell(offset: usize, len: usize, num_cells: usize) -> usize {
+    (offset as u128 * num_cells as u128 / len as u128) as usize
+}
+
+/// The p-value of the uniformity test whose statistic is the sum of `term`
+/// over the counts of `num_cells` cells, given the nonzero numbers of
+/// occurrences of `len` values by value, which are merged as in
+/// [`merged_cell`] if there are fewer cells than values.
+fn uniformity_test_p_value(
+    nonzero_num_occurrences_by_value: &[(usize, u64)],
+    len: usize,
+    num_cells: usize,
+    term: fn(f64, f64) -> f64,
+) -> f64 {
+    let nonzero_num_occurrences = nonzero_num_occurrences_by_value.iter().map(|&(_, o)| o);
+    if num_cells == len {
+        return sparse_uniformity_test_p_value(nonzero_num_occurrences, len, term);
+    }
+
+    let mut cell_counts = vec![0; num_cells];
+    for &(value, o) in nonzero_num_occurrences_by_value {
+        cell_counts[merged_cell(value, len, num_cells)] += o;
+    }
+    let num_samples = nonzero_num_occurrences.sum::<u64>() as f64;
+    // The first value of a cell is the smallest one whose cell it is.
+    let cell_start = |cell: usize| (cell as u128 * len as u128).div_ceil(num_cells as u128);
+    let statistic = cell_counts
+        .iter()
+        .enumerate()
+        .map(|(cell, &o)| {
+            let cell_len = cell_start(cell + 1) - cell_start(cell);
+            term(o as f64, num_samples * cell_len as f64 / len as f64)
+        })
+        .sum::<f64>();
+
+    let degrees_of_freedom = num_cells as f64 - 1.0;
+
+    1.0 - ChiSquared::new(degrees_of_freedom).unwrap().cdf(statistic)
+}
+
+/// The term of the chi-squared statistic of an observed and an expected
+/// count.
+fn chi_squared_term(observed: f64, expected: f64) -> f64 {
+    (observed - expected).powi(2) / expected
+}
+
+/// The term of the G statistic of an observed and an expected count, which
+/// is twice the contribution of the count to the log-likelihood ratio.
+fn g_term(observed: f64, expected: f64) -> f64 {
+    if observed > 0.0 {
+        2.0 * observed * (observed / expected).ln()
+    } else {
+        0.0
+    }
+}
+
+/// The p-value of the uniformity test whose statistic is the sum of `term`
+/// over the counts, given the nonzero numbers of occurrences of `len`
+/// values.
+fn sparse_uniformity_test_p_value(
+    nonzero_num_occurrences: impl Iterator<Item = u64> + Clone,
+    len: usize,
+    term: fn(f64, f64) -> f64,
+) -> f64 {
+    let expected_count = nonzero_num_occurrences.clone().sum::<u64>() as f64 / len as f64;
+
+    let num_zero_occurrences = len - nonzero_num_occurrences.clone().count();
+    let statistic = nonzero_num_occurrences
+        .map(|o| term(o as f64, expected_count))
+        .sum::<f64>()
+        + num_zero_occurrences as f64 * term(0.0, expected_count);
+
+    let degrees_of_freedom = len as f64 - 1.0;
+
+    1.0 - ChiSquared::new(degrees_of_freedom).unwrap().cdf(statistic)
+}
+
+/// Returns the p-value of the exact multinomial test of uniformity of
+/// `num_occurrences`, with the chi-squared statistic, which is estimated as
+/// the fraction of `num_samples` samples of the uniform multinomial
+/// distribution whose statistics are at least that of `num_occurrences`.
+///
+/// The samples are drawn from a fixed seed, so that the p-value only depends
+/// on the counts, and it is at least `1 / (num_samples + 1)`.
+fn monte_carlo_multinomial_uniformity_test_p_value(
+    num_occurrences: &[u64],
+    num_samples: usize,
+) -> f64 {
+    const SEED: u64 = 0x6d75_6c74_696e_6f6d;
+    // The statistics of the samples that equal the observed one, up to
+    // rounding errors, count as at least as large.
+    const RELATIVE_TOLERANCE: f64 = 64.0 * f64::EPSILON;
+
+    let num_keys = num_occurrences.iter().sum::<u64>();
+    let len = num_occurrences.len();
+    let expected_count = num_keys as f64 / len as f64;
+    let statistic = |counts: &[u64]| {
+        counts
+            .iter()
+            .map(|&o| chi_squared_term(o as f64, expected_count))
+            .sum::<f64>()
+    };
+    let observed_statistic = statistic(num_occurrences);
+
+    let mut rng = ChaCha12Rng::seed_from_u64(SEED);
+    let weights = vec![1.0; len];
+    let mut counts = vec![0; len];
+    let num_at_least_observed = (0..num_samples)
+        .filter(|_| {
+            sample_multinomial(&mut rng, num_keys, &weights, &mut counts);
+            statistic(&counts) >= observed_statistic * (1.0 - RELATIVE_TOLERANCE)
+        })
+        .count();
+    (num_at_least_observed + 1) as f64 / (num_samples + 1) as f64
+}
+
+/// Draws the counts of `num_trials` trials of the multinomial distribution
+/// whose probabilities are proportional to `weights`.
+fn sample_multinomial(rng: &mut impl Rng, num_trials: u64, weights: &[f64], counts: &mut [u64]) {
+    // The count of each outcome is binomial given those of the previous
+    // outcomes.
+    let mut remaining_trials = num_trials;
+    let mut remaining_weight = weights.iter().sum::<f64>();
+    for (&weight, count) in weights.iter().zip(counts) {
+        *count = if remaining_trials == 0 || weight >= remaining_weight {
+            remaining_trials
+        } else {
+            Binomial::new(remaining_trials, weight / remaining_weight)
+                .unwrap()
+                .sample(rng)
+        };
+        remaining_trials -= *count;
+        remaining_weight -= weight;
+    }
+}
+
+/// Returns the L1 and L2 distances between the distribution of the values,
+/// given their nonzero numbers of occurrences, and the uniform distribution
+/// over `0..len`.
+fn uniformity_distances(
+    nonzero_num_occurrences: impl Iterator<Item = u64> + Clone,
+    num_zero_occurrences: usize,
+    len: usize,
+) -> (f64, f64) {
+    let num_samples = nonzero_num_occurrences.clone().sum::<u64>() as f64;
+    let len = len as f64;
+    let differences = nonzero_num_occurrences.map(|o| o as f64 / num_samples - 1.0 / len);
+    // The values that did not occur are each 1 / len away from the uniform
+    // distribution.
+    let l1_distance =
+        differences.clone().map(f64::abs).sum::<f64>() + num_zero_occurrences as f64 / len;
+    let l2_distance = (differences.map(|d| d.powi(2)).sum::<f64>()
+        + num_zero_occurrences as f64 / len.powi(2))
+    .sqrt();
+    (l1_distance, l2_distance)
+}
+
+/// Returns the 95% basic bootstrap confidence intervals of the L1 and L2
+/// distances of [`uniformity_distances`], from `num_samples` samples of the
+/// multinomial distribution of the observed frequencies.
+///
+/// The distances of the samples are biased upward, as those of the counts
+/// are, so the quantiles of the samples are reflected around the observed
+/// distances, which subtracts the bias that the samples estimate, unlike
+/// percentile intervals. The bounds are clamped to 0.
+///
+/// The samples are drawn from a fixed seed, so that the intervals only
+/// depend on the counts. The values that did not occur are not sampled, as
+/// their observed frequencies are 0.
+fn bootstrap_uniformity_distances_cis(
+    nonzero_num_occurrences: &[u64],
+    num_zero_occurrences: usize,
+    len: usize,
+    num_samples: usize,
+) -> [(f64, f64); 2] {
+    const SEED: u64 = 0x626f_6f74_7374_7261;
+    const CONFIDENCE_LEVEL: f64 = 0.95;
+
+    let num_keys = nonzero_num_occurrences.iter().sum::<u64>();
+    let (l1_distance, l2_distance) = uniformity_distances(
+        nonzero_num_occurrences.iter().copied(),
+        num_zero_occurrences,
+        len,
+    );
+    let weights = nonzero_num_occurrences
+        .iter()
+        .map(|&o| o as f64)
+        .collect::<Vec<_>>();
+    let mut rng = ChaCha12Rng::seed_from_u64(SEED);
+    let mut counts = vec![0; weights.len()];
+    let (mut l1_distances, mut l2_distances): (Vec<_>, Vec<_>) = (0..num_samples)
+        .map(|_| {
+            sample_multinomial(&mut rng, num_keys, &weights, &mut counts);
+            let num_sampled_zero_occurrences = counts.iter().filter(|&&o| o == 0).count();
+            uniformity_distances(
+                counts.iter().copied().filter(|&o| o > 0),
+                num_zero_occurrences + num_sampled_zero_occurrences,
+                len,
+            )
+        })
+        .unzip();
+    [
+        (l1_distance, &mut l1_distances),
+        (l2_distance, &mut l2_distances),
+    ]
+    .map(|(distance, distances)| {
+        distances.sort_unstable_by(f64::total_cmp);
+        let quantile = |q: f64| distances[((distances.len() - 1) as f64 * q).round() as usize];
+        (
+            (2.0 * distance - quantile((1.0 + CONFIDENCE_LEVEL) / 2.0)).max(0.0),
+            (2.0 * distance - quantile((1.0 - CONFIDENCE_LEVEL) / 2.0)).max(0.0),
+        )
+    })
+}
+
+/// Returns the largest difference between the empirical distribution function
+/// of the values, given their nonzero numbers of occurrences, and that of the
+/// uniform distribution over `0..len`.
+fn kolmogorov_smirnov_uniformity_statistic(
+    mut nonzero_num_occurrences_by_value: Vec<(usize, u64)>,
+    len: usize,
+) -> f64 {
+    nonzero_num_occurrences_by_value.sort_unstable_by_key(|&(value, _)| value);
+    let num_samples = nonzero_num_occurrences_by_value
+        .iter()
+        .map(|&(_, o)| o)
+        .sum::<u64>() as f64;
+    // Between the values that occur, the empirical distribution function is
+    // constant, so the largest differences are next to them.
+    let mut cumulative_num_occurrences = 0;
+    let mut statistic = 0.0_f64;
+    for (value, o) in nonzero_num_occurrences_by_value {
+        let below = value as f64 / len as f64 - cumulative_num_occurrences as f64 / num_samples;
+        cumulative_num_occurrences += o;
+        let above =
+            cumulative_num_occurrences as f64 / num_samples - (value + 1) as f64 / len as f64;
+        statistic = statistic.max(below).max(above);
+    }
+    statistic
+}
+
+/// Returns the p-value of the Kolmogorov-Smirnov test of `num_samples`
+/// samples, with the asymptotic distribution of the statistic and Stephens'
+/// correction for finite samples, which is conservative for discrete
+/// distributions, or `None` without samples.
+pub fn kolmogorov_smirnov_p_value(statistic: f64, num_samples: u64) -> Option<f64> {
+    if num_samples == 0 {
+        return None;
+    }
+    let sqrt_n = (num_samples as f64).sqrt();
+    let lambda = (sqrt_n + 0.12 + 0.11 / sqrt_n) * statistic;
+    // The series converges slowly for small lambdas, whose p-values are 1
+    // for all practical purposes.
+    if lambda < 0.2 {
+        return Some(1.0);
+    }
+    let p_value = 2.0
+        * (1..=100)
+            .map(|k| {
+                let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+                sign * (-2.0 * (k * k) as f64 * lambda * lambda).exp()
+            })
+            .sum::<f64>();
+    Some(p_value.clamp(0.0, 1.0))
+}
+
+/// Returns the numbers of cells of the dimensions of the mutual independence
+/// test of the counts, which are runs of consecutive values, as in
+/// [`merged_cell`], and the smallest expected count of the cells.
+///
+/// The values are the cells, unless their expected counts are below
+/// [`MIN_EXPECTED_COUNT`], in which case the number of cells of the dimension
+/// that has the most is halved, until they are at least that, or until no
+/// dimension has more than two.
+fn mutual_independence_test_num_cells(num_cooccurrences: &NumCooccurrences) -> (Vec<usize>, f64) {
+    let mut marginal_counts = dimension_lens(num_cooccurrences)
+        .map(|len| vec![0; len])
+        .collect::<Vec<_>>();
+    num_cooccurrences
+        .counts()
+        .iter()
+        .enumerate()
+        .for_each(|(index, &o)| {
+            iter::zip(num_cooccurrences.offsets(index), &mut marginal_counts)
+                .for_each(|(offset, counts)| counts[offset] += o);
+        });
+    let num_samples = num_cooccurrences.counts().iter().sum::<u64>() as f64;
+    // The expected count of a cell is the product of the marginal
+    // probabilities of its values, so the smallest one is that of the
+    // smallest marginal counts.
+    let min_expected_count = |num_cells: &[usize]| {
+        iter::zip(&marginal_counts, num_cells)
+            .map(|(counts, &num_cells)| {
+                let mut cell_counts = vec![0; num_cells];
+                counts.iter().enumerate().for_each(|(offset, &o)| {
+                    cell_counts[merged_cell(offset, counts.len(), num_cells)] += o
+                });
+                *cell_counts.iter().min().unwrap() as f64 / num_samples
+            })
+            .product::<f64>()
+            * num_samples
+    };
+
+    let mut num_cells = dimension_lens(num_cooccurrences).collect::<Vec<_>>();
+    loop {
+        let min_expected_count = min_expected_count(&num_cells);
+        let (dimension, &max_num_cells) = num_cells
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &num_cells)| num_cells)
+            .unwrap();
+        // Without samples, the expected count is NaN, and is never enough.
+        if min_expected_count >= MIN_EXPECTED_COUNT || max_num_cells <= 2 {
+            return (num_cells, min_expected_count);
+        }
+        num_cells[dimension] = max_num_cells.div_ceil(2);
+    }
+}
+
+/// The p-value of the mutual independence test whose statistic is the sum of
+/// `term` over the counts of cells, of which the dimensions have `num_cells`.
+fn mutual_independence_test_p_value(
+    num_cooccurrences: &NumCooccurrences,
+    num_cells: &[usize],
+    term: fn(f64, f64) -> f64,
+) -> f64 {
+    let (statistic, _) = mutual_independence_test_statistic(num_cooccurrences, num_cells, term);
+
+    let degrees_of_freedom = (num_cells.iter().product::<usize>() - 1)
+        - (num_cells.iter().map(|len| len - 1).sum::<usize>());
+
+    assert!(degrees_of_freedom > 0);
+    1.0 - ChiSquared::new(degrees_of_freedom as f64)
+        .unwrap()
+        .cdf(statistic)
+}
+
+/// Returns the largest Cramér's V of the pairs of dimensions that have more
+/// than one value, `sqrt(chi2 / (n * (min(k, l) - 1)))` for dimensions of `k`
+/// and `l` values, which is 1 when a dimension determines another, and is the
+/// phi coefficient of 2x2 tables. It is 0 without such pairs or counts.
+fn cramers_v(num_cooccurrences: &NumCooccurrences) -> f64 {
+    let lens = dimension_lens(num_cooccurrences).collect::<Vec<_>>();
+    let strides = lens
+        .iter()
+        .scan(1, |stride, &len| {
+            let dimension_stride = *stride;
+            *stride *= len;
+            Some(dimension_stride)
+        })
+        .collect::<Vec<_>>();
+    let num_samples = num_cooccurrences.counts().iter().sum::<u64>() as f64;
+    if num_samples == 0.0 {
+        return 0.0;
+    }
+    (0..lens.len())
+        .filter(|&dimension| lens[dimension] > 1)
+        .tuple_combinations()
+        .map(|(first, second)| {
+            let mut pair_counts = vec![0.0; lens[first] * lens[second]];
+            let mut first_counts = vec![0.0; lens[first]];
+            let mut second_counts = vec![0.0; lens[second]];
+            for (index, &o) in num_cooccurrences.counts().iter().enumerate() {
+                let first_offset = index / strides[first] % lens[first];
+                let second_offset = index / strides[second] % lens[second];
+                pair_counts[first_offset * lens[second] + second_offset] += o as f64;
+                first_counts[first_offset] += o as f64;
+                second_counts[second_offset] += o as f64;
+            }
+            let statistic = pair_counts
+                .iter()
+                .enumerate()
+                .map(|(cell, &o)| {
+                    let e = first_counts[cell / lens[second]] * second_counts[cell % lens[second]]
+                        / num_samples;
+                    if e > 0.0 {
+                        chi_squared_term(o, e)
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f64>();
+            (statistic / (num_samples * (lens[first].min(lens[second]) - 1) as f64)).sqrt()
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Returns the statistic of the mutual independence test that is the sum of
+/// `term` over the counts of cells, of which the dimensions have `num_cells`,
+/// and the number of samples.
+fn mutual_independence_test_statistic(
+    num_cooccurrences: &NumCooccurrences,
+    num_cells: &[usize],
+    term: fn(f64, f64) -> f64,
+) -> (f64, f64) {
+    let lens = dimension_lens(num_cooccurrences).collect::<Vec<_>>();
+    let mut cell_counts = vec![0.0; num_cells.iter().product()];
+    let mut marginal_counts = num_cells
+        .iter()
+        .map(|&num_cells| vec![0.0; num_cells])
+        .collect::<Vec<_>>();
+    num_cooccurrences
+        .counts()
+        .iter()
+        .enumerate()
+        .for_each(|(index, &o)| {
+            let mut cell_index = 0;
+            let mut stride = 1;
+            for ((offset, (&len, &num_cells)), marginal_counts) in num_cooccurrences
+                .offsets(index)
+                .zip(iter::zip(&lens, num_cells))
+                .zip(&mut marginal_counts)
+            {
+                let cell = merged_cell(offset, len, num_cells);
+                marginal_counts[cell] += o as f64;
+                cell_index += cell * stride;
+                stride *= num_cells;
+            }
+            cell_counts[cell_index] += o as f64;
+        });
+    let num_samples = cell_counts.iter().sum::<f64>();
+
+    let statistic = cell_counts
+        .iter()
+        .enumerate()
+        .map(|(mut cell_index, &o)| {
+            let joint_probability = iter::zip(&marginal_counts, num_cells)
+                .map(|(marginal_counts, &num_cells)| {
+                    let cell = cell_index % num_cells;
+                    cell_index /= num_cells;
+                    marginal_counts[cell] / num_samples
+                })
+                .product::<f64>();
+            let expected_count = joint_probability * num_samples;
+            // The cells of values that did not occur have no counts either.
+            if expected_count > 0.0 {
+                term(o, expected_count)
+            } else {
+                0.0
+            }
+        })
+        .sum::<f64>();
+    (statistic, num_samples)
+}
+
+/// Returns the numbers of values of the dimensions of the counts.
+fn dimension_lens(
+    num_cooccurrences: &NumCooccurrences,
+) -> impl Iterator<Item = usize> + Clone + '_ {
+    num_cooccurrences
+        .dimensions()
+        .iter()
+        .map(|dimension| (dimension.end() - dimension.start() + 1) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    fn num_cooccurrences(lens: &[u64], counts: &[u64]) -> NumCooccurrences {
+        let mut num_cooccurrences =
+            NumCooccurrences::new(lens.iter().map(|&len| 0..=len - 1).collect());
+        for (index, &count) in counts.iter().enumerate() {
+            (0..count).for_each(|_| num_cooccurrences.record(index));
+        }
+        num_cooccurrences
+    }
+
+    #[test]
+    fn wilson_interval_of_newcombe() {
+        // Newcombe (1998), Two-sided confidence intervals for the single
+        // proportion: comparison of seven methods, Table I.
+        for (num_successes, num_trials, low, high) in [
+            (81, 263, 0.2553, 0.3662),
+            (15, 148, 0.0624, 0.1605),
+            (0, 20, 0.0, 0.1611),
+            (1, 29, 0.0061, 0.1718),
+        ] {
+            let (actual_low, actual_high) = wilson_interval(num_successes, num_trials, 0.95);
+            assert_close(actual_low, low, 5e-5);
+            assert_close(actual_high, high, 5e-5);
+        }
+    }
+
+    #[test]
+    fn binomial_test_p_value_of_two_standard_deviations() {
+        assert_close(binomial_test_p_value(60, 100, 0.5), 0.0455003, 1e-6);
+        assert_close(binomial_test_p_value(40, 100, 0.5), 0.0455003, 1e-6);
+        assert_close(binomial_test_p_value(50, 100, 0.5), 1.0, 1e-12);
+    }
+
+    #[test]
+    fn uniformity_test_num_cells_merges_values_of_small_expected_counts() {
+        assert_eq!(uniformity_test_num_cells(50, 10), 10);
+        assert_eq!(uniformity_test_num_cells(49, 10), 5);
+        assert_eq!(uniformity_test_num_cells(12, 10), 2);
+        assert_eq!(uniformity_test_num_cells(10, 10), 2);
+        assert_eq!(uniformity_test_num_cells(0, 10), 0);
+    }
+
+    #[test]
+    fn merged_cells_differ_in_length_by_at_most_one() {
+        let cells = (0..10)
+            .map(|offset| merged_cell(offset, 10, 3))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, [0, 0, 0, 0, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn chi_squared_uniformity_test_of_a_die() {
+        // 60 rolls of a die, whose chi-squared statistic is 13.4 with 5
+        // degrees of freedom.
+        let num_occurrences = [5, 8, 9, 8, 10, 20];
+        assert_close(
+            chi_squared_uniformity_test_p_value(&num_occurrences),
+            0.0199052,
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn chi_squared_uniformity_test_of_merged_cells() {
+        // 30 samples of 12 values are merged into the 6 cells of pairs of
+        // values, whose counts are 2, 4, 5, 4, 5, 10, and whose chi-squared
+        // statistic is 7.2 with 5 degrees of freedom.
+        let num_occurrences = [1, 1, 0, 4, 5, 0, 2, 2, 4, 1, 3, 7];
+        let (p_value, num_cells) = chi_squared_uniformity_test(&num_occurrences);
+        assert_eq!(num_cells, 6);
+        assert_close(p_value, 0.2061859, 1e-6);
+    }
+
+    #[test]
+    fn g_test_of_a_die() {
+        // The G statistic of the same rolls is 11.757.
+        let nonzero_num_occurrences_by_value = [5, 8, 9, 8, 10, 20]
+            .into_iter()
+            .enumerate()
+            .collect::<Vec<_>>();
+        assert_close(
+            uniformity_test_p_value(&nonzero_num_occurrences_by_value, 6, 6, g_term),
+            0.0382685,
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn mutual_independence_test_of_a_2x2_table() {
+        // The chi-squared statistic of [[10, 20], [30, 40]] is 0.794 with 1
+        // degree of freedom, and its phi coefficient is -0.089.
+        let num_cooccurrences = num_cooccurrences(&[2, 2], &[10, 30, 20, 40]);
+        assert_close(
+            mutual_independence_test_p_value(&num_cooccurrences, &[2, 2], chi_squared_term),
+            0.3729985,
+            1e-6,
+        );
+        assert_close(cramers_v(&num_cooccurrences), 0.0890871, 1e-6);
+    }
+
+    #[test]
+    fn cramers_v_of_dependent_dimensions() {
+        // The three dimensions are equal.
+        let counts = (0..27)
+            .map(|index| if index % 13 == 0 { 10 } else { 0 })
+            .collect::<Vec<_>>();
+        assert_close(
+            cramers_v(&num_cooccurrences(&[3, 3, 3], &counts)),
+            1.0,
+            1e-12,
+        );
+        assert_eq!(cramers_v(&num_cooccurrences(&[3, 3, 3], &[0; 27])), 0.0);
+    }
+
+    #[test]
+    fn mutual_independence_test_num_cells_halves_the_largest_dimension() {
+        let num_cells = mutual_independence_test_num_cells(&num_cooccurrences(&[4, 4], &[5; 16]));
+        assert_eq!(num_cells, (vec![4, 4], 5.0));
+        // With 3 samples per value, the last of the largest dimensions is
+        // halved.
+        let num_cells = mutual_independence_test_num_cells(&num_cooccurrences(&[4, 4], &[3; 16]));
+        assert_eq!(num_cells.0, [4, 2]);
+        assert_close(num_cells.1, 6.0, 1e-12);
+        // Without samples, the dimensions are halved down to two cells.
+        let num_cells = mutual_independence_test_num_cells(&num_cooccurrences(&[4, 4], &[0; 16]));
+        assert_eq!(num_cells.0, [2, 2]);
+        assert!(num_cells.1.is_nan());
+    }
+
+    #[test]
+    fn kolmogorov_smirnov_p_values_of_critical_values() {
+        // The asymptotic critical values of the Kolmogorov distribution.
+        let num_samples = 10000;
+        let sqrt_n = 100.0;
+        for (lambda, p_value) in [(1.2239, 0.10), (1.3581, 0.05), (1.6276, 0.01)] {
+            let statistic = lambda / (sqrt_n + 0.12 + 0.11 / sqrt_n);
+            assert_close(
+                kolmogorov_smirnov_p_value(statistic, num_samples).unwrap(),
+                p_value,
+                1e-4,
+            );
+        }
+        assert_eq!(kolmogorov_smirnov_p_value(0.001, num_samples), Some(1.0));
+        assert_eq!(kolmogorov_smirnov_p_value(0.5, 0), None);
+    }
+
+    #[test]
+    fn kolmogorov_smirnov_uniformity_statistic_of_extreme_samples() {
+        assert_eq!(
+            kolmogorov_smirnov_uniformity_statistic(vec![(0, 4)], 4),
+            0.75
+        );
+        assert_eq!(
+            kolmogorov_smirnov_uniformity_statistic(vec![(3, 4)], 4),
+            0.75
+        );
+        let num_occurrences = vec![(2, 1), (0, 1), (3, 1), (1, 1)];
+        assert_eq!(
+            kolmogorov_smirnov_uniformity_statistic(num_occurrences, 4),
+            0.0
+        );
+    }
+
+    #[test]
+    fn exact_multinomial_test_of_two_values() {
+        // Of the 2^n equally likely sequences of n samples of 2 values, 2
+        // have all their samples on the same value.
+        let num_samples = 100_000;
+        for (num_occurrences, p_value) in [([3, 0], 0.25), ([5, 0], 0.0625), ([0, 6], 0.03125)] {
+            assert_close(
+                monte_carlo_multinomial_uniformity_test_p_value(&num_occurrences, num_samples),
+                p_value,
+                0.01,
+            );
+        }
+        // All the samples are at least as far from uniform as the most
+        // uniform counts.
+        assert_eq!(
+            monte_carlo_multinomial_uniformity_test_p_value(&[2, 1], num_samples),
+            1.0
+        );
+    }
+}
@@ -0,0 +1,650 @@
+use std::{cell::Cell, fmt, fs, io, path::PathBuf, sync::Arc};
+
+use rand::{Rng, RngCore};
+use rand_distr::Zipf;
+
+use crate::rng::thread_rng;
+
+/// The distribution of the keys that experiments hash.
+///
+/// Structured keys are written to the start of the input, which is padded
+/// with zeros or truncated to the input size. Integers are little-endian, so
+/// that truncating them keeps their varying bytes, while IPv4 addresses and
+/// UUIDs are big-endian, as they usually are in memory.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyDistribution {
+    /// Uniformly random bytes.
+    #[default]
+    Uniform,
+    /// Consecutive 64-bit integers, from a random one for each thread.
+    Sequential,
+    /// Increasing timestamps in microseconds since the Unix epoch, a
+    /// thousand per second on average.
+    Timestamps,
+    /// Random IPv4 addresses of the private network 10.0.0.0/8.
+    Ipv4,
+    /// UUIDv7s, whose first 48 bits are increasing timestamps in
+    /// milliseconds, and whose other bits are random apart from the version
+    /// and variant bits.
+    Uuidv7,
+    /// 64-bit integers whose frequencies follow Zipf's law, as the keys of
+    /// skewed traffic do.
+    Zipf,
+    /// Uniformly random 64-bit integers below a small number of IDs, e.g.,
+    /// barely more than the number of values, as the keys of small ID spaces
+    /// are.
+    SmallIds,
+    /// Uniformly random strings of the input size, whose bytes are the first
+    /// letters of the lowercase alphabet, as low-entropy textual keys are.
+    Alphabet,
+}
+
+/// The format of a file of keys.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeysFileFormat {
+    /// A key per line, without the line feed or the carriage return and line
+    /// feed that ends it. Empty lines are skipped.
+    #[default]
+    Lines,
+    /// Keys preceded by their lengths, as 32-bit little-endian integers, which
+    /// may be zero.
+    LengthPrefixed,
+}
+
+/// The order in which the keys of a file are hashed.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeysFileOrder {
+    /// In the order of the file, from a random key for each thread, looping
+    /// back to the first key at the end of the file.
+    #[default]
+    Loop,
+    /// Uniformly at random, with replacement.
+    Sample,
+}
+
+/// The options of the keys of experiments.
+#[derive(clap::Args, Clone, Debug)]
+pub struct KeyOptions {
+    /// The distribution of the keys.
+    #[clap(long, value_enum, default_value_t)]
+    key_distribution: KeyDistribution,
+    /// The exponent of the Zipf distribution of keys, the larger the more
+    /// skewed; the most frequent key makes up about `1 / ln(n)` of the keys
+    /// for an exponent of 1 and `n` keys.
+    #[clap(long, default_value_t = 1.0, value_parser = parse_zipf_exponent)]
+    zipf_exponent: f64,
+    /// The number of distinct keys of the Zipf distribution of keys.
+    #[clap(long, default_value_t = 1_000_000, value_parser = clap::value_parser!(u64).range(1..))]
+    zipf_num_keys: u64,
+    /// The number of distinct keys of the small-ids distribution of keys,
+    /// which are the integers below it.
+    #[clap(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+    num_small_ids: u64,
+    /// The number of letters of the alphabet distribution of keys, i.e., the
+    /// number of distinct bytes of the keys.
+    #[clap(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..=26))]
+    alphabet_size: u8,
+    /// A file of keys to hash instead of generating them, e.g., anonymized
+    /// production keys. Keys are padded with zeros or truncated to the
+    /// input size, which should be the length of the longest key.
+    #[clap(long, conflicts_with = "key_distribution")]
+    keys_file: Option<PathBuf>,
+    #[clap(long, value_enum, default_value_t, requires = "keys_file")]
+    keys_file_format: KeysFileFormat,
+    #[clap(long, value_enum, default_value_t, requires = "keys_file")]
+    keys_file_order: KeysFileOrder,
+}
+
+impl KeyOptions {
+    /// Returns the keys of the options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file of keys cannot be read, is malformed or has no keys.
+    pub fn keys(&self) -> Keys {
+        Keys {
+            distribution: self.key_distribution,
+            file: self.keys_file.as_ref().map(|path| {
+                fs::read(path)
+                    .and_then(|bytes| {
+                        FileKeys::new(bytes, self.keys_file_format, self.keys_file_order)
+                    })
+                    .unwrap_or_else(|err| panic!("Cannot read {}: {err}", path.display()))
+            }),
+            zipf: (self.key_distribution == KeyDistribution::Zipf).then(|| ZipfKeys {
+                ranks: Zipf::new(self.zipf_num_keys, self.zipf_exponent)
+                    .expect("The Zipf options are checked when parsed"),
+                salt: thread_rng().gen(),
+            }),
+            num_small_ids: self.num_small_ids,
+            alphabet_size: self.alphabet_size,
+        }
+    }
+
+    /// Returns the suffix of the names of the output files, which is empty
+    /// for uniform keys, so that the names of their files are unchanged.
+    pub fn file_name_suffix(&self) -> String {
+        if let Some(path) = &self.keys_file {
+            return format!(
+                "_keys_from_{}_{}",
+                path.file_stem().unwrap_or_default().to_string_lossy(),
+                match self.keys_file_order {
+                    KeysFileOrder::Loop => "looped",
+                    KeysFileOrder::Sample => "sampled",
+                }
+            );
+        }
+        match self.key_distribution {
+            KeyDistribution::Uniform => String::new(),
+            KeyDistribution::Zipf => {
+                format!("_zipf_{}_{}_keys", self.zipf_exponent, self.zipf_num_keys)
+            }
+            KeyDistribution::SmallIds => format!("_{}_small_ids_keys", self.num_small_ids),
+            KeyDistribution::Alphabet => format!("_{}_letter_alphabet_keys", self.alphabet_size),
+            distribution => format!("_{distribution}_keys"),
+        }
+    }
+}
+
+/// Parses an exponent of the Zipf distribution, which is a finite
+/// non-negative number, an exponent of 0 making the keys uniform.
+fn parse_zipf_exponent(arg: &str) -> Result<f64, String> {
+    let exponent = arg.parse::<f64>().map_err(|err| err.to_string())?;
+    if exponent.is_finite() && exponent >= 0.0 {
+        Ok(exponent)
+    } else {
+        Err("expected a finite non-negative number".to_owned())
+    }
+}
+
+/// A generator of keys, which is shared by the threads of an experiment.
+/// The default keys are uniformly random.
+#[derive(Clone, Debug, Default)]
+pub struct Keys {
+    distribution: KeyDistribution,
+    zipf: Option<ZipfKeys>,
+    /// The keys of a file, which replace those of the distribution.
+    file: Option<FileKeys>,
+    num_small_ids: u64,
+    alphabet_size: u8,
+}
+
+#[derive(Clone, Debug)]
+struct ZipfKeys {
+    ranks: Zipf<f64>,
+    /// Mixed with the ranks, so that the frequent keys are arbitrary, but the
+    /// same for all threads.
+    salt: u64,
+}
+
+/// The keys of a file, which are loaded in memory, and shared by the
+/// threads.
+#[derive(Clone, Debug)]
+struct FileKeys {
+    bytes: Arc<[u8]>,
+    /// The end of every key in `bytes`, which is the start of the next one.
+    ends: Arc<[usize]>,
+    order: KeysFileOrder,
+}
+
+impl FileKeys {
+    /// Parses the keys of the bytes of a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a length or a key is truncated, or if there are no
+    /// keys.
+    fn new(mut bytes: Vec<u8>, format: KeysFileFormat, order: KeysFileOrder) -> io::Result<Self> {
+        let invalid_data = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut ends = Vec::new();
+        // The line endings or the lengths are removed, so that keys are
+        // contiguous.
+        let (mut read, mut len) = (0, 0);
+        while read < bytes.len() {
+            let key_len = match format {
+                KeysFileFormat::Lines => {
+                    let line_len = bytes[read..]
+                        .iter()
+                        .position(|&byte| byte == b'\n')
+                        .unwrap_or(bytes.len() - read);
+                    let key_len =
+                        line_len - usize::from(bytes[read..read + line_len].ends_with(b"\r"));
+                    bytes.copy_within(read..read + key_len, len);
+                    read += line_len + 1;
+                    if key_len == 0 {
+                        continue;
+                    }
+                    key_len
+                }
+                KeysFileFormat::LengthPrefixed => {
+                    let len_bytes = bytes.get(read..read + 4).ok_or_else(|| {
+                        invalid_data(format!("Truncated length of the key at byte {read}"))
+                    })?;
+                    let key_len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+                    if read + 4 + key_len > bytes.len() {
+                        return Err(invalid_data(format!("Truncated key at byte {read}")));
+                    }
+                    bytes.copy_within(read + 4..read + 4 + key_len, len);
+                    read += 4 + key_len;
+                    key_len
+                }
+            };
+            len += key_len;
+            ends.push(len);
+        }
+        if ends.is_empty() {
+            return Err(invalid_data("No keys".to_owned()));
+        }
+        bytes.truncate(len);
+        Ok(Self {
+            bytes: bytes.into(),
+            ends: ends.into(),
+            order,
+        })
+    }
+
+    fn fill(&self, bytes: &mut [u8]) {
+        let index = match self.order {
+            KeysFileOrder::Loop => NEXT_FILE_KEY_INDEX.with(|next| {
+                let index = next
+                    .get()
+                    .unwrap_or_else(|| thread_rng().gen_range(0..self.ends.len()));
+                next.set(Some((index + 1) % self.ends.len()));
+                index
+            }),
+            KeysFileOrder::Sample => thread_rng().gen_range(0..self.ends.len()),
+        };
+        let start = index.checked_sub(1).map_or(0, |i| self.ends[i]);
+        write_truncated(bytes, &self.bytes[start..self.ends[index]]);
+    }
+}
+
+thread_local! {
+    static NEXT_SEQUENTIAL_KEY: Cell<Option<u64>> = const { Cell::new(None) };
+    static NEXT_TIMESTAMP_MICROS: Cell<Option<u64>> = const { Cell::new(None) };
+    static NEXT_FILE_KEY_INDEX: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+impl Keys {
+    /// Restarts the keys of the current thread, which are then drawn as if
+    /// from a new thread, e.g., after changing the stream of its generator.
+    pub fn restart_thread() {
+        NEXT_SEQUENTIAL_KEY.set(None);
+        NEXT_TIMESTAMP_MICROS.set(None);
+        NEXT_FILE_KEY_INDEX.set(None);
+    }
+
+    /// Writes the next key to `bytes`.
+    ///
+    /// The keys that are not random are increasing for each thread, so that
+    /// concurrent experiments hash distinct keys.
+    #[inline]
+    pub fn fill(&self, bytes: &mut [u8]) {
+        if let Some(file) = &self.file {
+            return file.fill(bytes);
+        }
+        match self.distribution {
+            KeyDistribution::Uniform => thread_rng().fill_bytes(bytes),
+            KeyDistribution::Sequential => {
+                let key = NEXT_SEQUENTIAL_KEY.with(|next| {
+                    let key = next.get().unwrap_or_else(|| thread_rng().gen());
+                    next.set(Some(key.wrapping_add(1)));
+                    key
+                });
+                write_truncated(bytes, &key.to_le_bytes());
+            }
+            KeyDistribution::Timestamps => {
+                write_truncated(bytes, &next_timestamp_micros().to_le_bytes())
+            }
+            KeyDistribution::Ipv4 => {
+                let host = thread_rng().gen_range(0..1_u32 << 24);
+                write_truncated(bytes, &(10 << 24 | host).to_be_bytes());
+            }
+            KeyDistribution::Uuidv7 => {
+                let timestamp_millis = next_timestamp_micros() / 1000;
+                let random = thread_rng().gen::<u128>();
+                let uuid = (timestamp_millis as u128 & ((1 << 48) - 1)) << 80
+                    | 0x7 << 76
+                    | (random & 0xFFF) << 64
+                    | 0b10 << 62
+                    | random >> 66;
+                write_truncated(bytes, &uuid.to_be_bytes());
+            }
+            KeyDistribution::Zipf => {
+                let zipf = self.zipf.as_ref().unwrap();
+                let rank = thread_rng().sample(zipf.ranks) as u64;
+                write_truncated(bytes, &mix(rank ^ zipf.salt).to_le_bytes());
+            }
+            KeyDistribution::SmallIds => {
+                let id = thread_rng().gen_range(0..self.num_small_ids);
+                write_truncated(bytes, &id.to_le_bytes());
+            }
+            KeyDistribution::Alphabet => bytes
+                .iter_mut()
+                .for_each(|byte| *byte = b'a' + thread_rng().gen_range(0..self.alphabet_size)),
+        }
+    }
+}
+
+impl fmt::Display for KeyDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                KeyDistribution::Uniform => "uniform",
+                KeyDistribution::Sequential => "sequential",
+                KeyDistribution::Timestamps => "timestamps",
+                KeyDistribution::Ipv4 => "ipv4",
+                KeyDistribution::Uuidv7 => "uuidv7",
+                KeyDistribution::Zipf => "zipf",
+                KeyDistribution::SmallIds => "small-ids",
+                KeyDistribution::Alphabet => "alphabet",
+            }
+        )
+    }
+}
+
+/// Returns the next timestamp of the thread, which starts at a random time
+/// of the 2020s and increases by up to two milliseconds at a time.
+fn next_timestamp_micros() -> u64 {
+    // 2020-01-01 and 2030-01-01.
+    const START_MICROS: u64 = 1_577_836_800_000_000;
+    const END_MICROS: u64 = 1_893_456_000_000_000;
+    NEXT_TIMESTAMP_MICROS.with(|next| {
+        let timestamp = next
+            .get()
+            .unwrap_or_else(|| thread_rng().gen_range(START_MICROS..END_MICROS));
+        next.set(Some(timestamp + thread_rng().gen_range(1..2000)));
+        timestamp
+    })
+}
+
+/// The finalizer of MurmurHash3, which is a bijection.
+pub fn mix(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xFF51AFD7ED558CCD);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xC4CEB9FE1A85EC53);
+    x ^ (x >> 33)
+}
+
+fn write_truncated(bytes: &mut [u8], key: &[u8]) {
+    let len = bytes.len().min(key.len());
+    bytes[..len].copy_from_slice(&key[..len]);
+    bytes[len..].fill(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+
+    fn file_keys(bytes: &[u8], format: KeysFileFormat) -> Vec<Vec<u8>> {
+        let keys = FileKeys::new(bytes.to_vec(), format, KeysFileOrder::Loop).unwrap();
+        let starts = [0].into_iter().chain(keys.ends.iter().copied());
+        starts
+            .zip(keys.ends.iter())
+            .map(|(start, &end)| keys.bytes[start..end].to_vec())
+            .collect()
+    }
+
+    fn length_prefixed(keys: &[&[u8]]) -> Vec<u8> {
+        keys.iter()
+            .flat_map(|key| {
+                (key.len() as u32)
+                    .to_le_bytes()
+                    .into_iter()
+                    .chain(key.to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines_keys_file() {
+        let keys: [&[u8]; 3] = [b"a", b"bc", b"def"];
+        for bytes in [
+            &b"a\nbc\ndef"[..],
+            b"a\nbc\ndef\n",
+            b"a\r\nbc\r\ndef\r\n",
+            b"\na\n\n\nbc\r\n\r\ndef\n\n",
+        ] {
+            assert_eq!(file_keys(bytes, KeysFileFormat::Lines), keys, "{bytes:?}");
+        }
+        // Only line endings are removed.
+        assert_eq!(
+            file_keys(b" a\tb \rc\r\r\n", KeysFileFormat::Lines),
+            [b" a\tb \rc\r"]
+        );
+    }
+
+    #[test]
+    fn length_prefixed_keys_file() {
+        let keys: [&[u8]; 5] = [b"a", b"", b"b\nc\r\n", b"", &[0; 300]];
+        assert_eq!(
+            file_keys(&length_prefixed(&keys), KeysFileFormat::LengthPrefixed),
+            keys
+        );
+        assert_eq!(
+            file_keys(&length_prefixed(&[b""]), KeysFileFormat::LengthPrefixed),
+            [b""]
+        );
+    }
+
+    #[test]
+    fn invalid_keys_files() {
+        let bytes = length_prefixed(&[b"ab", b"cd"]);
+        for (bytes, format, message) in [
+            (&b""[..], KeysFileFormat::Lines, "No keys"),
+            (b"\n\r\n\n", KeysFileFormat::Lines, "No keys"),
+            (b"", KeysFileFormat::LengthPrefixed, "No keys"),
+            (
+                &bytes[..8],
+                KeysFileFormat::LengthPrefixed,
+                "Truncated length of the key at byte 6",
+            ),
+            (
+                &bytes[..11],
+                KeysFileFormat::LengthPrefixed,
+                "Truncated key at byte 6",
+            ),
+        ] {
+            let err = FileKeys::new(bytes.to_vec(), format, KeysFileOrder::Loop).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn looped_keys_file() {
+        let keys = FileKeys::new(
+            b"0\n1\n2\n3\n4".to_vec(),
+            KeysFileFormat::Lines,
+            KeysFileOrder::Loop,
+        )
+        .unwrap();
+        for _ in 0..10 {
+            Keys::restart_thread();
+            let mut bytes = [0; 2];
+            keys.fill(&mut bytes);
+            let first = bytes[0] - b'0';
+            for i in 1..12 {
+                keys.fill(&mut bytes);
+                // The keys are padded with zeros to the input size.
+                assert_eq!(bytes, [b'0' + (first + i) % 5, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn sampled_keys_file() {
+        let keys = FileKeys::new(
+            length_prefixed(&[b"0", b"1", b"2", b"3", b"4"]),
+            KeysFileFormat::LengthPrefixed,
+            KeysFileOrder::Sample,
+        )
+        .unwrap();
+        let mut counts = [0; 5];
+        let mut bytes = [0];
+        for _ in 0..10_000 {
+            keys.fill(&mut bytes);
+            counts[usize::from(bytes[0] - b'0')] += 1;
+        }
+        // 10 standard deviations.
+        assert!(
+            counts.iter().all(|&count| (1600..=2400).contains(&count)),
+            "{counts:?}"
+        );
+    }
+
+    /// Returns the keys of `distribution` that threads draw in turn, as
+    /// integers of their bytes.
+    fn thread_keys(distribution: KeyDistribution, parse: fn([u8; 16]) -> u128) -> Vec<Vec<u128>> {
+        let keys = Keys {
+            distribution,
+            ..Keys::default()
+        };
+        std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        (0..1000)
+                            .map(|_| {
+                                let mut bytes = [0; 16];
+                                keys.fill(&mut bytes);
+                                parse(bytes)
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// 2020-01-01 to 2030-01-01, when the timestamps of threads start.
+    const TIMESTAMPS_MICROS: Range<u128> = 1_577_836_800_000_000..1_893_456_000_000_000;
+
+    #[test]
+    fn sequential_keys_are_consecutive_on_each_thread() {
+        for keys in thread_keys(KeyDistribution::Sequential, u128::from_le_bytes) {
+            assert!(keys.iter().all(|&key| key <= u128::from(u64::MAX)));
+            for pair in keys.windows(2) {
+                assert_eq!(pair[1], (pair[0] + 1) % (1 << 64));
+            }
+        }
+    }
+
+    #[test]
+    fn timestamps_are_increasing_on_each_thread() {
+        for keys in thread_keys(KeyDistribution::Timestamps, u128::from_le_bytes) {
+            assert!(TIMESTAMPS_MICROS.contains(&keys[0]), "{}", keys[0]);
+            for pair in keys.windows(2) {
+                assert!((1..2000).contains(&(pair[1] - pair[0])), "{pair:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn uuidv7_keys() {
+        let threads = thread_keys(KeyDistribution::Uuidv7, u128::from_be_bytes);
+        let timestamp_millis = |uuid: u128| uuid >> 80;
+        let random_bits = |uuid: u128| (uuid >> 64 & 0xFFF) << 62 | (uuid & ((1 << 62) - 1));
+        for uuids in &threads {
+            let first_millis = timestamp_millis(uuids[0]);
+            assert!(
+                (TIMESTAMPS_MICROS.start / 1000..TIMESTAMPS_MICROS.end / 1000)
+                    .contains(&first_millis),
+                "{first_millis}"
+            );
+            for &uuid in uuids {
+                assert_eq!(uuid >> 76 & 0xF, 7, "version of {uuid:032x}");
+                assert_eq!(uuid >> 62 & 0b11, 0b10, "variant of {uuid:032x}");
+            }
+            for pair in uuids.windows(2) {
+                let elapsed_millis = timestamp_millis(pair[1]) - timestamp_millis(pair[0]);
+                assert!(elapsed_millis <= 2, "{pair:x?}");
+            }
+            // A thousand keys make up to two seconds.
+            assert!(timestamp_millis(uuids[999]) - first_millis <= 2000);
+        }
+        // The 74 other bits are random: each one is set in about half the
+        // keys, within 10 standard deviations.
+        let uuids: Vec<u128> = threads.concat();
+        for bit in 0..74 {
+            let num_set = uuids
+                .iter()
+                .filter(|&&uuid| random_bits(uuid) >> bit & 1 == 1)
+                .count();
+            assert!(
+                (1684..=2316).contains(&num_set),
+                "bit {bit} is set {num_set} times"
+            );
+        }
+    }
+
+    #[derive(clap::Parser, Debug)]
+    struct Args {
+        #[clap(flatten)]
+        key_options: KeyOptions,
+    }
+
+    #[test]
+    fn zipf_options() {
+        use clap::Parser;
+
+        for args in [
+            &["--zipf-num-keys", "0"][..],
+            &["--zipf-exponent=-1"],
+            &["--zipf-exponent", "NaN"],
+            &["--zipf-exponent", "inf"],
+        ] {
+            let err = Args::try_parse_from(["keys"].iter().chain(args)).unwrap_err();
+            assert_eq!(
+                err.kind(),
+                clap::error::ErrorKind::ValueValidation,
+                "{args:?}"
+            );
+        }
+        for args in [
+            &[][..],
+            &["--zipf-num-keys", "1"],
+            &["--zipf-exponent", "0"],
+            &["--zipf-exponent", "2.5"],
+        ] {
+            let options =
+                Args::try_parse_from(["keys", "--key-distribution", "zipf"].iter().chain(args))
+                    .unwrap()
+                    .key_options;
+            let mut bytes = [0; 8];
+            options.keys().fill(&mut bytes);
+        }
+    }
+
+    #[test]
+    fn malformed_keys_file_panics() {
+        let path = std::env::temp_dir().join(format!("keys_{}", std::process::id()));
+        fs::write(&path, &length_prefixed(&[b"ab"])[..5]).unwrap();
+        let options = KeyOptions {
+            key_distribution: KeyDistribution::default(),
+            zipf_exponent: 1.0,
+            zipf_num_keys: 1_000_000,
+            num_small_ids: 1000,
+            alphabet_size: 4,
+            keys_file: Some(path.clone()),
+            keys_file_format: KeysFileFormat::LengthPrefixed,
+            keys_file_order: KeysFileOrder::Loop,
+        };
+        let result = std::panic::catch_unwind(|| options.keys());
+        fs::remove_file(&path).unwrap();
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(
+            message,
+            format!("Cannot read {}: Truncated key at byte 0", path.display())
+        );
+    }
+}
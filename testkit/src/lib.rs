@@ -0,0 +1,50 @@
+//! The statistical experiments of the Flip Hash benchmarks, e.g., tests of
+//! the uniformity, independence, consistency and avalanche of the hashes of
+//! keys, to run against any ranged hash function that implements
+//! [`Algorithm`](algo::Algorithm).
+//!
+//! An [`Experiment`](exp::Experiment) hashes keys and records what it
+//! observes in an [`Accumulator`](acc::Accumulator), whose counts can be
+//! merged across threads, written to checkpoints, and summarized:
+//!
+//! ```
+//! use std::{fmt, ops::RangeToInclusive};
+//!
+//! use flip_hash_testkit::{
+//!     algo::Algorithm,
+//!     exp::{Experiment, GoodnessOfFitTest, Regularity},
+//!     keys::Keys,
+//! };
+//!
+//! struct Modulo;
+//!
+//! impl fmt::Display for Modulo {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         write!(f, "Modulo")
+//!     }
+//! }
+//!
+//! impl Algorithm for Modulo {
+//!     fn hash(&self, key: &[u8], seed: u64, range: RangeToInclusive<u64>) -> u64 {
+//!         (u64::from_le_bytes(key.try_into().unwrap()) ^ seed) % (range.end + 1)
+//!     }
+//! }
+//!
+//! let experiment = Regularity::new(
+//!     ..=9,
+//!     8,
+//!     Keys::default(),
+//!     GoodnessOfFitTest::Chi2,
+//!     None,
+//!     None,
+//! );
+//! let accumulator = experiment.accumulate(&Modulo, 100_000);
+//! println!("{}", serde_json::to_string(&experiment.summary(&accumulator)).unwrap());
+//! ```
+
+pub mod acc;
+pub mod algo;
+pub mod checkpoint;
+pub mod exp;
+pub mod keys;
+pub mod rng;
@@ -0,0 +1,79 @@
+//! The random number generators of experiments, which are seeded from the
+//! entropy of the system, or from `--rng-seed` to reproduce results.
+//!
+//! Every thread has its own generator. When there is a seed, the generator
+//! of a thread is a ChaCha stream of the seed, and threads that use distinct
+//! streams draw independent numbers.
+
+use std::{cell::RefCell, sync::OnceLock};
+
+use rand::{Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+thread_local! {
+    static THREAD_RNG: RefCell<ChaCha12Rng> = RefCell::new(match SEED.get() {
+        Some(&seed) => stream(seed, 0),
+        None => ChaCha12Rng::from_entropy(),
+    });
+}
+
+/// Seeds the generators of all the threads with `seed`, the current one
+/// using stream 0.
+///
+/// # Panics
+///
+/// Panics if the seed is already set.
+pub fn set_seed(seed: u64) {
+    SEED.set(seed).expect("The seed is already set");
+    set_stream(0);
+}
+
+/// Makes the generator of the current thread that of `stream` of the seed,
+/// if there is one, so that what the thread draws only depends on the seed
+/// and on `stream`.
+pub fn set_stream(stream_index: u64) {
+    if let Some(&seed) = SEED.get() {
+        THREAD_RNG.with(|rng| *rng.borrow_mut() = stream(seed, stream_index));
+    }
+}
+
+fn stream(seed: u64, stream_index: u64) -> ChaCha12Rng {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    rng.set_stream(stream_index);
+    rng
+}
+
+/// Returns a handle to the generator of the current thread, to use instead
+/// of [`rand::thread_rng`].
+#[inline]
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng
+}
+
+/// A handle to the generator of the current thread.
+#[derive(Clone, Copy, Debug)]
+pub struct ThreadRng;
+
+impl RngCore for ThreadRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        THREAD_RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        THREAD_RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        THREAD_RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        THREAD_RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
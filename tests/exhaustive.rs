@@ -0,0 +1,59 @@
+//! Checks the properties of Flip Hash for all the keys of a small domain and
+//! all the ranges up to a given end, rather than for sampled inputs, to cover
+//! the corner cases of the bit manipulations.
+
+use flip_hash::flip_hash_64;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+const NUM_KEYS: u64 = 1 << 16;
+const MAX_RANGE_END: u64 = 1024;
+
+#[test]
+fn exhaustive() {
+    // counts[range_end][hash]
+    let mut counts = vec![vec![0_u64; MAX_RANGE_END as usize + 1]; MAX_RANGE_END as usize + 1];
+    for key in 0..NUM_KEYS {
+        let mut previous_hash = 0;
+        for range_end in 0..=MAX_RANGE_END {
+            let hash = flip_hash_64(key, ..=range_end);
+            assert!(hash <= range_end, "{key} in ..={range_end}: {hash}");
+            assert!(
+                hash == previous_hash || hash == range_end,
+                "{key} in ..={range_end}: {hash}, in ..={}: {previous_hash}",
+                range_end.saturating_sub(1)
+            );
+            counts[range_end as usize][hash as usize] += 1;
+            previous_hash = hash;
+        }
+    }
+
+    // Consecutive keys should be distributed uniformly too. With that many
+    // tests, some p-values are expected to be low, but not too many, and not
+    // too low.
+    let p_values = (1..=MAX_RANGE_END)
+        .map(|range_end| {
+            chi_squared_uniformity_test_p_value(&counts[range_end as usize][..=range_end as usize])
+        })
+        .collect::<Vec<_>>();
+    let num_low_p_values = p_values.iter().filter(|&&p_value| p_value < 0.01).count();
+    assert!(
+        num_low_p_values as f64 <= 0.02 * p_values.len() as f64,
+        "{num_low_p_values} p-values out of {} are lower than 0.01",
+        p_values.len()
+    );
+    let min_p_value = p_values.iter().copied().fold(f64::INFINITY, f64::min);
+    assert!(min_p_value > 1e-6, "{min_p_value}");
+}
+
+fn chi_squared_uniformity_test_p_value(num_occurrences: &[u64]) -> f64 {
+    let expected_count = num_occurrences.iter().sum::<u64>() as f64 / num_occurrences.len() as f64;
+
+    let statistic = num_occurrences
+        .iter()
+        .map(|&o| (o as f64 - expected_count).powi(2) / expected_count)
+        .sum::<f64>();
+
+    let degrees_of_freedom = num_occurrences.len() as f64 - 1.0;
+
+    1.0 - ChiSquared::new(degrees_of_freedom).unwrap().cdf(statistic)
+}
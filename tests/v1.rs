@@ -0,0 +1,189 @@
+//! Golden vectors of the functions of `flip_hash::v1`, whose hashes must
+//! never change. Do not update them: if they fail, the change that makes them
+//! fail belongs to a new version.
+
+use flip_hash::v1;
+use test_case::test_case;
+
+/// A key that is long enough for XXH3 to use SIMD instructions.
+#[cfg(any(
+    feature = "aes",
+    feature = "keyed",
+    feature = "murmur2",
+    feature = "xxh3"
+))]
+fn long_key() -> Vec<u8> {
+    (0..=255).chain(0..=99).collect()
+}
+
+#[test_case(0, 0, 0, 0)]
+#[test_case(0, 0, 1, 0)]
+#[test_case(1, 0, 1, 1)]
+#[test_case(15960427081186311679, 0, 17, 17)]
+#[test_case(15960427081186311679, 0, 18, 17)]
+#[test_case(10427592028180905159, 0, 1000, 452)]
+#[test_case(10427592028180905159, 1, 1000, 770)]
+#[test_case(42, 42, 1048576, 0)]
+#[test_case(42, 42, 1048575, 0)]
+#[test_case(18446744073709551615, 0, 18446744073709551615, 4668610942802735782)]
+#[test_case(18446744073709551615, 18446744073709551615, 18446744073709551615, 0)]
+#[test_case(0, 18446744073709551615, 9223372036854775808, 4668610942802735782)]
+#[test_case(7, 3, 9223372036854775807, 3517858511499793282)]
+#[test_case(123456789, 987654321, 4294967296, 2336534667)]
+#[test_case(123456789, 987654321, 4294967297, 2336534667)]
+#[test_case(2718281828459045235, 3141592653589793238, 99999, 56706)]
+#[test_case(1, 2, 3, 3)]
+#[test_case(3, 2, 1, 1)]
+#[test_case(1099511627776, 0, 12, 9)]
+#[test_case(3735928559, 3405691582, 65535, 39431)]
+fn flip_hash_64(key: u64, seed: u64, range_end: u64, hash: u64) {
+    assert_eq!(v1::flip_hash_64_with_seed(key, seed, ..=range_end), hash);
+}
+
+#[test_case(0, 0, 17, 0)]
+#[test_case(15960427081186311679, 0, 17, 17)]
+#[test_case(15960427081186311679, 1, 17, 8)]
+#[test_case(15960427081186311679, 2, 17, 7)]
+#[test_case(10427592028180905159, 3, 1000, 757)]
+#[test_case(42, 4294967295, 1048576, 549901)]
+#[test_case(18446744073709551615, 7, 18446744073709551615, 2764804289153113437)]
+fn flip_hash_64_with_replica(key: u64, replica_index: u32, range_end: u64, hash: u64) {
+    assert_eq!(
+        v1::flip_hash_64_with_replica(key, replica_index, ..=range_end),
+        hash
+    );
+}
+
+#[test_case(0, 0)]
+#[test_case(1, 12994781566227106604)]
+#[test_case(2, 4233148493373801447)]
+#[test_case(4294967295, 14731816277868330182)]
+fn replica_seed(replica_index: u32, seed: u64) {
+    assert_eq!(v1::replica_seed(replica_index), seed);
+}
+
+#[cfg(feature = "xxh3")]
+#[test_case(b"", 0, 17, 3)]
+#[test_case(b"a", 1, 1000, 569)]
+#[test_case(b"flip hash", 42, 18446744073709551615, 10592897119071777987)]
+#[test_case(b"0123456789abcdef", 18446744073709551615, 4294967296, 2382940900)]
+#[test_case(b"the quick brown fox jumps over the lazy dog", 7, 12, 0)]
+#[test_case(&long_key(), 0, 17, 0)]
+fn flip_hash_xxh3_64(key: &[u8], seed: u64, range_end: u64, hash: u64) {
+    assert_eq!(
+        v1::flip_hash_xxh3_64_with_seed(key, seed, ..=range_end),
+        hash
+    );
+    assert_eq!(
+        v1::flip_hash_const_xxh3_64_with_seed(key, seed, ..=range_end),
+        hash
+    );
+}
+
+// NFC and NFD forms of the same string are hashed to different values.
+#[cfg(feature = "xxh3")]
+#[test_case("", 0, 17, 3)]
+#[test_case("café", 1, 1000, 710 ; "nfc")]
+#[test_case("cafe\u{301}", 1, 1000, 980 ; "nfd")]
+#[test_case("Ζεύς", 42, 18446744073709551615, 11392470189117097188 ; "greek")]
+#[test_case("日本語のキー", 7, 4294967296, 3174146389 ; "japanese")]
+#[test_case("🦀", 18446744073709551615, 12, 3 ; "emoji")]
+fn flip_hash_str(key: &str, seed: u64, range_end: u64, hash: u64) {
+    assert_eq!(v1::flip_hash_str_with_seed(key, seed, ..=range_end), hash);
+}
+
+#[cfg(feature = "xxh3")]
+#[test_case(b"", 0, 17, 9)]
+#[test_case(b"a", 1, 1000, 569)]
+#[test_case(
+    b"flip hash",
+    42,
+    340282366920938463463374607431768211455,
+    140566099310334973002148666559493830111
+)]
+#[test_case(
+    b"0123456789abcdef",
+    18446744073709551615,
+    18446744073709551616,
+    4953307530249260542
+)]
+#[test_case(
+    b"the quick brown fox jumps over the lazy dog",
+    7,
+    1267650600228229401496703205388,
+    327127227149693932637956564886
+)]
+#[test_case(&long_key(), 0, 17, 0)]
+fn flip_hash_xxh3_128(key: &[u8], seed: u64, range_end: u128, hash: u128) {
+    assert_eq!(
+        v1::flip_hash_xxh3_128_with_seed(key, seed, ..=range_end),
+        hash
+    );
+    assert_eq!(
+        v1::flip_hash_const_xxh3_128_with_seed(key, seed, ..=range_end),
+        hash
+    );
+}
+
+#[cfg(feature = "aes")]
+#[test_case(b"", 0, 17, 14)]
+#[test_case(b"a", 1, 1000, 315)]
+#[test_case(b"flip hash", 42, 18446744073709551615, 14155385725055599346)]
+#[test_case(b"0123456789abcdef", 18446744073709551615, 4294967296, 1724104023)]
+#[test_case(b"the quick brown fox jumps over the lazy dog", 7, 12, 3)]
+#[test_case(&long_key(), 0, 17, 8)]
+fn flip_hash_aes_64(key: &[u8], seed: u64, range_end: u64, hash: u64) {
+    assert_eq!(
+        v1::flip_hash_aes_64_with_seed(key, seed, ..=range_end),
+        hash
+    );
+}
+
+#[cfg(feature = "murmur2")]
+#[test_case(b"", 0, 17, 0)]
+#[test_case(b"a", 1, 1000, 309)]
+#[test_case(b"flip hash", 42, 18446744073709551615, 6805075313396413093)]
+#[test_case(b"0123456789abcdef", 18446744073709551615, 4294967296, 3730975208)]
+#[test_case(b"the quick brown fox jumps over the lazy dog", 7, 12, 1)]
+#[test_case(&long_key(), 0, 17, 9)]
+fn flip_hash_murmur2_64(key: &[u8], seed: u64, range_end: u64, hash: u64) {
+    assert_eq!(
+        v1::flip_hash_murmur2_64_with_seed(key, seed, ..=range_end),
+        hash
+    );
+}
+
+#[cfg(feature = "keyed")]
+const SECRET: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+];
+
+#[cfg(feature = "keyed")]
+#[test_case(b"", [0; 16], 17, 6)]
+#[test_case(b"a", SECRET, 1000, 848)]
+#[test_case(b"flip hash", [0xFF; 16], 18446744073709551615, 1592525807951942417)]
+#[test_case(b"0123456789abcdef", SECRET, 4294967296, 148554337)]
+#[test_case(b"the quick brown fox jumps over the lazy dog", [0xFF; 16], 12, 2)]
+#[test_case(&long_key(), SECRET, 17, 11)]
+fn flip_hash_keyed_64(key: &[u8], secret: [u8; 16], range_end: u64, hash: u64) {
+    assert_eq!(v1::flip_hash_keyed_64(key, &secret, ..=range_end), hash);
+}
+
+// The UUIDs are parsed from their textual forms, so that the vectors would
+// catch hashing their bytes in another order.
+#[cfg(feature = "uuid")]
+#[test_case("00000000-0000-0000-0000-000000000000", 0, 17, 13)]
+#[test_case("67e55044-10b1-426f-9247-bb680e5fe0c8", 0, 17, 3)]
+#[test_case("67e55044-10b1-426f-9247-bb680e5fe0c8", 1, 1000, 289)]
+#[test_case(
+    "ffffffff-ffff-ffff-ffff-ffffffffffff",
+    42,
+    18446744073709551615,
+    13555379374292366963
+)]
+#[test_case("01890a5d-ac96-774b-bcce-b302099a8057", 7, 4294967296, 2987621919)]
+#[test_case("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8", 18446744073709551615, 12, 8)]
+fn flip_hash_uuid(key: &str, seed: u64, range_end: u64, hash: u64) {
+    let key = uuid::Uuid::parse_str(key).unwrap();
+    assert_eq!(v1::flip_hash_uuid_with_seed(key, seed, ..=range_end), hash);
+}